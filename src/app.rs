@@ -1,46 +1,227 @@
-use iced::widget::container;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use iced::widget::{container, text_editor};
 use iced::window;
 use iced::{Element, Fill, Subscription, Task, Theme};
 use tokio::sync::mpsc;
 
-use crate::components::{home_view, settings_dialog, title_bar};
-use crate::config;
-use crate::download::DownloadTask;
-use crate::message::{DownloadProgress, Format, Message, SettingsField};
+use crate::components::{close_dialog, home_view, settings_dialog, HomeState, SettingsDialogState, subscriptions_dialog, title_bar};
+use crate::config::{self, WindowGeometry};
+use crate::disk::SpaceCheck;
+use crate::download::bandwidth::{self, needs_restart};
+use crate::download::archive;
+use crate::download::template;
+use crate::download::queue::{ActiveRun, Download, DownloadId, DownloadQueue, DownloadState, RunId};
+use crate::download::schedule::{self, StartCondition};
+use crate::download::space;
+use crate::download::subscriptions::{self, ChannelSubscription};
+use crate::download::{task, DownloadTask};
+use crate::message::{Format, Message, PathField, SettingsField, StartMode};
 use crate::settings::AdvancedSettings;
-use crate::theme::{custom_theme, window_container_style};
-use crate::widgets::modal;
+use crate::theme::{window_container_style, ThemeChoice, ThemeSpec, UserThemes};
+use crate::tools::capabilities::{self, Capabilities};
+use crate::tools::ffmpeg::{self, FfmpegInfo};
+use crate::tools::ytdlp;
+use crate::tray::{self, CloseAction, TrayAction, TrayEvent, TrayHandle};
+use crate::notify::{self, Notification, NotificationAction, Notifier, NotifyEvent, NullNotifier};
+use crate::opener::{Opener, SystemOpener};
+use crate::paths;
+use crate::picker::{self, PickKind};
+use crate::units;
+use crate::urls::{self, UrlKind};
+use crate::widgets::{modal, resize_edges};
 
 pub struct App {
     url: String,
     format: Format,
-    download_state: DownloadState,
+    start_mode: StartMode,
+    start_time: String,
+    /// Why the last Download press could not schedule the download.
+    start_error: Option<String>,
+    downloads: DownloadQueue,
     settings: AdvancedSettings,
     pending_settings: Option<AdvancedSettings>,
     show_settings: bool,
     persist_settings: bool,
-    cancel_sender: Option<mpsc::Sender<()>>,
+    archive_viewer: ArchiveViewer,
+    subscriptions: Vec<ChannelSubscription>,
+    subscription_form: SubscriptionForm,
+    show_subscriptions: bool,
+    ytdlp_status: YtDlpStatus,
+    ffmpeg: FfmpegInfo,
+    preflight_warning: Option<String>,
+    /// The warning can't be overridden with Download Anyway.
+    preflight_blocked: bool,
+    /// URL whose free space check is running.
+    checking_space: Option<String>,
+    /// Downloads waiting on the checks; the first is being checked or
+    /// awaits confirmation.
+    pending_starts: VecDeque<PendingStart>,
+    clipboard: ClipboardWatch,
+    batch: Option<BatchInput>,
+    drop_hover: bool,
+    /// Result of the last file drop, or an error with no download card to
+    /// show it on.
+    notice: Option<String>,
+    capabilities: Option<Capabilities>,
+    user_themes: UserThemes,
+    system_theme: iced::theme::Mode,
+    window: WindowTracker,
+    show_close_guard: bool,
+    /// Runs stopped on the way out; the window closes once all have ended.
+    closing: Option<HashSet<RunId>>,
+    /// Why deferred downloads could not be saved the last time, shown in
+    /// the close dialog.
+    schedule_error: Option<String>,
+    quit_when_idle: bool,
+    tray: Option<TrayHandle>,
+    tray_error: Option<String>,
+    /// Why the last notification could not be shown.
+    notify_error: Option<String>,
+    window_hidden: bool,
+    window_focused: bool,
+    notifier: Box<dyn Notifier>,
+    opener: Arc<dyn Opener>,
+    /// Completed download whose file is about to be deleted, awaiting confirmation.
+    pending_delete: Option<DownloadId>,
+    picker_error: Option<String>,
+    /// Failed checks of the path settings being edited, by field.
+    path_errors: HashMap<PathField, String>,
+    template_editor: TemplateEditor,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct YtDlpStatus {
+    pub version: Option<String>,
+    pub busy: bool,
+    pub last_result: Option<Result<String, String>>,
+    pub install_file: String,
+    /// Install a local file even without a checksum file next to it.
+    pub allow_unverified: bool,
+    /// A copy to roll back to exists.
+    pub has_previous: bool,
+}
+
+/// Downloads held back by the checks every download goes through before it
+/// is added: the preflight warning, then the free space check.
+#[derive(Debug, Clone)]
+struct PendingStart {
+    urls: Vec<String>,
+    format: Format,
+    origin: StartOrigin,
+    /// The preflight check passed or was overridden with Download Anyway.
+    preflight_passed: bool,
+}
+
+impl PendingStart {
+    fn new(urls: Vec<String>, format: Format, origin: StartOrigin) -> Self {
+        Self {
+            urls,
+            format,
+            origin,
+            preflight_passed: false,
+        }
+    }
+}
+
+/// Where a [`PendingStart`] came from, which decides how it is added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartOrigin {
+    /// The URL field, following the start time picker.
+    Field,
+    /// The batch editor, which is cleared once its URLs are added.
+    Batch,
+    Clipboard,
+    /// A dropped file with several links.
+    Drop,
+    /// New uploads found by a subscription.
+    Subscription(u64),
+}
+
+impl StartOrigin {
+    /// Started with the Download button, so the format picker applies.
+    fn is_input(self) -> bool {
+        matches!(self, StartOrigin::Field | StartOrigin::Batch)
+    }
 }
 
+/// Follows the window's geometry so it can be restored next launch.
 #[derive(Debug, Clone, Default)]
-pub enum DownloadState {
-    #[default]
-    Idle,
-    Downloading {
-        progress: f32,
-        speed: String,
-        eta: String,
-        filename: String,
-    },
-    PostProcessing {
-        status: String,
-    },
-    Completed {
-        output_path: String,
-    },
-    Error {
-        message: String,
-    },
+struct WindowTracker {
+    /// Geometry to save; only updated while the window is not maximized so
+    /// restoring brings back the size it had before.
+    saved: WindowGeometry,
+    /// Latest size and position reported, committed to `saved` once it is
+    /// known whether the window is maximized.
+    size: Option<iced::Size>,
+    position: Option<iced::Point>,
+}
+
+/// The multi-line URL editor shown in batch mode.
+#[derive(Debug, Default)]
+pub struct BatchInput {
+    pub content: text_editor::Content,
+    pub import_file: String,
+    pub error: Option<String>,
+}
+
+/// Tracks the clipboard while watching for copied URLs.
+#[derive(Debug, Clone, Default)]
+pub struct ClipboardWatch {
+    /// Clipboard text at the last poll, to notice when it changes.
+    last: Option<String>,
+    /// Whether `last` holds a real reading. Whatever was on the clipboard
+    /// before watching started is not offered.
+    primed: bool,
+    /// URL currently offered in the banner.
+    pub offer: Option<String>,
+    /// URLs offered before, so a dismissed one is not offered again.
+    offered: HashSet<String>,
+}
+
+/// State of the download archive viewer in the settings dialog.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveViewer {
+    /// Which per-format archive is shown when archives are kept per format.
+    pub format: Format,
+    pub entries: Vec<String>,
+    pub search: String,
+    pub import_file: String,
+    pub last_result: Option<Result<String, String>>,
+}
+
+/// State of the filename template editor's preview.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateEditor {
+    pub probe_url: String,
+    /// Metadata of the probed video; the preview uses sample data without it.
+    pub metadata: Option<HashMap<String, String>>,
+    pub probing: bool,
+    pub probe_error: Option<String>,
+}
+
+/// The "add subscription" form in the subscriptions dialog.
+#[derive(Debug, Clone)]
+pub struct SubscriptionForm {
+    pub url: String,
+    pub format: Format,
+    pub interval: String,
+    pub error: Option<String>,
+}
+
+impl Default for SubscriptionForm {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            format: Format::Default,
+            interval: "24".to_string(),
+            error: None,
+        }
+    }
 }
 
 impl App {
@@ -48,35 +229,183 @@ impl App {
         let (settings, persist) = config::load_settings()
             .map(|s| (s, true))
             .unwrap_or_else(|| (AdvancedSettings::default(), false));
+        let ffmpeg_location = settings.ffmpeg_location.clone();
+        let ytdlp_path = ytdlp::resolve_path(&settings);
+
+        let mut downloads = DownloadQueue::default();
+        for scheduled in schedule::load() {
+            downloads.restore(scheduled);
+        }
 
         (
-            Self {
-                url: String::new(),
-                format: Format::Default,
-                download_state: DownloadState::Idle,
-                settings,
-                pending_settings: None,
-                show_settings: false,
-                persist_settings: persist,
-                cancel_sender: None,
-            },
-            Task::none(),
+            Self::with_state(settings, persist, downloads, subscriptions::load()),
+            Task::batch([detect_ffmpeg(&ffmpeg_location), probe_capabilities(ytdlp_path), iced::system::theme().map(Message::SystemThemeChanged), Task::perform(
+                async {
+                    tokio::task::spawn_blocking(|| {
+                        ytdlp::managed_path()
+                            .filter(|p| p.exists())
+                            .and_then(|p| ytdlp::version(&p))
+                    })
+                    .await
+                    .ok()
+                    .flatten()
+                },
+                Message::YtDlpVersionLoaded,
+            )]),
         )
     }
 
+    /// The app around already loaded state, before any background work.
+    fn with_state(
+        settings: AdvancedSettings,
+        persist_settings: bool,
+        downloads: DownloadQueue,
+        subscriptions: Vec<ChannelSubscription>,
+    ) -> Self {
+        Self {
+            url: String::new(),
+            format: Format::Default,
+            start_mode: StartMode::Now,
+            start_time: String::new(),
+            start_error: None,
+            downloads,
+            settings,
+            pending_settings: None,
+            show_settings: false,
+            persist_settings,
+            archive_viewer: ArchiveViewer::default(),
+            subscriptions,
+            subscription_form: SubscriptionForm::default(),
+            show_subscriptions: false,
+            ytdlp_status: YtDlpStatus::default(),
+            ffmpeg: FfmpegInfo::default(),
+            preflight_warning: None,
+            preflight_blocked: false,
+            checking_space: None,
+            pending_starts: VecDeque::new(),
+            clipboard: ClipboardWatch::default(),
+            batch: None,
+            drop_hover: false,
+            notice: None,
+            capabilities: None,
+            user_themes: load_user_themes(),
+            system_theme: iced::theme::Mode::None,
+            window: WindowTracker {
+                saved: config::load_window_geometry(),
+                ..Default::default()
+            },
+            show_close_guard: false,
+            closing: None,
+            schedule_error: None,
+            quit_when_idle: false,
+            tray: None,
+            tray_error: None,
+            notify_error: None,
+            window_hidden: false,
+            window_focused: true,
+            notifier: Box::new(NullNotifier),
+            opener: Arc::new(SystemOpener),
+            pending_delete: None,
+            picker_error: None,
+            path_errors: HashMap::new(),
+            template_editor: TemplateEditor::default(),
+        }
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
-        Subscription::none()
+        let scheduled_bandwidth = !self.settings.bandwidth_schedule.trim().is_empty();
+        let bandwidth = if scheduled_bandwidth && self.downloads.running_count() > 0 {
+            iced::time::every(Duration::from_secs(30)).map(|_| Message::BandwidthTick)
+        } else {
+            Subscription::none()
+        };
+
+        let scheduled = if self.downloads.has_scheduled() {
+            iced::time::every(Duration::from_secs(15)).map(|_| Message::ScheduleTick)
+        } else {
+            Subscription::none()
+        };
+
+        let channels = if self.subscriptions.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_secs(60)).map(|_| Message::SubscriptionTick)
+        };
+
+        let clipboard = if self.settings.watch_clipboard {
+            iced::time::every(Duration::from_secs(1)).map(|_| Message::ClipboardPoll)
+        } else {
+            Subscription::none()
+        };
+
+        // winit only reports dropped files; links dragged from a browser
+        // arrive as text and never reach the window.
+        let window_events = iced::event::listen_with(|event, _status, _window| match event {
+            iced::Event::Window(window::Event::FileHovered(_)) => Some(Message::FileHovered),
+            iced::Event::Window(window::Event::FilesHoveredLeft) => Some(Message::FilesHoveredLeft),
+            iced::Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            iced::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+            iced::Event::Window(window::Event::Moved(position)) => Some(Message::WindowMoved(position)),
+            iced::Event::Window(window::Event::CloseRequested) => Some(Message::WindowClose),
+            iced::Event::Window(window::Event::Focused) => Some(Message::WindowFocused(true)),
+            iced::Event::Window(window::Event::Unfocused) => Some(Message::WindowFocused(false)),
+            _ => None,
+        });
+
+        // Only watched while following the desktop, so the dark/light
+        // switch applies without a restart.
+        let system_theme = if self.active_settings().theme == ThemeChoice::System {
+            iced::system::theme_changes().map(Message::SystemThemeChanged)
+        } else {
+            Subscription::none()
+        };
+
+        let tray = if self.settings.tray_icon {
+            Subscription::run(tray::run).map(Message::Tray)
+        } else {
+            Subscription::none()
+        };
+
+        let tray_tooltip = if self.tray_available() {
+            iced::time::every(Duration::from_secs(2)).map(|_| Message::TrayTick)
+        } else {
+            Subscription::none()
+        };
+
+        let notifications = if self.settings.notify_on_success || self.settings.notify_on_failure {
+            Subscription::run(notify::run).map(Message::Notify)
+        } else {
+            Subscription::none()
+        };
+
+        Subscription::batch([
+            notifications,
+            bandwidth,
+            scheduled,
+            channels,
+            clipboard,
+            window_events,
+            system_theme,
+            tray,
+            tray_tooltip,
+        ])
     }
 
+    pub fn theme(state: &Self) -> Theme {
+        state.theme_spec().apply()
+    }
 
-    pub fn theme(_state: &Self) -> Theme {
-        custom_theme()
+    /// The theme being shown. The pending settings are used so a theme can
+    /// be previewed before saving.
+    fn theme_spec(&self) -> ThemeSpec {
+        self.user_themes.resolve(&self.active_settings().theme, self.system_theme)
     }
 
     pub fn update(&mut self, message: Message) -> Task<Message> {
         match message {
             Message::UrlChanged(url) => {
                 self.url = url;
+                self.notice = None;
                 Task::none()
             }
             Message::UrlSubmit => {
@@ -88,122 +417,517 @@ impl App {
             }
             Message::FormatSelected(format) => {
                 self.format = format;
+                // A warning or running size check was for the previous format
+                if self.pending_starts.front().is_some_and(|p| p.origin.is_input()) {
+                    self.pending_starts.pop_front();
+                    self.preflight_warning = None;
+                    self.checking_space = None;
+                    return self.check_next_start();
+                }
+                self.pending_starts.retain(|p| !p.origin.is_input());
+                Task::none()
+            }
+            Message::StartModeSelected(mode) => {
+                self.start_mode = mode;
+                self.start_error = None;
+                Task::none()
+            }
+            Message::StartTimeChanged(time) => {
+                self.start_time = time;
+                self.start_error = None;
                 Task::none()
             }
             Message::StartDownload => {
-                if self.url.trim().is_empty() {
+                let urls = self.pending_urls();
+                if urls.is_empty() || self.pending_starts.iter().any(|p| p.origin.is_input()) {
                     return Task::none();
                 }
 
-                let url = self.url.clone();
-                let format = self.format;
-                let settings = self.settings.clone();
-
-                let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
-                self.cancel_sender = Some(cancel_tx);
-
-                self.download_state = DownloadState::Downloading {
-                    progress: 0.0,
-                    speed: "Starting...".to_string(),
-                    eta: "N/A".to_string(),
-                    filename: String::new(),
-                };
-
-                Task::stream(async_stream::stream! {
-                    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
-                    let task = DownloadTask::new(url, format, settings);
-
-                    let download_handle = tokio::spawn(async move { task.run(progress_tx).await });
-
-                    loop {
-                        tokio::select! {
-                            _ = cancel_rx.recv() => {
-                                download_handle.abort();
-                                yield Message::DownloadComplete(Err("Cancelled".to_string()));
-                                break;
-                            }
-                            progress = progress_rx.recv() => {
-                                match progress {
-                                    Some(p) => yield Message::DownloadProgress(p),
-                                    None => break,
-                                }
-                            }
-                        }
+                if let Err(e) = self.start_condition() {
+                    self.start_error = Some(e);
+                    return Task::none();
+                }
+                let origin = if self.batch.is_some() { StartOrigin::Batch } else { StartOrigin::Field };
+                self.request_start(PendingStart::new(urls, self.format, origin))
+            }
+            Message::DiskSpaceChecked(url, warning) => {
+                if self.checking_space.as_ref() != Some(&url) {
+                    return Task::none();
+                }
+                self.checking_space = None;
+                match warning {
+                    Some(warning) => {
+                        self.preflight_blocked = self.settings.disk_space_check == SpaceCheck::Block;
+                        self.preflight_warning = Some(warning);
+                        Task::none()
                     }
-
-                    match download_handle.await {
-                        Ok(Ok(path)) => yield Message::DownloadComplete(Ok(path)),
-                        Ok(Err(e)) => yield Message::DownloadComplete(Err(e)),
-                        Err(e) if e.is_cancelled() => {},
-                        Err(e) => yield Message::DownloadComplete(Err(format!("Task error: {}", e))),
+                    None => self.enqueue(),
+                }
+            }
+            Message::ConfirmDownload => {
+                self.preflight_warning = None;
+                match self.pending_starts.front_mut() {
+                    // Overriding the preflight warning still checks the space
+                    Some(pending) if !pending.preflight_passed => {
+                        pending.preflight_passed = true;
+                        self.check_space()
                     }
-                })
+                    Some(_) => self.enqueue(),
+                    None => Task::none(),
+                }
             }
-            Message::CancelDownload => {
-                if let Some(sender) = self.cancel_sender.take() {
-                    let _ = sender.try_send(());
-                }
-                Task::none()
-            }
-            Message::DownloadProgress(progress) => {
-                match progress {
-                    DownloadProgress::Downloading {
-                        progress: pct,
-                        speed,
-                        eta,
-                        filename,
-                    } => {
-                        self.download_state = DownloadState::Downloading {
-                            progress: pct,
-                            speed,
-                            eta,
-                            filename,
-                        };
+            Message::DismissPreflight => {
+                self.preflight_warning = None;
+                self.pending_starts.pop_front();
+                self.check_next_start()
+            }
+            Message::CancelDownload(id) => {
+                if let Some(mut download) = self.downloads.remove(id) {
+                    download.stop();
+                    if matches!(download.state, DownloadState::Scheduled { .. }) {
+                        self.save_schedule();
                     }
-                    DownloadProgress::PostProcessing { status } => {
-                        self.download_state = DownloadState::PostProcessing { status };
+                }
+                self.download_ended()
+            }
+            Message::DownloadProgress(run, progress) => {
+                if let Some(download) = self.downloads.by_run_mut(run) {
+                    download.apply(progress);
+                }
+                Task::none()
+            }
+            Message::DownloadComplete(run, result) => {
+                if let Some(ref mut stopped) = self.closing {
+                    stopped.remove(&run);
+                    return if stopped.is_empty() { self.quit() } else { Task::none() };
+                }
+                let mut notification = None;
+                if let Some(download) = self.downloads.by_run_mut(run) {
+                    download.run = None;
+                    match result {
+                        // A skipped video finishes successfully without a file
+                        Ok(_) if matches!(download.state, DownloadState::Skipped { .. }) => {}
+                        Ok(path) => download.state = DownloadState::Completed { output_path: path },
+                        Err(message) => download.state = DownloadState::Error { message },
                     }
-                    DownloadProgress::Error(msg) => {
-                        self.download_state = DownloadState::Error { message: msg };
+                    notification = completion_notification(download, &self.settings);
+                }
+                if let Some(notification) = notification
+                    && (self.window_hidden
+                        || !self.window_focused
+                        || !self.settings.notify_in_background_only)
+                {
+                    self.notifier.notify(notification);
+                }
+                self.download_ended()
+            }
+            Message::BandwidthTick => self.rebalance(),
+            Message::OpenDownload(id) => match self.output_path(id) {
+                Some(path) => self.open_file(Some(id), path, false),
+                None => Task::none(),
+            },
+            Message::RevealDownload(id) => match self.output_path(id) {
+                Some(path) => self.open_file(Some(id), path, true),
+                None => Task::none(),
+            },
+            Message::CopyDownloadPath(id) => match self.output_path(id) {
+                Some(path) => iced::clipboard::write(path.to_string_lossy().to_string()),
+                None => Task::none(),
+            },
+            Message::OpenerFinished(id, result) => {
+                if let Err(e) = result {
+                    match id.and_then(|id| self.downloads.get_mut(id)) {
+                        Some(download) => download.notice = Some(e),
+                        None => self.notice = Some(e),
                     }
                 }
                 Task::none()
             }
-            Message::DownloadComplete(result) => {
-                self.cancel_sender = None;
-                match result {
-                    Ok(path) => {
-                        self.download_state = DownloadState::Completed { output_path: path };
+            Message::DeleteDownload(id) => {
+                self.pending_delete = Some(id);
+                Task::none()
+            }
+            Message::CancelDeleteDownload => {
+                self.pending_delete = None;
+                Task::none()
+            }
+            Message::ConfirmDeleteDownload(id) => {
+                self.pending_delete = None;
+                let Some(path) = self.output_path(id) else {
+                    return Task::none();
+                };
+                match fs::remove_file(&path) {
+                    Ok(()) => {
+                        self.downloads.remove(id);
                     }
-                    Err(msg) => {
-                        if msg != "Cancelled" {
-                            self.download_state = DownloadState::Error { message: msg };
+                    // Not removed from the list: the file may have been
+                    // moved, and the entry is the only pointer to it
+                    Err(e) => {
+                        let message = if e.kind() == std::io::ErrorKind::NotFound {
+                            format!("{} no longer exists; nothing was deleted", path.display())
                         } else {
-                            self.download_state = DownloadState::Idle;
+                            format!("Failed to delete {}: {}", path.display(), e)
+                        };
+                        if let Some(download) = self.downloads.get_mut(id) {
+                            download.notice = Some(message);
+                        }
+                    }
+                }
+                Task::none()
+            }
+            Message::ScheduleInputChanged(id, value) => {
+                if let Some(download) = self.downloads.get_mut(id)
+                    && let DownloadState::Scheduled { ref mut input, .. } = download.state
+                {
+                    *input = value;
+                }
+                Task::none()
+            }
+            Message::RescheduleDownload(id) => {
+                if let Some(download) = self.downloads.get_mut(id)
+                    && let DownloadState::Scheduled {
+                        ref mut condition,
+                        ref input,
+                    } = download.state
+                {
+                    match StartCondition::parse(input, chrono::Local::now()) {
+                        Ok(parsed) => {
+                            *condition = parsed;
+                            download.notice = None;
+                        }
+                        Err(e) => download.notice = Some(e),
+                    }
+                }
+                self.save_schedule();
+                self.start_queued()
+            }
+            Message::StartDownloadNow(id) => {
+                if let Some(download) = self.downloads.get_mut(id)
+                    && matches!(download.state, DownloadState::Scheduled { .. })
+                {
+                    download.state = DownloadState::Queued;
+                    download.notice = None;
+                }
+                self.save_schedule();
+                self.start_queued()
+            }
+            Message::ScheduleTick => self.start_queued(),
+            Message::ClipboardPoll => iced::clipboard::read().map(Message::ClipboardRead),
+            Message::ClipboardRead(contents) => {
+                if !self.settings.watch_clipboard || contents == self.clipboard.last {
+                    return Task::none();
+                }
+                let primed = std::mem::replace(&mut self.clipboard.primed, true);
+                self.clipboard.last = contents;
+                if !primed {
+                    return Task::none();
+                }
+
+                if let Some(url) = self.clipboard.last.as_deref().and_then(urls::detect)
+                    && url != urls::normalize(&self.url)
+                    && !self.downloads.contains_url(&url)
+                    && self.clipboard.offered.insert(url.clone())
+                {
+                    self.clipboard.offer = Some(url);
+                }
+                Task::none()
+            }
+            Message::AcceptClipboardUrl => {
+                let Some(url) = self.clipboard.offer.take() else {
+                    return Task::none();
+                };
+                self.request_start(PendingStart::new(vec![url], self.format, StartOrigin::Clipboard))
+            }
+            Message::DismissClipboardUrl => {
+                self.clipboard.offer = None;
+                Task::none()
+            }
+            Message::ToggleBatchMode => {
+                self.batch = match self.batch {
+                    Some(_) => None,
+                    None => Some(BatchInput {
+                        content: text_editor::Content::with_text(self.url.trim()),
+                        ..BatchInput::default()
+                    }),
+                };
+                Task::none()
+            }
+            Message::BatchEdited(action) => {
+                if let Some(ref mut batch) = self.batch {
+                    batch.content.perform(action);
+                }
+                Task::none()
+            }
+            Message::BatchImportFileChanged(path) => {
+                if let Some(ref mut batch) = self.batch {
+                    batch.import_file = path;
+                }
+                Task::none()
+            }
+            Message::ImportBatchFile => {
+                if let Some(ref mut batch) = self.batch {
+                    let path = std::path::PathBuf::from(batch.import_file.trim());
+                    match std::fs::read_to_string(&path) {
+                        Ok(imported) => {
+                            let mut text = batch.content.text();
+                            if !text.trim().is_empty() && !text.ends_with('\n') {
+                                text.push('\n');
+                            }
+                            text.push_str(&imported);
+                            batch.content = text_editor::Content::with_text(text.trim_end());
+                            batch.error = None;
                         }
+                        Err(e) => batch.error = Some(format!("Failed to read {}: {}", path.display(), e)),
+                    }
+                }
+                Task::none()
+            }
+            Message::FileHovered => {
+                self.drop_hover = true;
+                self.notice = None;
+                Task::none()
+            }
+            Message::FilesHoveredLeft => {
+                self.drop_hover = false;
+                Task::none()
+            }
+            Message::FileDropped(path) => {
+                self.drop_hover = false;
+                match urls::from_file(&path) {
+                    // A single link goes into the URL field so format and
+                    // start time can still be picked before downloading
+                    Ok(found) if found.len() == 1 => {
+                        self.url = found.into_iter().next().unwrap_or_default();
+                        self.notice = None;
+                        Task::none()
+                    }
+                    Ok(found) => {
+                        self.notice = None;
+                        self.request_start(PendingStart::new(found, self.format, StartOrigin::Drop))
                     }
+                    Err(e) => {
+                        self.notice = Some(e);
+                        Task::none()
+                    }
+                }
+            }
+            Message::OpenSubscriptions => {
+                self.show_subscriptions = true;
+                Task::none()
+            }
+            Message::CloseSubscriptions => {
+                self.close_subscriptions();
+                Task::none()
+            }
+            Message::SubscriptionUrlChanged(url) => {
+                self.subscription_form.url = url;
+                Task::none()
+            }
+            Message::SubscriptionFormatSelected(format) => {
+                self.subscription_form.format = format;
+                Task::none()
+            }
+            Message::SubscriptionIntervalChanged(interval) => {
+                self.subscription_form.interval = interval;
+                Task::none()
+            }
+            Message::AddSubscription => {
+                let form = &mut self.subscription_form;
+                let url = form.url.trim().to_string();
+                if url.is_empty() {
+                    return Task::none();
+                }
+                let Some(interval) = form.interval.trim().parse::<u32>().ok().filter(|h| *h > 0) else {
+                    form.error = Some("Check interval must be a whole number of hours".to_string());
+                    return Task::none();
+                };
+                if self.subscriptions.iter().any(|s| s.url == url) {
+                    form.error = Some("Already subscribed".to_string());
+                    return Task::none();
                 }
+
+                let id = self.subscriptions.iter().map(|s| s.id).max().unwrap_or(0) + 1;
+                self.subscriptions.push(ChannelSubscription {
+                    id,
+                    url,
+                    format: form.format,
+                    check_interval_hours: interval,
+                    last_checked: None,
+                    seeded: false,
+                    new_uploads: 0,
+                    checking: false,
+                    last_error: None,
+                });
+                self.subscription_form = SubscriptionForm::default();
+                self.save_subscriptions();
+                self.check_subscription(id)
+            }
+            Message::RemoveSubscription(id) => {
+                self.subscriptions.retain(|s| s.id != id);
+                self.save_subscriptions();
                 Task::none()
             }
+            Message::CheckSubscription(id) => self.check_subscription(id),
+            Message::SubscriptionChecked(id, result) => {
+                let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.id == id) else {
+                    return Task::none();
+                };
+                subscription.checking = false;
+                subscription.last_checked = Some(chrono::Local::now());
+
+                let mut found = Vec::new();
+                match result {
+                    Ok(uploads) => {
+                        subscription.seeded = true;
+                        subscription.last_error = None;
+                        found = uploads.into_iter().map(|upload| upload.url).collect();
+                    }
+                    Err(e) => subscription.last_error = Some(e),
+                }
+                let format = subscription.format;
+
+                self.save_subscriptions();
+                self.request_start(PendingStart::new(found, format, StartOrigin::Subscription(id)))
+            }
+            Message::SubscriptionTick => {
+                let now = chrono::Local::now();
+                let due: Vec<u64> = self
+                    .subscriptions
+                    .iter()
+                    .filter(|s| s.is_due(now))
+                    .map(|s| s.id)
+                    .collect();
+                Task::batch(due.into_iter().map(|id| self.check_subscription(id)))
+            }
             Message::OpenSettings => {
                 self.pending_settings = Some(self.settings.clone());
                 self.show_settings = true;
+                self.user_themes = load_user_themes();
+                self.picker_error = None;
+                self.archive_viewer.last_result = None;
+                self.ytdlp_status.has_previous = ytdlp::has_previous();
+                self.check_paths();
+                self.reload_archive();
+                Task::none()
+            }
+            Message::InsertTemplateField(field) => {
+                if let Some(ref mut settings) = self.pending_settings {
+                    settings.filename_template = template::insert_field(&settings.filename_template, &field);
+                }
+                Task::none()
+            }
+            Message::InsertTemplateFolder(field) => {
+                if let Some(ref mut settings) = self.pending_settings {
+                    settings.filename_template = template::insert_folder(&settings.filename_template, &field);
+                }
+                Task::none()
+            }
+            Message::TemplatePresetSelected(preset) => {
+                if let Some(ref mut settings) = self.pending_settings {
+                    settings.filename_template = preset.template.to_string();
+                }
+                Task::none()
+            }
+            Message::TemplateProbeUrlChanged(url) => {
+                self.template_editor.probe_url = url;
+                Task::none()
+            }
+            Message::ProbeTemplateMetadata => {
+                let url = self.template_editor.probe_url.trim().to_string();
+                if url.is_empty() || self.template_editor.probing {
+                    return Task::none();
+                }
+                self.template_editor.probing = true;
+                self.template_editor.probe_error = None;
+
+                let ytdlp_path = ytdlp::resolve_path(self.active_settings());
+                let access = task::access_args(self.active_settings(), self.capabilities.as_ref());
+                Task::perform(
+                    async move {
+                        tokio::task::spawn_blocking(move || template::probe(&ytdlp_path, &access, &url))
+                            .await
+                            .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
+                    },
+                    Message::TemplateMetadataProbed,
+                )
+            }
+            Message::TemplateMetadataProbed(result) => {
+                self.template_editor.probing = false;
+                match result {
+                    Ok(metadata) => self.template_editor.metadata = Some(metadata),
+                    Err(e) => self.template_editor.probe_error = Some(e),
+                }
+                Task::none()
+            }
+            Message::ArchiveFormatSelected(format) => {
+                self.archive_viewer.format = format;
+                self.reload_archive();
+                Task::none()
+            }
+            Message::ArchiveSearchChanged(search) => {
+                self.archive_viewer.search = search;
+                Task::none()
+            }
+            Message::RemoveArchiveEntry(entry) => {
+                if let Some(path) = self.archive_path() {
+                    self.archive_viewer.last_result =
+                        Some(archive::remove(&path, &entry).map(|_| format!("Removed {}", entry)));
+                }
+                self.reload_archive();
+                Task::none()
+            }
+            Message::ArchiveImportFileChanged(path) => {
+                self.archive_viewer.import_file = path;
+                Task::none()
+            }
+            Message::ImportArchive => {
+                let source = std::path::PathBuf::from(self.archive_viewer.import_file.trim());
+                if let Some(path) = self.archive_path() {
+                    self.archive_viewer.last_result = Some(
+                        archive::import(&path, &source)
+                            .map(|count| format!("Imported {} new entries", count)),
+                    );
+                }
+                self.reload_archive();
+                Task::none()
+            }
+            Message::CloseSettings => {
+                self.pending_settings = None;
+                self.show_settings = false;
+                Task::none()
+            }
+            Message::SystemThemeChanged(mode) => {
+                self.system_theme = mode;
                 Task::none()
             }
-            Message::CloseSettings | Message::ModalBackdropClicked => {
+            Message::ModalBackdropClicked => {
                 self.pending_settings = None;
                 self.show_settings = false;
+                self.close_subscriptions();
                 Task::none()
             }
             Message::SaveSettings => {
+                let mut task = Task::none();
                 if let Some(pending) = self.pending_settings.take() {
+                    let mut tasks = Vec::new();
+                    if pending.ffmpeg_location != self.settings.ffmpeg_location {
+                        tasks.push(detect_ffmpeg(&pending.ffmpeg_location));
+                    }
+                    if pending.watch_clipboard != self.settings.watch_clipboard {
+                        self.clipboard = ClipboardWatch::default();
+                    }
+                    if ytdlp::resolve_path(&pending) != ytdlp::resolve_path(&self.settings) {
+                        tasks.push(probe_capabilities(ytdlp::resolve_path(&pending)));
+                    }
                     self.settings = pending;
                     if self.persist_settings {
                         let _ = config::save_settings(&self.settings);
                     }
+                    tasks.push(self.start_queued());
+                    task = Task::batch(tasks);
                 }
                 self.show_settings = false;
-                Task::none()
+                task
             }
             Message::ResetDefaults => {
                 self.pending_settings = Some(AdvancedSettings::default());
@@ -216,11 +940,59 @@ impl App {
                 }
                 Task::none()
             }
+            Message::BrowsePath(field) => {
+                self.picker_error = None;
+                let current = paths::expand(field.value(self.active_settings()));
+                // Start in the folder the current value points into.
+                let (kind, start) = if field.is_folder() {
+                    (PickKind::Folder, Some(current))
+                } else {
+                    (PickKind::File, current.parent().map(|p| p.to_path_buf()))
+                };
+                let start = start.filter(|p| p.is_dir());
+                Task::perform(picker::pick(kind, field.title().to_string(), start), move |result| {
+                    Message::PathPicked(field, result)
+                })
+            }
+            Message::PathPicked(field, result) => {
+                match result {
+                    Ok(Some(path)) => {
+                        return self.update(Message::SettingsChanged(
+                            field.setting(path.to_string_lossy().to_string()),
+                        ));
+                    }
+                    Ok(None) => {}
+                    Err(e) => self.picker_error = Some(e),
+                }
+                Task::none()
+            }
             Message::SettingsChanged(field) => {
+                let path_changed = matches!(
+                    field,
+                    SettingsField::OutputDirectory(_)
+                        | SettingsField::TempDirectory(_)
+                        | SettingsField::SubtitleDirectory(_)
+                        | SettingsField::ThumbnailDirectory(_)
+                        | SettingsField::CookiesFile(_)
+                        | SettingsField::YtDlpPath(_)
+                );
                 if let Some(ref mut settings) = self.pending_settings {
                     match field {
+                        SettingsField::Theme(v) => settings.theme = v,
+                        SettingsField::TrayIcon(v) => settings.tray_icon = v,
+                        SettingsField::MinimizeToTray(v) => settings.minimize_to_tray = v,
+                        SettingsField::CloseAction(v) => settings.close_action = v,
+                        SettingsField::NotifyOnSuccess(v) => settings.notify_on_success = v,
+                        SettingsField::NotifyOnFailure(v) => settings.notify_on_failure = v,
+                        SettingsField::NotifyInBackgroundOnly(v) => settings.notify_in_background_only = v,
                         SettingsField::OutputDirectory(v) => settings.output_directory = v,
+                        SettingsField::TempDirectory(v) => settings.temp_directory = v,
+                        SettingsField::SubtitleDirectory(v) => settings.subtitle_directory = v,
+                        SettingsField::ThumbnailDirectory(v) => settings.thumbnail_directory = v,
                         SettingsField::FilenameTemplate(v) => settings.filename_template = v,
+                        SettingsField::DownloadArchive(v) => settings.download_archive = v,
+                        SettingsField::DiskSpaceCheck(v) => settings.disk_space_check = v,
+                        SettingsField::DiskSpaceReserve(v) => settings.disk_space_reserve = v,
                         SettingsField::PreferredQuality(v) => settings.preferred_quality = v,
                         SettingsField::PreferredCodec(v) => settings.preferred_codec = v,
                         SettingsField::DownloadSubtitles(v) => settings.download_subtitles = v,
@@ -235,46 +1007,1212 @@ impl App {
                         SettingsField::Proxy(v) => settings.proxy = v,
                         SettingsField::RateLimit(v) => settings.rate_limit = v,
                         SettingsField::ConcurrentFragments(v) => settings.concurrent_fragments = v,
+                        SettingsField::GlobalRateLimit(v) => settings.global_rate_limit = v,
+                        SettingsField::BandwidthSchedule(v) => settings.bandwidth_schedule = v,
+                        SettingsField::MaxConcurrentDownloads(v) => {
+                            settings.max_concurrent_downloads = v
+                        }
                         SettingsField::CookiesFile(v) => settings.cookies_file = v,
                         SettingsField::YtDlpPath(v) => settings.ytdlp_path = v,
+                        SettingsField::FfmpegLocation(v) => settings.ffmpeg_location = v,
                         SettingsField::JsRuntimes(v) => settings.js_runtimes = v,
                         SettingsField::ExtraArguments(v) => settings.extra_arguments = v,
+                        SettingsField::WatchClipboard(v) => settings.watch_clipboard = v,
+                        SettingsField::IdentifyExtractor(v) => settings.identify_extractor = v,
+                        SettingsField::UseManagedYtDlp(v) => settings.use_managed_ytdlp = v,
+                        SettingsField::YtDlpUpdateChannel(v) => settings.ytdlp_update_channel = v,
+                        SettingsField::YtDlpMirrorUrl(v) => settings.ytdlp_mirror_url = v,
                     }
                 }
+                if path_changed {
+                    self.check_paths();
+                }
                 Task::none()
             }
-            Message::WindowMinimize => {
-                window::oldest().and_then(|id| window::minimize(id, true))
+            Message::YtDlpInstallFileChanged(path) => {
+                self.ytdlp_status.install_file = path;
+                Task::none()
             }
-            Message::WindowClose => {
-                window::oldest().and_then(window::close)
+            Message::YtDlpAllowUnverifiedToggled(allow) => {
+                self.ytdlp_status.allow_unverified = allow;
+                Task::none()
             }
-            Message::WindowDrag => {
-                window::oldest().and_then(window::drag)
+            Message::InstallYtDlpFromFile => {
+                let source = std::path::PathBuf::from(self.ytdlp_status.install_file.trim());
+                let allow_unverified = self.ytdlp_status.allow_unverified;
+                self.run_ytdlp_manager(move || ytdlp::install_from_file(&source, allow_unverified))
             }
-        }
-    }
-
-    pub fn view(&self) -> Element<'_, Message> {
-        use iced::widget::column;
-
-        let title = title_bar();
-        let home = home_view(&self.url, self.format, &self.download_state);
-
-        let main_content = column![title, home];
+            Message::InstallYtDlpFromMirror => {
+                let settings = self.active_settings();
+                let source = ytdlp::release_source(&settings.ytdlp_mirror_url, settings.ytdlp_update_channel);
+                self.run_ytdlp_manager(move || ytdlp::install_from_mirror(&source))
+            }
+            Message::UpdateYtDlp => {
+                let settings = self.active_settings();
+                let target = ytdlp::update_target(&settings.ytdlp_mirror_url, settings.ytdlp_update_channel);
+                self.run_ytdlp_manager(move || ytdlp::update(&target?))
+            }
+            Message::RollbackYtDlp => self.run_ytdlp_manager(ytdlp::rollback),
+            Message::YtDlpVersionLoaded(version) => {
+                self.ytdlp_status.version = version;
+                Task::none()
+            }
+            Message::CapabilitiesProbed(capabilities) => {
+                self.capabilities = capabilities;
+                Task::none()
+            }
+            Message::FfmpegDetected(info) => {
+                self.ffmpeg = info;
+                Task::none()
+            }
+            Message::YtDlpManagerFinished(result) => {
+                self.ytdlp_status.busy = false;
+                self.ytdlp_status.has_previous = ytdlp::has_previous();
+                let task = match result {
+                    Ok(ref version) => {
+                        self.ytdlp_status.version = Some(version.clone());
+                        probe_capabilities(ytdlp::resolve_path(&self.settings))
+                    }
+                    Err(_) => Task::none(),
+                };
+                self.ytdlp_status.last_result = Some(result);
+                task
+            }
+            Message::Notify(NotifyEvent::Ready(notifier)) => {
+                self.notifier = Box::new(notifier);
+                self.notify_error = None;
+                Task::none()
+            }
+            Message::Notify(NotifyEvent::Unavailable(error) | NotifyEvent::Failed(error)) => {
+                self.notify_error = Some(error);
+                Task::none()
+            }
+            Message::Notify(NotifyEvent::Action(action, file)) => {
+                // Errors go on the download's card while it is still listed
+                let id = self
+                    .downloads
+                    .iter()
+                    .find(|d| {
+                        matches!(d.state, DownloadState::Completed { ref output_path } if Path::new(output_path) == file)
+                    })
+                    .map(|d| d.id);
+                self.open_file(id, file, action == NotificationAction::ShowInFolder)
+            }
+            Message::WindowFocused(focused) => {
+                self.window_focused = focused;
+                Task::none()
+            }
+            Message::WindowMinimize => {
+                if self.settings.minimize_to_tray && self.tray_available() {
+                    self.hide_window()
+                } else {
+                    window::oldest().and_then(|id| window::minimize(id, true))
+                }
+            }
+            Message::WindowClose => {
+                if self.settings.close_action == CloseAction::HideToTray && self.tray_available() {
+                    self.hide_window()
+                } else {
+                    self.request_quit()
+                }
+            }
+            Message::Tray(TrayEvent::Ready(handle)) => {
+                self.tray = Some(handle);
+                self.tray_error = None;
+                self.update(Message::TrayTick)
+            }
+            Message::Tray(TrayEvent::Unavailable(error)) => {
+                self.tray = None;
+                self.tray_error = Some(error);
+                // Nothing left to bring a hidden window back with.
+                if self.window_hidden {
+                    self.show_window()
+                } else {
+                    Task::none()
+                }
+            }
+            Message::Tray(TrayEvent::Action(action)) => match action {
+                TrayAction::Activate if !self.window_hidden => self.hide_window(),
+                TrayAction::Activate | TrayAction::ShowWindow => self.show_window(),
+                TrayAction::PauseAll => {
+                    self.downloads.pause_all();
+                    Task::none()
+                }
+                TrayAction::ResumeAll => {
+                    self.downloads.resume_all();
+                    self.start_queued()
+                }
+                TrayAction::Quit => {
+                    let show = self.show_window();
+                    Task::batch([show, self.request_quit()])
+                }
+            },
+            Message::TrayTick => {
+                if let Some(ref tray) = self.tray {
+                    tray.set_tooltip(self.tray_tooltip());
+                }
+                Task::none()
+            }
+            Message::CloseGuardDismissed => {
+                self.show_close_guard = false;
+                Task::none()
+            }
+            Message::QuitResumingNextLaunch => {
+                // Saved before stopping anything, so a failure can still be
+                // answered by waiting. Choosing to quit again goes ahead.
+                let retry = self.schedule_error.is_some();
+                if let Err(e) = schedule::save(&self.downloads.suspended()) {
+                    self.schedule_error = Some(e);
+                    if !retry {
+                        return Task::none();
+                    }
+                }
+                self.show_close_guard = false;
+                let stopped = self.downloads.suspend_all();
+                if stopped.is_empty() {
+                    return self.quit();
+                }
+                self.closing = Some(stopped.into_iter().collect());
+                Task::none()
+            }
+            Message::QuitWhenDownloadsFinish => {
+                self.show_close_guard = false;
+                self.quit_when_idle = true;
+                if self.tray_available() {
+                    self.hide_window()
+                } else {
+                    window::oldest().and_then(|id| window::minimize(id, true))
+                }
+            }
+            Message::WindowDrag => {
+                window::oldest().and_then(window::drag)
+            }
+            Message::WindowToggleMaximize => {
+                window::oldest().and_then(window::toggle_maximize)
+            }
+            Message::WindowResize(direction) => {
+                if self.window.saved.maximized {
+                    return Task::none();
+                }
+                window::oldest().and_then(move |id| window::drag_resize(id, direction))
+            }
+            Message::WindowResized(size) => {
+                self.window.size = Some(size);
+                window::oldest().and_then(window::is_maximized).map(Message::WindowMaximized)
+            }
+            Message::WindowMoved(position) => {
+                self.window.position = Some(position);
+                window::oldest().and_then(window::is_maximized).map(Message::WindowMaximized)
+            }
+            Message::WindowMaximized(maximized) => {
+                let saved = &mut self.window.saved;
+                saved.maximized = maximized;
+                if !maximized {
+                    if let Some(size) = self.window.size {
+                        saved.width = size.width;
+                        saved.height = size.height;
+                    }
+                    if let Some(position) = self.window.position {
+                        saved.x = Some(position.x);
+                        saved.y = Some(position.y);
+                    }
+                }
+                Task::none()
+            }
+        }
+    }
+
+    /// Returns a warning when the download is likely to fail or produce
+    /// something other than what was asked for.
+    fn preflight_check(&self, pending: &PendingStart) -> Option<String> {
+        let url = pending.urls.first().cloned().unwrap_or_default();
+        let task = DownloadTask::new(url, pending.format, self.settings.clone());
+        if task.requires_ffmpeg() && !self.ffmpeg.is_available() {
+            return Some(
+                "ffmpeg was not found, but this download needs it for merging, conversion or embedding. Set its location under Advanced."
+                    .to_string(),
+            );
+        }
+        if let Err(e) = template::validate(&self.settings.filename_template) {
+            return Some(format!("The filename template is invalid: {}. Fix it under Advanced.", e));
+        }
+        None
+    }
+
+    /// Queues downloads behind the checks, which run right away unless
+    /// others are already waiting on them.
+    fn request_start(&mut self, pending: PendingStart) -> Task<Message> {
+        if pending.urls.is_empty() {
+            return Task::none();
+        }
+        self.pending_starts.push_back(pending);
+        if self.pending_starts.len() == 1 {
+            self.check_next_start()
+        } else {
+            Task::none()
+        }
+    }
+
+    /// Runs the checks for the first waiting start, stopping at a warning
+    /// until it is confirmed or dismissed.
+    fn check_next_start(&mut self) -> Task<Message> {
+        let Some(pending) = self.pending_starts.front() else {
+            return Task::none();
+        };
+        if let Some(warning) = self.preflight_check(pending) {
+            self.preflight_warning = Some(match pending.origin {
+                StartOrigin::Subscription(id) => {
+                    let url = self.subscriptions.iter().find(|s| s.id == id).map_or("", |s| s.url.as_str());
+                    format!("New uploads from {}: {}", url, warning)
+                }
+                _ => warning,
+            });
+            self.preflight_blocked = false;
+            return Task::none();
+        }
+        if let Some(pending) = self.pending_starts.front_mut() {
+            pending.preflight_passed = true;
+        }
+        self.check_space()
+    }
+
+    /// Measures a single video before it starts and warns when it won't fit
+    /// on the disk. Several URLs, playlists and channels start right away,
+    /// since measuring them means fetching every entry.
+    fn check_space(&mut self) -> Task<Message> {
+        let Some(pending) = self.pending_starts.front() else {
+            return Task::none();
+        };
+        let url = match pending.urls.as_slice() {
+            [url] if urls::analyze(url).is_ok_and(|info| matches!(info.kind, UrlKind::Video | UrlKind::Other)) => {
+                url.clone()
+            }
+            _ => return self.enqueue(),
+        };
+        if self.settings.disk_space_check == SpaceCheck::Off {
+            return self.enqueue();
+        }
+
+        let task = DownloadTask::new(url.clone(), pending.format, self.settings.clone())
+            .with_capabilities(self.capabilities.clone());
+        let reserve = units::parse_size(&self.settings.disk_space_reserve).unwrap_or(0);
+        self.checking_space = Some(url.clone());
+        Task::perform(space::check(task, reserve), move |warning| {
+            Message::DiskSpaceChecked(url.clone(), warning)
+        })
+    }
+
+    /// What the Download button adds: the valid lines of the batch editor,
+    /// or the URL field.
+    fn pending_urls(&self) -> Vec<String> {
+        match self.batch {
+            Some(ref batch) => urls::parse_batch(&batch.content.text())
+                .into_iter()
+                .filter_map(|line| line.url.ok())
+                .collect(),
+            None => urls::analyze(&self.url)
+                .map(|info| vec![info.normalized])
+                .unwrap_or_default(),
+        }
+    }
+
+    /// When the Download button should start downloads; `None` is now.
+    fn start_condition(&self) -> Result<Option<StartCondition>, String> {
+        match self.start_mode {
+            StartMode::Now => Ok(None),
+            StartMode::At if self.start_time.trim().is_empty() => {
+                Err("Enter a start time, or choose another start option".to_string())
+            }
+            StartMode::At => StartCondition::parse(&self.start_time, chrono::Local::now()).map(Some),
+            StartMode::WhenIdle => Ok(Some(StartCondition::WhenIdle)),
+        }
+    }
+
+    /// Adds the first waiting start to the list, then checks the next one.
+    fn enqueue(&mut self) -> Task<Message> {
+        let Some(PendingStart { urls, format, origin, .. }) = self.pending_starts.pop_front() else {
+            return Task::none();
+        };
+
+        match origin {
+            StartOrigin::Field | StartOrigin::Batch => {
+                // Checked before the batch editor is consumed, so a bad time
+                // keeps the list for another try
+                let condition = match self.start_condition() {
+                    Ok(condition) => condition,
+                    Err(e) => {
+                        self.start_error = Some(e);
+                        return self.check_next_start();
+                    }
+                };
+                self.start_error = None;
+                if origin == StartOrigin::Batch {
+                    self.batch = None;
+                }
+
+                let mut scheduled = false;
+                for url in urls {
+                    // Batch lists skip what is already in the list; a single
+                    // URL may be downloaded again on purpose
+                    if origin == StartOrigin::Batch && self.downloads.contains_url(&url) {
+                        continue;
+                    }
+                    match condition {
+                        Some(condition) => {
+                            self.downloads.push_scheduled(url, format, condition);
+                            scheduled = true;
+                        }
+                        None => {
+                            self.downloads.push(url, format);
+                        }
+                    }
+                }
+                if scheduled {
+                    self.save_schedule();
+                }
+            }
+            StartOrigin::Clipboard => {
+                for url in urls {
+                    self.downloads.push(url, format);
+                }
+            }
+            StartOrigin::Drop => {
+                let total = urls.len();
+                let mut added = 0;
+                for url in urls {
+                    if !self.downloads.contains_url(&url) {
+                        self.downloads.push(url, format);
+                        added += 1;
+                    }
+                }
+                self.notice = Some(if added == total {
+                    format!("Added {} downloads", added)
+                } else {
+                    format!("Added {} downloads, {} already in the list", added, total - added)
+                });
+            }
+            StartOrigin::Subscription(id) => {
+                let mut added = 0;
+                for url in urls {
+                    if self.downloads.contains_url(&url) {
+                        continue;
+                    }
+                    let download_id = self.downloads.push(url, format);
+                    if let Some(download) = self.downloads.get_mut(download_id) {
+                        download.from_subscription = true;
+                    }
+                    added += 1;
+                }
+                if let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.id == id) {
+                    subscription.new_uploads += added;
+                    self.save_subscriptions();
+                }
+            }
+        }
+        Task::batch([self.start_queued(), self.check_next_start()])
+    }
+
+    /// The archive file shown in the viewer, following the scope being edited.
+    fn archive_path(&self) -> Option<std::path::PathBuf> {
+        archive::path(self.active_settings().download_archive, self.archive_viewer.format)
+    }
+
+    fn reload_archive(&mut self) {
+        self.archive_viewer.entries = self
+            .archive_path()
+            .map(|path| archive::entries(&path))
+            .unwrap_or_default();
+    }
+
+    /// Closing the dialog counts as having seen the new uploads.
+    fn close_subscriptions(&mut self) {
+        self.show_subscriptions = false;
+        for subscription in &mut self.subscriptions {
+            subscription.new_uploads = 0;
+        }
+    }
+
+    fn save_subscriptions(&self) {
+        let _ = subscriptions::save(&self.subscriptions);
+    }
+
+    /// Validates the path settings being edited. Done when they change
+    /// rather than in `view`, which runs on every redraw.
+    fn check_paths(&mut self) {
+        let settings = self.active_settings();
+        let errors = PathField::ALL
+            .into_iter()
+            .filter_map(|field| field.check(field.value(settings)).err().map(|e| (field, e)))
+            .collect();
+        self.path_errors = errors;
+    }
+
+    /// Probes a subscription for uploads that are not in the download archive.
+    fn check_subscription(&mut self, id: u64) -> Task<Message> {
+        let ytdlp_path = ytdlp::resolve_path(&self.settings);
+        let access = task::access_args(&self.settings, self.capabilities.as_ref());
+        let scope = self.settings.download_archive;
+        let Some(subscription) = self.subscriptions.iter_mut().find(|s| s.id == id) else {
+            return Task::none();
+        };
+        if subscription.checking {
+            return Task::none();
+        }
+        let Some(archive_path) = archive::subscription_path(scope, subscription.format) else {
+            subscription.last_error = Some("Could not determine data directory".to_string());
+            return Task::none();
+        };
+        subscription.checking = true;
+
+        let url = subscription.url.clone();
+        let seed = !subscription.seeded;
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    subscriptions::check(&ytdlp_path, &url, &access, &archive_path, seed)
+                })
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
+            },
+            move |result| Message::SubscriptionChecked(id, result),
+        )
+    }
+
+    /// Downloads running or waiting for a slot.
+    fn active_downloads(&self) -> usize {
+        self.downloads.running_count() + self.downloads.waiting().count()
+    }
+
+    /// Moves the queue along after a download finished or was cancelled,
+    /// quitting if the user chose to leave once everything is done.
+    fn download_ended(&mut self) -> Task<Message> {
+        let task = self.start_queued();
+        if self.quit_when_idle && self.active_downloads() == 0 {
+            return self.quit();
+        }
+        task
+    }
+
+    /// Output file of a completed download.
+    fn output_path(&self, id: DownloadId) -> Option<PathBuf> {
+        self.downloads.iter().find(|d| d.id == id).and_then(|d| match d.state {
+            DownloadState::Completed { ref output_path } => Some(PathBuf::from(output_path)),
+            _ => None,
+        })
+    }
+
+    /// Opens or reveals a file off the UI thread; the opener may wait on
+    /// the file manager.
+    fn open_file(&self, id: Option<DownloadId>, path: PathBuf, reveal: bool) -> Task<Message> {
+        let opener = Arc::clone(&self.opener);
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || {
+                    if reveal {
+                        opener.reveal(&path)
+                    } else {
+                        opener.open(&path)
+                    }
+                })
+                .await
+                .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
+            },
+            move |result| Message::OpenerFinished(id, result),
+        )
+    }
+
+    /// Closes straight away when nothing is downloading, otherwise asks first.
+    fn request_quit(&mut self) -> Task<Message> {
+        if self.active_downloads() > 0 {
+            self.show_close_guard = true;
+            Task::none()
+        } else {
+            self.quit()
+        }
+    }
+
+    fn tray_available(&self) -> bool {
+        self.settings.tray_icon && self.tray.is_some()
+    }
+
+    fn hide_window(&mut self) -> Task<Message> {
+        self.window_hidden = true;
+        window::oldest().and_then(|id| window::set_mode(id, window::Mode::Hidden))
+    }
+
+    fn show_window(&mut self) -> Task<Message> {
+        self.window_hidden = false;
+        window::oldest().and_then(|id| {
+            window::set_mode(id, window::Mode::Windowed).chain(window::gain_focus(id))
+        })
+    }
+
+    /// Aggregate progress for the tray icon's tooltip.
+    fn tray_tooltip(&self) -> String {
+        let fractions: Vec<f32> = self
+            .downloads
+            .iter()
+            .filter_map(|d| match d.state {
+                DownloadState::Downloading { ref progress, .. } => Some(progress.overall_fraction()),
+                _ => None,
+            })
+            .collect();
+        let waiting = self.downloads.waiting().count();
+        let paused = self
+            .downloads
+            .iter()
+            .filter(|d| matches!(d.state, DownloadState::Paused))
+            .count();
+
+        let mut parts = Vec::new();
+        if !fractions.is_empty() {
+            let average = fractions.iter().sum::<f32>() / fractions.len() as f32;
+            parts.push(format!(
+                "{} downloading ({:.0}%)",
+                fractions.len(),
+                average * 100.0
+            ));
+        }
+        if waiting > 0 {
+            parts.push(format!("{} queued", waiting));
+        }
+        if paused > 0 {
+            parts.push(format!("{} paused", paused));
+        }
+
+        if parts.is_empty() {
+            "No active downloads".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    /// Saves what should survive a restart and closes the window.
+    fn quit(&mut self) -> Task<Message> {
+        self.save_schedule();
+        // Losing the window size is no reason to keep the app open; it just
+        // starts at the default size next time.
+        let _ = config::save_window_geometry(&self.window.saved);
+        window::oldest().and_then(window::close)
+    }
+
+    /// Writes deferred downloads to disk so they survive a restart. A
+    /// failure is shown in the close dialog.
+    fn save_schedule(&mut self) {
+        self.schedule_error = schedule::save(&self.downloads.scheduled()).err();
+    }
+
+    /// Releases scheduled downloads that are due, starts queued downloads
+    /// while there are free slots, then rebalances the bandwidth budget
+    /// across everything running.
+    fn start_queued(&mut self) -> Task<Message> {
+        // Everything left was deferred to the next launch.
+        if self.closing.is_some() {
+            return Task::none();
+        }
+
+        if self.downloads.release_due(chrono::Local::now()) {
+            self.save_schedule();
+        }
+
+        let max = self
+            .settings
+            .max_concurrent_downloads
+            .trim()
+            .parse::<usize>()
+            .unwrap_or(1)
+            .max(1);
+        let free = max.saturating_sub(self.downloads.running_count());
+        let queued: Vec<DownloadId> = self.downloads.waiting().take(free).collect();
+
+        let share = self.bandwidth_share(self.downloads.running_count() + queued.len());
+        let mut tasks: Vec<Task<Message>> = queued
+            .into_iter()
+            .map(|id| self.launch(id, share))
+            .collect();
+        tasks.push(self.rebalance());
+        Task::batch(tasks)
+    }
+
+    /// The global budget currently in effect, from the schedule or the
+    /// global limit. `None` means no global limit.
+    fn bandwidth_budget(&self) -> Option<u64> {
+        let rules = bandwidth::parse_schedule(&self.settings.bandwidth_schedule).unwrap_or_default();
+        let default = bandwidth::parse_rate(&self.settings.global_rate_limit);
+        bandwidth::budget_at(&rules, default, chrono::Local::now().time())
+    }
+
+    fn bandwidth_share(&self, running: usize) -> Option<u64> {
+        bandwidth::share(self.bandwidth_budget(), running)
+    }
+
+    /// Restarts running transfers whose `--limit-rate` no longer matches their
+    /// share of the budget. Post-processing is never interrupted.
+    fn rebalance(&mut self) -> Task<Message> {
+        let target = self.bandwidth_share(self.downloads.running_count());
+        let restart: Vec<DownloadId> = self
+            .downloads
+            .iter()
+            .filter(|d| matches!(d.state, DownloadState::Queued | DownloadState::Downloading { .. }))
+            .filter(|d| d.run.as_ref().is_some_and(|r| needs_restart(r.rate_limit, target)))
+            .map(|d| d.id)
+            .collect();
+
+        Task::batch(restart.into_iter().map(|id| {
+            if let Some(download) = self.downloads.get_mut(id) {
+                download.stop();
+            }
+            self.launch(id, target)
+        }))
+    }
+
+    /// Spawns yt-dlp for a queue entry. Restarted downloads keep their state
+    /// and statistics; yt-dlp resumes from the partial file.
+    fn launch(&mut self, id: DownloadId, rate_limit: Option<u64>) -> Task<Message> {
+        let run_id = self.downloads.next_id();
+        let settings = self.settings.clone();
+        let capabilities = self.capabilities.clone();
+        let scope = settings.download_archive;
+
+        let Some(download) = self.downloads.get_mut(id) else {
+            return Task::none();
+        };
+
+        let task = DownloadTask::new(download.url.clone(), download.format, settings)
+            .with_capabilities(capabilities.clone())
+            .with_rate_limit(rate_limit)
+            .with_download_archive(if download.from_subscription {
+                archive::subscription_path(scope, download.format)
+            } else {
+                archive::path(scope, download.format)
+            });
+
+        let unsupported = task.unsupported_options();
+        download.notice = (!unsupported.is_empty()).then(|| {
+            let version = capabilities
+                .as_ref()
+                .map(|c| c.version.as_str())
+                .unwrap_or_default();
+            format!(
+                "Skipped options not supported by yt-dlp {}: {}",
+                version,
+                unsupported.join(", ")
+            )
+        });
+
+        let (cancel_tx, cancel_rx) = mpsc::channel::<()>(1);
+        download.run = Some(ActiveRun {
+            id: run_id,
+            cancel: cancel_tx,
+            rate_limit,
+        });
+
+        if matches!(download.state, DownloadState::Queued) {
+            download.history.reset();
+        }
+
+        run_download(run_id, task, cancel_rx)
+    }
+
+    /// Settings currently being edited in the dialog, falling back to the
+    /// saved ones.
+    fn active_settings(&self) -> &AdvancedSettings {
+        self.pending_settings.as_ref().unwrap_or(&self.settings)
+    }
 
-        let content: Element<'_, Message> = if self.show_settings {
-            let settings = self.pending_settings.as_ref().unwrap_or(&self.settings);
-            let dialog = settings_dialog(settings, self.persist_settings);
-            modal(main_content, dialog, Message::ModalBackdropClicked)
+    fn run_ytdlp_manager(
+        &mut self,
+        operation: impl FnOnce() -> Result<String, String> + Send + 'static,
+    ) -> Task<Message> {
+        if self.ytdlp_status.busy {
+            return Task::none();
+        }
+        self.ytdlp_status.busy = true;
+        self.ytdlp_status.last_result = None;
+
+        Task::perform(
+            async move {
+                tokio::task::spawn_blocking(operation)
+                    .await
+                    .unwrap_or_else(|e| Err(format!("Task error: {}", e)))
+            },
+            Message::YtDlpManagerFinished,
+        )
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        use iced::widget::column;
+
+        let radius = self.theme_spec().radius;
+        let title = title_bar(self.window.saved.maximized, radius);
+        let home = home_view(HomeState {
+            url: &self.url,
+            format: self.format,
+            start_mode: self.start_mode,
+            start_time: &self.start_time,
+            start_error: self.start_error.as_deref(),
+            downloads: &self.downloads,
+            preflight_warning: self.preflight_warning.as_deref(),
+            preflight_blocked: self.preflight_blocked,
+            checking_space: self.checking_space.is_some(),
+            clipboard_offer: self.clipboard.offer.as_deref(),
+            batch: self.batch.as_ref(),
+            capabilities: self
+                .capabilities
+                .as_ref()
+                .filter(|_| self.settings.identify_extractor),
+            drop_hover: self.drop_hover,
+            notice: self.notice.as_deref(),
+            new_uploads: self.subscriptions.iter().map(|s| s.new_uploads).sum(),
+            pending_delete: self.pending_delete,
+            radius,
+        });
+
+        let main_content = column![title, home];
+
+        let content: Element<'_, Message> = if self.show_close_guard {
+            modal(
+                main_content,
+                close_dialog(self.active_downloads(), self.schedule_error.as_deref(), radius),
+                Message::CloseGuardDismissed,
+                radius,
+            )
+        } else if self.show_settings {
+            let dialog = settings_dialog(
+                self.active_settings(),
+                SettingsDialogState {
+                    persist_enabled: self.persist_settings,
+                    ytdlp_status: &self.ytdlp_status,
+                    ffmpeg: &self.ffmpeg,
+                    archive: &self.archive_viewer,
+                    themes: &self.user_themes,
+                    tray_error: self.tray_error.as_deref(),
+                    notify_error: self.notify_error.as_deref(),
+                    template: &self.template_editor,
+                    picker_error: self.picker_error.as_deref(),
+                    path_errors: &self.path_errors,
+                    radius,
+                },
+            );
+            modal(main_content, dialog, Message::ModalBackdropClicked, radius)
+        } else if self.show_subscriptions {
+            let dialog = subscriptions_dialog(&self.subscriptions, &self.subscription_form, radius);
+            modal(main_content, dialog, Message::ModalBackdropClicked, radius)
         } else {
             main_content.into()
         };
 
-        container(content)
+        let window = container(content)
             .width(Fill)
             .height(Fill)
-            .style(window_container_style)
-            .into()
+            .style(window_container_style(radius));
+
+        if self.window.saved.maximized {
+            window.into()
+        } else {
+            resize_edges(window)
+        }
+    }
+}
+
+/// What to tell the desktop about a download that just ended, if the
+/// settings ask for it.
+fn completion_notification(download: &Download, settings: &AdvancedSettings) -> Option<Notification> {
+    match download.state {
+        DownloadState::Completed { ref output_path } if settings.notify_on_success => {
+            let file = PathBuf::from(output_path);
+            Some(Notification {
+                summary: "Download complete".to_string(),
+                body: file
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| download.url.clone()),
+                file: Some(file),
+            })
+        }
+        DownloadState::Error { ref message } if settings.notify_on_failure => Some(Notification {
+            summary: "Download failed".to_string(),
+            body: format!("{}\n{}", download.url, message),
+            file: None,
+        }),
+        _ => None,
+    }
+}
+
+fn load_user_themes() -> UserThemes {
+    config::themes_dir()
+        .map(|dir| UserThemes::load(&dir))
+        .unwrap_or_default()
+}
+
+fn run_download(
+    run: RunId,
+    task: DownloadTask,
+    mut cancel_rx: mpsc::Receiver<()>,
+) -> Task<Message> {
+    Task::stream(async_stream::stream! {
+        let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+
+        let download_handle = tokio::spawn(async move { task.run(progress_tx).await });
+
+        loop {
+            tokio::select! {
+                _ = cancel_rx.recv() => {
+                    download_handle.abort();
+                    yield Message::DownloadComplete(run, Err("Cancelled".to_string()));
+                    break;
+                }
+                progress = progress_rx.recv() => {
+                    match progress {
+                        Some(p) => yield Message::DownloadProgress(run, p),
+                        None => break,
+                    }
+                }
+            }
+        }
+
+        match download_handle.await {
+            Ok(Ok(path)) => yield Message::DownloadComplete(run, Ok(path)),
+            Ok(Err(e)) => yield Message::DownloadComplete(run, Err(e)),
+            Err(e) if e.is_cancelled() => {},
+            Err(e) => yield Message::DownloadComplete(run, Err(format!("Task error: {}", e))),
+        }
+    })
+}
+
+fn detect_ffmpeg(location: &str) -> Task<Message> {
+    let location = location.to_string();
+    Task::perform(
+        async move {
+            tokio::task::spawn_blocking(move || ffmpeg::detect(&location))
+                .await
+                .unwrap_or_default()
+        },
+        Message::FfmpegDetected,
+    )
+}
+
+fn probe_capabilities(ytdlp_path: std::path::PathBuf) -> Task<Message> {
+    Task::perform(
+        async move {
+            tokio::task::spawn_blocking(move || capabilities::probe(&ytdlp_path))
+                .await
+                .ok()
+                .flatten()
+        },
+        Message::CapabilitiesProbed,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notify::RecordingNotifier;
+    use crate::opener::RecordingOpener;
+    use iced::futures::StreamExt;
+    use iced_runtime::Action;
+
+    fn app() -> App {
+        App::with_state(AdvancedSettings::default(), false, DownloadQueue::default(), Vec::new())
+    }
+
+    /// Runs a task to completion and returns the messages it produced.
+    async fn outputs(task: Task<Message>) -> Vec<Message> {
+        let Some(stream) = iced_runtime::task::into_stream(task) else {
+            return Vec::new();
+        };
+        stream
+            .filter_map(|action| async move {
+                match action {
+                    Action::Output(message) => Some(message),
+                    _ => None,
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Feeds a task's messages back into the app, like the runtime does.
+    async fn settle(app: &mut App, task: Task<Message>) {
+        for message in outputs(task).await {
+            let _ = app.update(message);
+        }
+    }
+
+    fn scratch_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("yt-dlgui-app-{}-{}.mp4", name, std::process::id()));
+        fs::write(&path, b"video").unwrap();
+        path
+    }
+
+    fn completed(app: &mut App, path: &Path) -> DownloadId {
+        let id = app.downloads.push("https://example.com/watch?v=1".to_string(), Format::Default);
+        app.downloads.get_mut(id).unwrap().state = DownloadState::Completed {
+            output_path: path.display().to_string(),
+        };
+        id
+    }
+
+    #[tokio::test]
+    async fn open_and_reveal_go_through_the_opener() {
+        let mut app = app();
+        let opener = RecordingOpener::default();
+        app.opener = Arc::new(opener.clone());
+        let file = scratch_file("open");
+        let id = completed(&mut app, &file);
+
+        let task = app.update(Message::OpenDownload(id));
+        settle(&mut app, task).await;
+        let task = app.update(Message::RevealDownload(id));
+        settle(&mut app, task).await;
+
+        assert_eq!(
+            *opener.calls.lock().unwrap(),
+            vec![("open", file.clone()), ("reveal", file.clone())]
+        );
+        assert!(app.downloads.iter().all(|d| d.notice.is_none()));
+        let _ = fs::remove_file(file);
+    }
+
+    #[tokio::test]
+    async fn opening_a_missing_file_shows_a_notice() {
+        let mut app = app();
+        let opener = RecordingOpener::default();
+        app.opener = Arc::new(opener.clone());
+        let file = std::env::temp_dir().join("yt-dlgui-app-missing.mp4");
+        let id = completed(&mut app, &file);
+
+        let task = app.update(Message::OpenDownload(id));
+        settle(&mut app, task).await;
+
+        assert!(opener.calls.lock().unwrap().is_empty());
+        let notice = app.downloads.iter().next().unwrap().notice.clone().unwrap();
+        assert!(notice.contains("no longer exists"), "{}", notice);
+    }
+
+    #[tokio::test]
+    async fn notification_actions_report_missing_files() {
+        let mut app = app();
+        app.opener = Arc::new(RecordingOpener::default());
+        let listed = std::env::temp_dir().join("yt-dlgui-app-listed-missing.mp4");
+        let id = completed(&mut app, &listed);
+
+        let task = app.update(Message::Notify(NotifyEvent::Action(NotificationAction::Open, listed)));
+        settle(&mut app, task).await;
+        assert!(app.downloads.get_mut(id).unwrap().notice.is_some());
+
+        // Cleared from the list, so the error has no card to go on
+        let cleared = std::env::temp_dir().join("yt-dlgui-app-cleared-missing.mp4");
+        let task = app.update(Message::Notify(NotifyEvent::Action(NotificationAction::ShowInFolder, cleared)));
+        settle(&mut app, task).await;
+        assert!(app.notice.as_deref().is_some_and(|n| n.contains("yt-dlgui-app-cleared-missing.mp4")));
+    }
+
+    #[test]
+    fn delete_removes_file_and_entry_after_confirmation() {
+        let mut app = app();
+        let file = scratch_file("delete");
+        let id = completed(&mut app, &file);
+
+        let _ = app.update(Message::DeleteDownload(id));
+        assert!(file.exists(), "deleted before confirmation");
+        let _ = app.update(Message::ConfirmDeleteDownload(id));
+
+        assert!(!file.exists());
+        assert_eq!(app.downloads.iter().count(), 0);
+    }
+
+    #[test]
+    fn delete_of_missing_file_keeps_entry() {
+        let mut app = app();
+        let file = std::env::temp_dir().join("yt-dlgui-app-already-gone.mp4");
+        let id = completed(&mut app, &file);
+
+        let _ = app.update(Message::ConfirmDeleteDownload(id));
+
+        let download = app.downloads.iter().next().expect("entry was dropped");
+        assert!(download.notice.as_deref().is_some_and(|n| n.contains("nothing was deleted")));
+    }
+
+    #[test]
+    fn path_checks_run_when_settings_open_and_paths_change() {
+        let file = scratch_file("not-a-folder");
+        let settings = AdvancedSettings {
+            output_directory: file.to_string_lossy().into_owned(),
+            ..Default::default()
+        };
+        let mut app = App::with_state(settings, false, DownloadQueue::default(), Vec::new());
+
+        let _ = app.update(Message::OpenSettings);
+        assert!(app.path_errors.contains_key(&PathField::OutputDirectory));
+
+        let dir = std::env::temp_dir().to_string_lossy().into_owned();
+        let _ = app.update(Message::SettingsChanged(SettingsField::OutputDirectory(dir)));
+        assert!(!app.path_errors.contains_key(&PathField::OutputDirectory));
+    }
+
+    #[test]
+    fn clipboard_offers_each_new_url_once() {
+        let settings = AdvancedSettings {
+            watch_clipboard: true,
+            ..Default::default()
+        };
+        let mut app = App::with_state(settings, false, DownloadQueue::default(), Vec::new());
+        let read = |app: &mut App, text: &str| {
+            let _ = app.update(Message::ClipboardRead(Some(text.to_string())));
+        };
+
+        // Whatever was copied before watching started is not offered
+        read(&mut app, "https://example.com/watch?v=1");
+        assert_eq!(app.clipboard.offer, None);
+
+        read(&mut app, "https://example.com/watch?v=2");
+        assert_eq!(app.clipboard.offer.as_deref(), Some("https://example.com/watch?v=2"));
+        let _ = app.update(Message::DismissClipboardUrl);
+
+        read(&mut app, "not a link");
+        read(&mut app, "https://example.com/watch?v=2");
+        assert_eq!(app.clipboard.offer, None);
+    }
+
+    /// An app whose downloads need ffmpeg, which it hasn't found, and that
+    /// doesn't measure free space.
+    fn gated_app() -> App {
+        let settings = AdvancedSettings {
+            disk_space_check: SpaceCheck::Off,
+            ..Default::default()
+        };
+        let mut app = App::with_state(settings, false, DownloadQueue::default(), Vec::new());
+        app.format = Format::Mp3;
+        app
+    }
+
+    #[test]
+    fn preflight_blocks_merge_and_extract_formats_without_ffmpeg() {
+        let mut app = gated_app();
+        let start = |format| {
+            PendingStart::new(vec!["https://example.com/watch?v=1".to_string()], format, StartOrigin::Field)
+        };
+
+        for format in [Format::Mp4, Format::Mp3, Format::Mkv, Format::Aac] {
+            let warning = app.preflight_check(&start(format));
+            assert!(warning.is_some_and(|w| w.starts_with("ffmpeg was not found")), "{}", format);
+        }
+        assert_eq!(app.preflight_check(&start(Format::Default)), None);
+
+        app.ffmpeg.ffmpeg_version = Some("6.1.1".to_string());
+        assert_eq!(app.preflight_check(&start(Format::Mp3)), None);
+    }
+
+    #[test]
+    fn clipboard_downloads_go_through_the_preflight_check() {
+        let mut app = gated_app();
+        app.clipboard.offer = Some("https://example.com/watch?v=3".to_string());
+
+        let _ = app.update(Message::AcceptClipboardUrl);
+        assert!(app.preflight_warning.as_deref().is_some_and(|w| w.contains("ffmpeg")));
+        assert_eq!(app.downloads.iter().count(), 0);
+
+        let _ = app.update(Message::ConfirmDownload);
+        assert_eq!(app.downloads.iter().count(), 1);
+    }
+
+    #[test]
+    fn dropped_links_wait_behind_an_unanswered_warning() {
+        let mut app = gated_app();
+        app.clipboard.offer = Some("https://example.com/watch?v=3".to_string());
+        let _ = app.update(Message::AcceptClipboardUrl);
+
+        let file = std::env::temp_dir().join(format!("yt-dlgui-app-drop-{}.txt", std::process::id()));
+        fs::write(&file, "https://example.com/watch?v=4\nhttps://example.com/watch?v=5\n").unwrap();
+        let _ = app.update(Message::FileDropped(file.clone()));
+        let _ = fs::remove_file(file);
+        assert_eq!(app.downloads.iter().count(), 0);
+
+        // Dismissing the clipboard download moves on to the dropped links
+        let _ = app.update(Message::DismissPreflight);
+        assert!(app.preflight_warning.is_some());
+        assert_eq!(app.downloads.iter().count(), 0);
+
+        let _ = app.update(Message::ConfirmDownload);
+        assert_eq!(app.downloads.iter().count(), 2);
+        assert_eq!(app.notice.as_deref(), Some("Added 2 downloads"));
+        assert!(app.preflight_warning.is_none());
+    }
+
+    /// Starts a download without running yt-dlp and returns its run.
+    fn running(app: &mut App) -> RunId {
+        app.downloads.push("https://example.com/watch?v=2".to_string(), Format::Default);
+        // The launch task is dropped, so no process is spawned
+        let _ = app.start_queued();
+        app.downloads.iter().find_map(|d| d.run.as_ref()).unwrap().id
+    }
+
+    fn notified(settings: AdvancedSettings, focused: bool, result: Result<String, String>) -> Vec<Notification> {
+        let mut app = App::with_state(settings, false, DownloadQueue::default(), Vec::new());
+        let notifier = RecordingNotifier::default();
+        app.notifier = Box::new(notifier.clone());
+        app.window_focused = focused;
+
+        let run = running(&mut app);
+        let _ = app.update(Message::DownloadComplete(run, result));
+        notifier.sent.lock().unwrap().clone()
+    }
+
+    #[test]
+    fn success_notifies_with_the_file() {
+        let sent = notified(AdvancedSettings::default(), false, Ok("/videos/Clip.mp4".to_string()));
+        assert_eq!(
+            sent,
+            vec![Notification {
+                summary: "Download complete".to_string(),
+                body: "Clip.mp4".to_string(),
+                file: Some(PathBuf::from("/videos/Clip.mp4")),
+            }]
+        );
+    }
+
+    #[test]
+    fn failure_notifies_with_the_error() {
+        let sent = notified(AdvancedSettings::default(), false, Err("ERROR: Video unavailable".to_string()));
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].summary, "Download failed");
+        assert!(sent[0].body.ends_with("ERROR: Video unavailable"));
+        assert_eq!(sent[0].file, None);
+    }
+
+    #[test]
+    fn toggles_silence_each_event() {
+        let settings = AdvancedSettings {
+            notify_on_success: false,
+            ..AdvancedSettings::default()
+        };
+        assert!(notified(settings.clone(), false, Ok("/videos/Clip.mp4".to_string())).is_empty());
+        assert_eq!(notified(settings, false, Err("failed".to_string())).len(), 1);
+
+        let settings = AdvancedSettings {
+            notify_on_failure: false,
+            ..AdvancedSettings::default()
+        };
+        assert!(notified(settings, false, Err("failed".to_string())).is_empty());
+    }
+
+    #[test]
+    fn focused_window_is_only_notified_when_asked() {
+        assert!(notified(AdvancedSettings::default(), true, Ok("/videos/Clip.mp4".to_string())).is_empty());
+
+        let settings = AdvancedSettings {
+            notify_in_background_only: false,
+            ..AdvancedSettings::default()
+        };
+        assert_eq!(notified(settings, true, Ok("/videos/Clip.mp4".to_string())).len(), 1);
+    }
+
+    #[test]
+    fn notification_failures_are_shown_in_settings() {
+        let mut app = app();
+        let error = "Failed to show notification: timed out".to_string();
+        let _ = app.update(Message::Notify(NotifyEvent::Failed(error.clone())));
+        assert_eq!(app.notify_error, Some(error));
     }
 }
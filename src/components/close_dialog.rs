@@ -0,0 +1,67 @@
+use iced::widget::{button, column, container, rule, text, Space};
+use iced::{Element, Fill};
+
+use crate::message::Message;
+use crate::theme::{
+    danger_button_style, error_detail_text_style, horizontal_rule_style, muted_text_style,
+    primary_button_style, secondary_button_style,
+};
+
+/// Asks what to do with active downloads when the window is closed.
+/// `schedule_error` is why the stopped downloads could not be saved, in
+/// which case stopping again quits without them.
+pub fn close_dialog<'a>(active: usize, schedule_error: Option<&'a str>, radius: f32) -> Element<'a, Message> {
+    let header = text("Downloads in progress").size(20);
+
+    let mut body = column![
+        text(format!(
+            "{} {} still running or queued.",
+            active,
+            if active == 1 { "download is" } else { "downloads are" }
+        ))
+        .size(14),
+        text("Stopped downloads are kept and start again when the app is next opened.")
+            .size(12)
+            .style(muted_text_style),
+    ]
+    .spacing(8);
+    if let Some(error) = schedule_error {
+        body = body.push(
+            text(format!("They could not be saved: {}", error))
+                .size(12)
+                .style(error_detail_text_style),
+        );
+    }
+    let stop_label = if schedule_error.is_some() {
+        "Stop now, even if they can't be saved"
+    } else {
+        "Stop now, resume next launch"
+    };
+
+    // Stacked so the long labels fit the narrow default window.
+    let action = |label: &'a str, message: Message| {
+        button(text(label).size(14).width(Fill).center())
+            .on_press(message)
+            .padding([8, 16])
+            .width(Fill)
+    };
+
+    let footer = column![
+        action("Keep downloading in background", Message::QuitWhenDownloadsFinish)
+            .style(primary_button_style(radius)),
+        action(stop_label, Message::QuitResumingNextLaunch)
+            .style(danger_button_style(radius)),
+        action("Wait", Message::CloseGuardDismissed).style(secondary_button_style(radius)),
+    ]
+    .spacing(8)
+    .padding([15, 20]);
+
+    column![
+        container(header).padding([15, 20]),
+        container(body).padding([0, 20]),
+        Space::new().height(15),
+        rule::horizontal(1).style(horizontal_rule_style),
+        footer,
+    ]
+    .into()
+}
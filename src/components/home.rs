@@ -1,28 +1,110 @@
-use iced::widget::{button, column, container, pick_list, progress_bar, row, text, text_input, Space};
+use iced::widget::{
+    button, column, container, pick_list, progress_bar, row, scrollable, text, text_editor, text_input,
+    Space,
+};
 use iced::{Alignment, Element, Fill};
 
-use crate::app::DownloadState;
-use crate::message::{Format, Message};
+use crate::disk;
+use crate::download::progress::{CombinedProgress, TransferStats};
+use crate::download::queue::{Download, DownloadId, DownloadQueue, DownloadState};
+use crate::download::stats::TransferHistory;
+use crate::download::schedule::StartCondition;
+use crate::app::BatchInput;
+use crate::tools::capabilities::Capabilities;
+use crate::message::{Format, Message, StartMode};
 use crate::theme::{
-    pick_list_style, primary_button_style,
-    progress_bar_style, secondary_button_style, text_input_style, danger_button_style,
+    danger_button_style, error_detail_text_style, error_text_style, muted_text_style,
+    pick_list_style, primary_button_style, progress_bar_style, scrollable_style,
+    secondary_button_style, section_style, success_text_style, text_editor_style, text_input_style,
+    warning_text_style,
 };
+use crate::urls;
+use crate::widgets::speed_graph;
+use crate::units::{format_bytes, format_duration, format_percent, format_speed};
 
 fn horizontal_space() -> Space {
     Space::new().width(Fill)
 }
 
-pub fn home_view<'a>(
-    url: &str,
-    format: Format,
-    download_state: &'a DownloadState,
-) -> Element<'a, Message> {
-    let url_input = text_input("Enter video URL...", url)
-        .on_input(Message::UrlChanged)
-        .on_submit(Message::StartDownload)
-        .padding(12)
-        .width(Fill)
-        .style(text_input_style);
+/// Everything the main screen shows, borrowed from the app state.
+pub struct HomeState<'a> {
+    pub url: &'a str,
+    pub format: Format,
+    pub start_mode: StartMode,
+    pub start_time: &'a str,
+    /// Why the last Download press was refused.
+    pub start_error: Option<&'a str>,
+    pub downloads: &'a DownloadQueue,
+    pub preflight_warning: Option<&'a str>,
+    /// Hides Download Anyway for warnings that block the download.
+    pub preflight_blocked: bool,
+    /// The free space check is running.
+    pub checking_space: bool,
+    pub clipboard_offer: Option<&'a str>,
+    pub batch: Option<&'a BatchInput>,
+    /// Used to name the extractor for the URL; `None` hides it.
+    pub capabilities: Option<&'a Capabilities>,
+    pub drop_hover: bool,
+    pub notice: Option<&'a str>,
+    pub new_uploads: usize,
+    /// Download whose file deletion awaits confirmation.
+    pub pending_delete: Option<DownloadId>,
+    /// Corner radius of the current theme.
+    pub radius: f32,
+}
+
+pub fn home_view<'a>(state: HomeState<'a>) -> Element<'a, Message> {
+    let HomeState {
+        url,
+        format,
+        start_mode,
+        start_time,
+        start_error: refused,
+        downloads,
+        preflight_warning,
+        preflight_blocked,
+        checking_space,
+        clipboard_offer,
+        batch,
+        capabilities,
+        drop_hover,
+        notice,
+        new_uploads,
+        pending_delete,
+        radius,
+    } = state;
+
+    let batch_toggle = button(text(if batch.is_some() { "Single" } else { "Batch" }))
+        .on_press(Message::ToggleBatchMode)
+        .padding([10, 16])
+        .style(secondary_button_style(radius));
+
+    let (url_input, url_count): (Element<'a, Message>, usize) = match batch {
+        Some(batch) => batch_section(batch, radius),
+        None => {
+            let analysis = (!url.trim().is_empty()).then(|| urls::analyze(url));
+            let valid = usize::from(matches!(analysis, Some(Ok(_))));
+            (
+                column![
+                    row![
+                        text_input("Enter video URL...", url)
+                            .on_input(Message::UrlChanged)
+                            .on_submit(Message::UrlSubmit)
+                            .padding(12)
+                            .width(Fill)
+                            .style(text_input_style(radius)),
+                        batch_toggle,
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                    url_hint(analysis, url, capabilities),
+                ]
+                .spacing(6)
+                .into(),
+                valid,
+            )
+        }
+    };
 
     let format_picker = pick_list(
         Format::ALL.as_slice(),
@@ -32,54 +114,349 @@ pub fn home_view<'a>(
     .placeholder("Format")
     .padding(10)
     .width(120)
-    .style(pick_list_style);
-
-    let is_downloading = matches!(
-        download_state,
-        DownloadState::Downloading { .. } | DownloadState::PostProcessing { .. }
-    );
-    let has_url = !url.trim().is_empty();
-
-    let download_button = if is_downloading {
-        button(text("Cancel"))
-            .on_press(Message::CancelDownload)
-            .padding([10, 20])
-            .style(danger_button_style)
+    .style(pick_list_style(radius));
+
+    let start_picker = pick_list(
+        StartMode::ALL.as_slice(),
+        Some(start_mode),
+        Message::StartModeSelected,
+    )
+    .padding(10)
+    .width(130)
+    .style(pick_list_style(radius));
+
+    let start_error = match start_mode {
+        StartMode::At => StartCondition::parse(start_time, chrono::Local::now()).err(),
+        _ => None,
+    };
+
+    let mut options_row = row![format_picker, start_picker]
+        .spacing(10)
+        .align_y(Alignment::Center);
+    if start_mode == StartMode::At {
+        options_row = options_row.push(
+            text_input("HH:MM or YYYY-MM-DD HH:MM", start_time)
+                .on_input(Message::StartTimeChanged)
+                .on_submit(Message::UrlSubmit)
+                .padding(10)
+                .width(220)
+                .style(text_input_style(radius)),
+        );
+    }
+
+    let start_error: Element<'a, Message> = match (refused, start_error) {
+        (Some(error), _) => text(error).size(12).style(error_text_style).into(),
+        (None, Some(error)) if !start_time.trim().is_empty() => text(error)
+            .size(12)
+            .style(error_text_style)
+            .into(),
+        _ => column![].into(),
+    };
+
+    let can_start = url_count > 0
+        && !checking_space
+        && (start_mode != StartMode::At || StartCondition::parse(start_time, chrono::Local::now()).is_ok());
+
+    let download_label = if checking_space {
+        "Checking Space...".to_string()
+    } else if batch.is_some() {
+        format!("Download {} {}", url_count, if url_count == 1 { "URL" } else { "URLs" })
     } else {
-        let btn = button(text("Download")).padding([10, 20]).style(primary_button_style);
-        if has_url {
-            btn.on_press(Message::StartDownload)
-        } else {
-            btn
-        }
+        "Download".to_string()
     };
+    let download_button = button(text(download_label))
+        .on_press_maybe(can_start.then_some(Message::StartDownload))
+        .padding([10, 20])
+        .style(primary_button_style(radius));
 
     let advanced_button = button(text("Advanced"))
         .on_press(Message::OpenSettings)
         .padding([10, 20])
-        .style(secondary_button_style);
+        .style(secondary_button_style(radius));
 
-    let buttons_row = row![download_button, horizontal_space(), advanced_button]
+    let subscriptions_label = if new_uploads > 0 {
+        format!(
+            "Subscriptions \u{2022} {} new {}",
+            new_uploads,
+            if new_uploads == 1 { "video" } else { "videos" }
+        )
+    } else {
+        "Subscriptions".to_string()
+    };
+    let subscriptions_button = button(text(subscriptions_label))
+        .on_press(Message::OpenSubscriptions)
+        .padding([10, 20])
+        .style(secondary_button_style(radius));
+
+    let buttons_row = row![download_button, horizontal_space(), subscriptions_button, advanced_button]
         .spacing(10)
         .align_y(Alignment::Center);
 
-    let progress_section: Element<'a, Message> = match download_state {
-        DownloadState::Idle => column![].into(),
-        DownloadState::Downloading {
-            progress,
-            speed,
-            eta,
-            filename,
-        } => {
-            let pbar = progress_bar(0.0..=1.0, *progress)
-                .style(progress_bar_style);
-
-            let percentage = format!("{:.1}%", progress * 100.0);
-            let status_text = format!("{} - ETA: {}", speed, eta);
-
-            let filename_text = text(truncate_filename(filename, 50))
+    let warning_section: Element<'a, Message> = match preflight_warning {
+        Some(warning) => column![
+            text(warning).size(13).style(warning_text_style),
+            row![
+                (!preflight_blocked).then(|| {
+                    button(text("Download Anyway").size(13))
+                        .on_press(Message::ConfirmDownload)
+                        .padding([6, 12])
+                        .style(primary_button_style(radius))
+                }),
+                button(text("Dismiss").size(13))
+                    .on_press(Message::DismissPreflight)
+                    .padding([6, 12])
+                    .style(secondary_button_style(radius)),
+            ]
+            .spacing(10),
+        ]
+        .spacing(8)
+        .into(),
+        None => column![].into(),
+    };
+
+    let clipboard_banner: Element<'a, Message> = match clipboard_offer {
+        Some(offer) => container(
+            row![
+                text(format!("Download {}?", truncate_filename(offer, 50))).size(13),
+                horizontal_space(),
+                button(text("Download").size(13))
+                    .on_press(Message::AcceptClipboardUrl)
+                    .padding([4, 10])
+                    .style(primary_button_style(radius)),
+                button(text("\u{2715}").size(12))
+                    .on_press(Message::DismissClipboardUrl)
+                    .padding([4, 8])
+                    .style(secondary_button_style(radius)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+        )
+        .padding([6, 12])
+        .width(Fill)
+        .style(section_style(radius))
+        .into(),
+        None => column![].into(),
+    };
+
+    let drop_banner: Element<'a, Message> = if drop_hover {
+        container(text("Drop to add links from .url, .webloc, .desktop or text files").size(13))
+            .padding([10, 12])
+            .width(Fill)
+            .center_x(Fill)
+            .style(section_style(radius))
+            .into()
+    } else if let Some(notice) = notice {
+        text(notice)
+            .size(12)
+            .style(muted_text_style)
+            .into()
+    } else {
+        column![].into()
+    };
+
+    let download_list = scrollable(
+        column(downloads.iter().map(|d| download_card(d, pending_delete == Some(d.id), radius)))
+            .spacing(10)
+            .padding(iced::Padding::ZERO.right(12)),
+    )
+    .height(Fill)
+    .style(scrollable_style(radius));
+
+    let content = column![
+        url_input,
+        options_row,
+        start_error,
+        buttons_row,
+        warning_section,
+        clipboard_banner,
+        drop_banner,
+        download_list,
+    ]
+    .spacing(15)
+    .padding(25)
+    .width(Fill);
+
+    container(content)
+        .width(Fill)
+        .height(Fill)
+        .into()
+}
+
+/// One line under the URL field: what the URL points at and which extractor
+/// will likely handle it, or why it cannot be downloaded.
+fn url_hint<'a>(
+    analysis: Option<Result<urls::UrlInfo, String>>,
+    url: &str,
+    capabilities: Option<&Capabilities>,
+) -> Element<'a, Message> {
+    let info = match analysis {
+        None => return column![].into(),
+        Some(Err(error)) => {
+            return text(error)
                 .size(12)
-                .color(iced::Color::from_rgb(0.6, 0.6, 0.6));
+                .style(error_text_style)
+                .into();
+        }
+        Some(Ok(info)) => info,
+    };
+
+    let mut hint = info.kind.to_string();
+    if let Some(capabilities) = capabilities {
+        let extractors = capabilities.extractors_for(&info.host);
+        if extractors.is_empty() {
+            hint.push_str(" \u{2022} generic extractor");
+        } else {
+            hint.push_str(&format!(" \u{2022} {}", extractors.join(", ")));
+        }
+    }
+    if info.normalized != url.trim() {
+        hint.push_str(&format!(" \u{2022} {}", truncate_filename(&info.normalized, 60)));
+    }
+
+    text(hint)
+        .size(12)
+        .style(muted_text_style)
+        .into()
+}
+
+/// The multi-line editor with its import row and per-line validation.
+/// Also returns how many lines hold a valid URL.
+fn batch_section(batch: &BatchInput, radius: f32) -> (Element<'_, Message>, usize) {
+    let lines = urls::parse_batch(&batch.content.text());
+    let valid = lines.iter().filter(|line| line.url.is_ok()).count();
+
+    let problems = column(lines.iter().filter_map(|line| {
+        line.url.as_ref().err().map(|error| {
+            text(format!("Line {}: {}", line.number, error))
+                .size(12)
+                .style(error_text_style)
+                .into()
+        })
+    }))
+    .spacing(2);
+
+    let read_error: Element<'_, Message> = match batch.error {
+        Some(ref error) => text(error)
+            .size(12)
+            .style(error_text_style)
+            .into(),
+        None => column![].into(),
+    };
+
+    let section = column![
+        text_editor(&batch.content)
+            .placeholder("One URL per line, # for comments")
+            .on_action(Message::BatchEdited)
+            .height(120)
+            .padding(10)
+            .style(text_editor_style(radius)),
+        row![
+            text_input("URL list file to import...", &batch.import_file)
+                .on_input(Message::BatchImportFileChanged)
+                .on_submit(Message::ImportBatchFile)
+                .padding(8)
+                .size(13)
+                .width(Fill)
+                .style(text_input_style(radius)),
+            button(text("Import").size(13))
+                .on_press_maybe((!batch.import_file.trim().is_empty()).then_some(Message::ImportBatchFile))
+                .padding([6, 12])
+                .style(secondary_button_style(radius)),
+            button(text("Single").size(13))
+                .on_press(Message::ToggleBatchMode)
+                .padding([6, 12])
+                .style(secondary_button_style(radius)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        read_error,
+        problems,
+    ]
+    .spacing(8)
+    .into();
+
+    (section, valid)
+}
+
+fn download_card(download: &Download, confirm_delete: bool, radius: f32) -> Element<'_, Message> {
+    let title = match download.state {
+        DownloadState::Downloading { ref filename, .. } if !filename.is_empty() => {
+            std::path::Path::new(filename)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| filename.clone())
+        }
+        _ => download.url.clone(),
+    };
+
+    let action = if download.state.is_finished() {
+        button(text("\u{2715}").size(12))
+            .on_press(Message::CancelDownload(download.id))
+            .padding([2, 8])
+            .style(secondary_button_style(radius))
+    } else {
+        button(text("Cancel").size(12))
+            .on_press(Message::CancelDownload(download.id))
+            .padding([4, 10])
+            .style(danger_button_style(radius))
+    };
+
+    let header = row![
+        text(truncate_filename(&title, 45))
+            .size(12)
+            .style(muted_text_style),
+        horizontal_space(),
+        action,
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let body: Element<'_, Message> = match download.state {
+        DownloadState::Queued => text("Queued").size(14).into(),
+        DownloadState::Paused => text("Paused").size(14).style(muted_text_style).into(),
+        DownloadState::Scheduled { ref condition, ref input } => {
+            let id = download.id;
+            column![
+                text(condition.to_string()).size(14),
+                row![
+                    text_input("HH:MM, YYYY-MM-DD HH:MM or idle", input)
+                        .on_input(move |value| Message::ScheduleInputChanged(id, value))
+                        .on_submit(Message::RescheduleDownload(id))
+                        .padding(8)
+                        .size(13)
+                        .width(Fill)
+                        .style(text_input_style(radius)),
+                    button(text("Reschedule").size(13))
+                        .on_press(Message::RescheduleDownload(id))
+                        .padding([6, 12])
+                        .style(secondary_button_style(radius)),
+                    button(text("Start Now").size(13))
+                        .on_press(Message::StartDownloadNow(id))
+                        .padding([6, 12])
+                        .style(primary_button_style(radius)),
+                ]
+                .spacing(8)
+                .align_y(Alignment::Center),
+            ]
+            .spacing(8)
+            .into()
+        }
+        DownloadState::Downloading { ref progress, .. } => {
+            let overall = progress.overall_fraction();
+            let pbar = progress_bar(0.0..=1.0, overall)
+                .style(progress_bar_style(radius));
+
+            let percentage = format_percent(overall);
+            let status_text = progress
+                .current()
+                .map(|stream| transfer_status(&stream.stats))
+                .unwrap_or_else(|| "Starting...".to_string());
+
+            let graph: Element<'_, Message> = if download.history.has_samples() {
+                speed_graph(&download.history).into()
+            } else {
+                column![].into()
+            };
 
             column![
                 pbar,
@@ -89,62 +466,216 @@ pub fn home_view<'a>(
                     text(status_text).size(14),
                 ]
                 .spacing(10),
-                filename_text,
+                stream_bars(progress, radius),
+                graph,
             ]
             .spacing(8)
             .into()
         }
-        DownloadState::PostProcessing { status } => {
+        DownloadState::PostProcessing { ref steps } => {
             let pbar = progress_bar(0.0..=1.0, 1.0)
-                .style(progress_bar_style);
+                .style(progress_bar_style(radius));
 
-            column![pbar, text(status).size(14),].spacing(8).into()
+            let step_list: Element<'_, Message> = if steps.is_empty() {
+                text("Processing...").size(14).into()
+            } else {
+                column(steps.iter().map(|step| {
+                    let label = if step.finished {
+                        format!("{} \u{2713}", step.stage)
+                    } else {
+                        format!("{}\u{2026}", step.stage)
+                    };
+                    text(label).size(14).into()
+                }))
+                .spacing(4)
+                .into()
+            };
+
+            column![pbar, step_list].spacing(8).into()
         }
-        DownloadState::Completed { output_path } => {
+        DownloadState::Completed { ref output_path } => {
             let filename = std::path::Path::new(output_path)
                 .file_name()
                 .map(|n| n.to_string_lossy().to_string())
                 .unwrap_or_else(|| output_path.clone());
 
             column![
-                text("Download Complete!").size(16).color(iced::Color::from_rgb(0.3, 0.7, 0.35)),
-                text(truncate_filename(&filename, 50)).size(12).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                text("Download Complete!").size(16).style(success_text_style),
+                text(truncate_filename(&filename, 50)).size(12).style(muted_text_style),
+                text(transfer_summary(&download.history)).size(12).style(muted_text_style),
+                file_actions(download.id, confirm_delete, radius),
             ]
             .spacing(4)
             .into()
         }
-        DownloadState::Error { message } => {
+        DownloadState::Skipped { ref title, ref reason } => {
             column![
-                text("Error").size(16).color(iced::Color::from_rgb(0.9, 0.25, 0.25)),
-                text(message).size(12).color(iced::Color::from_rgb(0.7, 0.4, 0.4)),
+                text(format!("Skipped \u{2014} {}", reason)).size(16).style(muted_text_style),
+                text(truncate_filename(title, 50)).size(12).style(muted_text_style),
+            ]
+            .spacing(4)
+            .into()
+        }
+        DownloadState::Error { ref message } => {
+            column![
+                text(if disk::is_disk_full(message) { "Disk Full" } else { "Error" })
+                    .size(16)
+                    .style(error_text_style),
+                text(message).size(12).style(error_detail_text_style),
             ]
             .spacing(4)
             .into()
         }
     };
 
-    let content = column![
-        url_input,
-        format_picker,
-        Space::new().height(10),
-        buttons_row,
-        Space::new().height(15),
-        progress_section,
-    ]
-    .spacing(15)
-    .padding(25)
-    .width(Fill);
+    let notice: Element<'_, Message> = match download.notice {
+        Some(ref notice) => text(notice)
+            .size(12)
+            .style(warning_text_style)
+            .into(),
+        None => column![].into(),
+    };
 
-    container(content)
+    container(column![header, body, notice].spacing(8))
+        .padding(12)
         .width(Fill)
-        .height(Fill)
+        .style(section_style(radius))
         .into()
 }
 
+/// Buttons for a downloaded file. Deleting asks for confirmation in place.
+fn file_actions<'a>(id: DownloadId, confirm_delete: bool, radius: f32) -> Element<'a, Message> {
+    let small = |label: &'a str, message: Message| {
+        button(text(label).size(12))
+            .on_press(message)
+            .padding([4, 10])
+            .style(secondary_button_style(radius))
+    };
+
+    let actions = if confirm_delete {
+        row![
+            text("Delete the file from disk?").size(12).style(warning_text_style),
+            horizontal_space(),
+            button(text("Delete").size(12))
+                .on_press(Message::ConfirmDeleteDownload(id))
+                .padding([4, 10])
+                .style(danger_button_style(radius)),
+            small("Keep", Message::CancelDeleteDownload),
+        ]
+    } else {
+        row![
+            small("Open", Message::OpenDownload(id)),
+            small("Show in Folder", Message::RevealDownload(id)),
+            small("Copy Path", Message::CopyDownloadPath(id)),
+            horizontal_space(),
+            button(text("Delete").size(12))
+                .on_press(Message::DeleteDownload(id))
+                .padding([4, 10])
+                .style(danger_button_style(radius)),
+        ]
+    };
+
+    actions.spacing(6).padding([4, 0]).align_y(Alignment::Center).into()
+}
+
+/// One small bar per requested format, shown only when a video is downloaded
+/// as several streams that get merged afterwards.
+fn stream_bars<'a>(progress: &CombinedProgress, radius: f32) -> Element<'a, Message> {
+    if progress.streams.len() < 2 {
+        return column![].into();
+    }
+
+    let bars = progress.streams.iter().enumerate().map(|(i, stream)| {
+        let fraction = if stream.finished { 1.0 } else { stream.stats.fraction() };
+        let status = if stream.finished {
+            "\u{2713}".to_string()
+        } else {
+            format_percent(fraction)
+        };
+
+        row![
+            text(format!("Stream {} ({})", i + 1, stream.format_id))
+                .size(12)
+                .width(110),
+            progress_bar(0.0..=1.0, fraction)
+                .girth(4)
+                .style(progress_bar_style(radius)),
+            text(status).size(12).width(50),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center)
+        .into()
+    });
+
+    column(bars).spacing(4).into()
+}
+
+fn transfer_summary(history: &TransferHistory) -> String {
+    let mut parts = vec![format!("Total {}", format_bytes(history.total_bytes()))];
+    if let Some(average) = history.average_speed() {
+        parts.push(format!("avg {}", format_speed(average)));
+    }
+    if history.peak_speed() > 0.0 {
+        parts.push(format!("peak {}", format_speed(history.peak_speed())));
+    }
+    parts.join(" \u{b7} ")
+}
+
+fn transfer_status(stats: &TransferStats) -> String {
+    let mut parts = Vec::new();
+
+    let size = match stats.total_bytes {
+        Some(total) => format!(
+            "{} / {}",
+            format_bytes(stats.downloaded_bytes),
+            format_bytes(total)
+        ),
+        None => format_bytes(stats.downloaded_bytes),
+    };
+    parts.push(size);
+
+    if let (Some(index), Some(count)) = (stats.fragment_index, stats.fragment_count) {
+        parts.push(format!("frag {}/{}", index, count));
+    }
+
+    parts.push(match stats.speed {
+        Some(speed) => format_speed(speed),
+        None => "Starting...".to_string(),
+    });
+
+    parts.push(match stats.eta {
+        Some(eta) => format!("ETA: {}", format_duration(eta)),
+        None => "ETA: N/A".to_string(),
+    });
+
+    parts.join(" - ")
+}
+
+/// Shortens to at most `max_len` characters, cutting on a character
+/// boundary so non-ASCII titles stay intact.
 fn truncate_filename(filename: &str, max_len: usize) -> String {
-    if filename.len() <= max_len {
+    if filename.chars().count() <= max_len {
         filename.to_string()
     } else {
-        format!("{}...", &filename[..max_len - 3])
+        let kept: String = filename.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", kept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::truncate_filename;
+
+    #[test]
+    fn truncate_keeps_short_names() {
+        assert_eq!(truncate_filename("video.mp4", 20), "video.mp4");
+    }
+
+    #[test]
+    fn truncate_cuts_on_char_boundary() {
+        // Each character is several bytes; a byte cut would split one
+        let title = "日本語のタイトルの動画です";
+        assert_eq!(truncate_filename(title, 8), "日本語のタ...");
+        assert_eq!(truncate_filename("Ça va éàü", 6), "Ça ...");
     }
 }
@@ -1,7 +1,11 @@
+pub mod close_dialog;
 pub mod home;
 pub mod settings_dialog;
+pub mod subscriptions_dialog;
 pub mod title_bar;
 
+pub use close_dialog::*;
 pub use home::*;
 pub use settings_dialog::*;
+pub use subscriptions_dialog::*;
 pub use title_bar::*;
@@ -1,45 +1,155 @@
 use iced::widget::{
-    button, column, container, row, scrollable, text, text_input, toggler, rule, Space,
+    button, column, container, pick_list, row, scrollable, text, text_input, toggler, rule, Space,
 };
 use iced::{Alignment, Element, Fill};
+use std::borrow::Borrow;
+use std::collections::HashMap;
 
-use crate::message::{Message, SettingsField};
+use crate::app::{ArchiveViewer, TemplateEditor, YtDlpStatus};
+use crate::disk::SpaceCheck;
+use crate::download::archive::ArchiveScope;
+use crate::download::bandwidth;
+use crate::download::template::{self, TemplatePreset};
+use crate::message::{Format, Message, PathField, SettingsField};
+use crate::config;
 use crate::settings::AdvancedSettings;
 use crate::theme::{
-    horizontal_rule_style, primary_button_style, scrollable_style, secondary_button_style,
-    section_style, text_input_style, toggler_style,
+    error_detail_text_style, error_text_style, heading_text_style, horizontal_rule_style,
+    invalid_text_input_style,
+    muted_text_style, pick_list_style, primary_button_style, scrollable_style,
+    secondary_button_style, section_style, success_text_style, text_input_style, toggler_style,
+    warning_text_style, UserThemes,
 };
+use crate::tools::ffmpeg::FfmpegInfo;
+use crate::tools::ytdlp::UpdateChannel;
+use crate::tray::CloseAction;
 
 fn horizontal_space() -> Space {
     Space::new().width(Fill)
 }
 
+/// Everything the settings dialog shows besides the settings themselves.
+pub struct SettingsDialogState<'a> {
+    pub persist_enabled: bool,
+    pub ytdlp_status: &'a YtDlpStatus,
+    pub ffmpeg: &'a FfmpegInfo,
+    pub archive: &'a ArchiveViewer,
+    pub themes: &'a UserThemes,
+    pub tray_error: Option<&'a str>,
+    /// Why desktop notifications could not be shown.
+    pub notify_error: Option<&'a str>,
+    pub template: &'a TemplateEditor,
+    /// Why the last Browse… dialog could not be shown.
+    pub picker_error: Option<&'a str>,
+    pub path_errors: &'a HashMap<PathField, String>,
+    /// Corner radius of the current theme.
+    pub radius: f32,
+}
+
 pub fn settings_dialog<'a>(
     settings: &AdvancedSettings,
-    persist_enabled: bool,
+    state: SettingsDialogState<'a>,
 ) -> Element<'a, Message> {
+    let SettingsDialogState {
+        persist_enabled,
+        ytdlp_status,
+        ffmpeg,
+        archive,
+        themes,
+        tray_error,
+        notify_error,
+        template,
+        picker_error,
+        path_errors,
+        radius,
+    } = state;
+    let path_error = |field: PathField| path_errors.get(&field).map(String::as_str);
     let header = text("Advanced Settings").size(20);
 
     let content = scrollable(
         column![
+            // Appearance Section
+            section("Appearance", appearance(settings, themes, radius), radius),
+            rule::horizontal(1).style(horizontal_rule_style),
+            // Tray Section
+            section("Tray", tray(settings, tray_error, radius), radius),
+            rule::horizontal(1).style(horizontal_rule_style),
+            // Notifications Section
+            section("Notifications", notifications(settings, notify_error, radius), radius),
+            rule::horizontal(1).style(horizontal_rule_style),
             // Output Section
             section(
                 "Output",
                 column![
-                    labeled_input(
+                    path_input(
                         "Output Directory",
                         "Leave empty for current directory",
                         &settings.output_directory,
-                        |s| Message::SettingsChanged(SettingsField::OutputDirectory(s)),
+                        PathField::OutputDirectory,
+                        path_error(PathField::OutputDirectory),
+                        radius,
+                    ),
+                    path_input(
+                        "Temporary Directory",
+                        "Leave empty to download in place",
+                        &settings.temp_directory,
+                        PathField::TempDirectory,
+                        path_error(PathField::TempDirectory),
+                        radius,
+                    ),
+                    path_input(
+                        "Subtitle Directory",
+                        "Leave empty to save next to the video",
+                        &settings.subtitle_directory,
+                        PathField::SubtitleDirectory,
+                        path_error(PathField::SubtitleDirectory),
+                        radius,
+                    ),
+                    path_input(
+                        "Thumbnail Directory",
+                        "Leave empty to save next to the video",
+                        &settings.thumbnail_directory,
+                        PathField::ThumbnailDirectory,
+                        path_error(PathField::ThumbnailDirectory),
+                        radius,
+                    ),
+                    template_editor(&settings.filename_template, template, radius),
+                    labeled_pick_list(
+                        "Download Archive",
+                        ArchiveScope::ALL.as_slice(),
+                        settings.download_archive,
+                        |s| Message::SettingsChanged(SettingsField::DownloadArchive(s)),
+                        radius,
+                    ),
+                ]
+                .spacing(12),
+                radius,
+            ),
+            rule::horizontal(1).style(horizontal_rule_style),
+            // Disk Space Section
+            section(
+                "Disk Space",
+                column![
+                    labeled_pick_list(
+                        "Before Downloading",
+                        SpaceCheck::ALL.as_slice(),
+                        settings.disk_space_check,
+                        |c| Message::SettingsChanged(SettingsField::DiskSpaceCheck(c)),
+                        radius,
                     ),
                     labeled_input(
-                        "Filename Template",
-                        "%(title)s.%(ext)s",
-                        &settings.filename_template,
-                        |s| Message::SettingsChanged(SettingsField::FilenameTemplate(s)),
+                        "Keep Free",
+                        "e.g., 1G, 500M",
+                        &settings.disk_space_reserve,
+                        |s| Message::SettingsChanged(SettingsField::DiskSpaceReserve(s)),
+                        radius,
                     ),
+                    text("Single videos are measured before they start. A download counts twice when it needs merging or conversion, since the parts stay on disk until the final file is written.")
+                        .size(12)
+                        .style(muted_text_style),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Quality Section
@@ -51,15 +161,18 @@ pub fn settings_dialog<'a>(
                         "e.g., bestvideo+bestaudio/best",
                         &settings.preferred_quality,
                         |s| Message::SettingsChanged(SettingsField::PreferredQuality(s)),
+                        radius,
                     ),
                     labeled_input(
                         "Preferred Codec",
                         "e.g., h264, vp9",
                         &settings.preferred_codec,
                         |s| Message::SettingsChanged(SettingsField::PreferredCodec(s)),
+                        radius,
                     ),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Subtitles Section
@@ -70,20 +183,24 @@ pub fn settings_dialog<'a>(
                         "Download Subtitles",
                         settings.download_subtitles,
                         |b| Message::SettingsChanged(SettingsField::DownloadSubtitles(b)),
+                        radius,
                     ),
                     labeled_input(
                         "Subtitle Languages",
                         "e.g., en,es,de",
                         &settings.subtitle_languages,
                         |s| Message::SettingsChanged(SettingsField::SubtitleLanguages(s)),
+                        radius,
                     ),
                     labeled_toggle(
                         "Embed Subtitles",
                         settings.embed_subtitles,
                         |b| Message::SettingsChanged(SettingsField::EmbedSubtitles(b)),
+                        radius,
                     ),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Metadata Section
@@ -94,14 +211,17 @@ pub fn settings_dialog<'a>(
                         "Embed Thumbnail",
                         settings.embed_thumbnail,
                         |b| Message::SettingsChanged(SettingsField::EmbedThumbnail(b)),
+                        radius,
                     ),
                     labeled_toggle(
                         "Embed Metadata",
                         settings.embed_metadata,
                         |b| Message::SettingsChanged(SettingsField::EmbedMetadata(b)),
+                        radius,
                     ),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // SponsorBlock Section
@@ -112,15 +232,18 @@ pub fn settings_dialog<'a>(
                         "Enable SponsorBlock",
                         settings.enable_sponsorblock,
                         |b| Message::SettingsChanged(SettingsField::EnableSponsorBlock(b)),
+                        radius,
                     ),
                     labeled_input(
                         "Categories",
                         "sponsor,selfpromo,interaction",
                         &settings.sponsorblock_categories,
                         |s| Message::SettingsChanged(SettingsField::SponsorBlockCategories(s)),
+                        radius,
                     ),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Network Section
@@ -132,66 +255,130 @@ pub fn settings_dialog<'a>(
                         "e.g., socks5://127.0.0.1:1080",
                         &settings.proxy,
                         |s| Message::SettingsChanged(SettingsField::Proxy(s)),
+                        radius,
                     ),
                     labeled_input(
                         "Rate Limit",
                         "e.g., 1M, 500K",
                         &settings.rate_limit,
                         |s| Message::SettingsChanged(SettingsField::RateLimit(s)),
+                        radius,
                     ),
                     labeled_input(
                         "Concurrent Fragments",
                         "1",
                         &settings.concurrent_fragments,
                         |s| Message::SettingsChanged(SettingsField::ConcurrentFragments(s)),
+                        radius,
+                    ),
+                    labeled_input(
+                        "Simultaneous Downloads",
+                        "2",
+                        &settings.max_concurrent_downloads,
+                        |s| Message::SettingsChanged(SettingsField::MaxConcurrentDownloads(s)),
+                        radius,
+                    ),
+                    labeled_input(
+                        "Global Bandwidth Limit",
+                        "Shared by all downloads, e.g., 4M",
+                        &settings.global_rate_limit,
+                        |s| Message::SettingsChanged(SettingsField::GlobalRateLimit(s)),
+                        radius,
+                    ),
+                    labeled_input(
+                        "Bandwidth Schedule",
+                        "e.g., 09:00-17:00=1M; 23:00-07:00=full",
+                        &settings.bandwidth_schedule,
+                        |s| Message::SettingsChanged(SettingsField::BandwidthSchedule(s)),
+                        radius,
                     ),
+                    validation_error(bandwidth::parse_schedule(&settings.bandwidth_schedule).err()),
                 ]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Authentication Section
             section(
                 "Authentication",
-                column![labeled_input(
+                column![path_input(
                     "Cookies File",
                     "Path to cookies.txt",
                     &settings.cookies_file,
-                    |s| Message::SettingsChanged(SettingsField::CookiesFile(s)),
+                    PathField::CookiesFile,
+                    path_error(PathField::CookiesFile),
+                    radius,
                 ),]
                 .spacing(12),
+                radius,
             ),
             rule::horizontal(1).style(horizontal_rule_style),
             // Advanced Section
             section(
                 "Advanced",
                 column![
-                    labeled_input(
+                    path_input(
                         "yt-dlp Path",
                         "yt-dlp",
                         &settings.ytdlp_path,
-                        |s| Message::SettingsChanged(SettingsField::YtDlpPath(s)),
+                        PathField::YtDlpPath,
+                        path_error(PathField::YtDlpPath),
+                        radius,
+                    ),
+                    labeled_input(
+                        "ffmpeg Location",
+                        "Path to ffmpeg or its folder (empty to search PATH)",
+                        &settings.ffmpeg_location,
+                        |s| Message::SettingsChanged(SettingsField::FfmpegLocation(s)),
+                        radius,
                     ),
+                    text(ffmpeg.to_string()).size(12).style(if ffmpeg.is_available() {
+                        muted_text_style
+                    } else {
+                        warning_text_style
+                    }),
                     labeled_input(
                         "JS Runtimes",
                         "e.g., deno,nodejs (for YouTube JS challenges)",
                         &settings.js_runtimes,
                         |s| Message::SettingsChanged(SettingsField::JsRuntimes(s)),
+                        radius,
                     ),
                     labeled_input(
                         "Extra Arguments",
                         "Additional command-line arguments",
                         &settings.extra_arguments,
                         |s| Message::SettingsChanged(SettingsField::ExtraArguments(s)),
+                        radius,
+                    ),
+                    labeled_toggle(
+                        "Offer to Download Copied URLs",
+                        settings.watch_clipboard,
+                        |b| Message::SettingsChanged(SettingsField::WatchClipboard(b)),
+                        radius,
+                    ),
+                    labeled_toggle(
+                        "Show Which Extractor Handles a URL",
+                        settings.identify_extractor,
+                        |b| Message::SettingsChanged(SettingsField::IdentifyExtractor(b)),
+                        radius,
                     ),
                 ]
                 .spacing(12),
+                radius,
             ),
+            rule::horizontal(1).style(horizontal_rule_style),
+            // Download Archive Section
+            section("Download Archive", archive_viewer(settings.download_archive, archive, radius), radius),
+            rule::horizontal(1).style(horizontal_rule_style),
+            // yt-dlp Management Section
+            section("yt-dlp Management", ytdlp_management(settings, ytdlp_status, radius), radius),
         ]
         .spacing(20)
         .padding(20),
     )
     .height(Fill)
-    .style(scrollable_style);
+    .style(scrollable_style(radius));
 
     let persist_toggle = row![
         text("Persist Settings").size(14),
@@ -199,24 +386,24 @@ pub fn settings_dialog<'a>(
         toggler(persist_enabled)
             .on_toggle(Message::TogglePersistence)
             .size(20)
-            .style(toggler_style),
+            .style(toggler_style(radius)),
     ]
     .align_y(Alignment::Center);
 
     let defaults_button = button(text("Defaults").size(14))
         .on_press(Message::ResetDefaults)
         .padding([8, 16])
-        .style(secondary_button_style);
+        .style(secondary_button_style(radius));
 
     let cancel_button = button(text("Cancel").size(14))
         .on_press(Message::CloseSettings)
         .padding([8, 16])
-        .style(secondary_button_style);
+        .style(secondary_button_style(radius));
 
     let save_button = button(text("Save").size(14))
         .on_press(Message::SaveSettings)
         .padding([8, 16])
-        .style(primary_button_style);
+        .style(primary_button_style(radius));
 
     let footer = row![
         persist_toggle,
@@ -231,7 +418,8 @@ pub fn settings_dialog<'a>(
     .align_y(Alignment::Center);
 
     column![
-        container(header).padding([15, 20]),
+        container(column![header, validation_error(picker_error.map(str::to_string))].spacing(4))
+            .padding([15, 20]),
         content,
         rule::horizontal(1).style(horizontal_rule_style),
         footer,
@@ -239,11 +427,302 @@ pub fn settings_dialog<'a>(
     .into()
 }
 
-fn section<'a>(title: &'a str, content: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+fn ytdlp_management<'a>(
+    settings: &AdvancedSettings,
+    status: &YtDlpStatus,
+    radius: f32,
+) -> Element<'a, Message> {
+    let installed = match status.version {
+        Some(ref version) => format!("Installed version: {}", version),
+        None => "Not installed".to_string(),
+    };
+
+    let channel_picker = row![
+        text("Update Channel").size(14),
+        horizontal_space(),
+        pick_list(
+            UpdateChannel::ALL.as_slice(),
+            Some(settings.ytdlp_update_channel),
+            |c| Message::SettingsChanged(SettingsField::YtDlpUpdateChannel(c)),
+        )
+        .padding(8)
+        .width(120)
+        .style(pick_list_style(radius)),
+    ]
+    .align_y(Alignment::Center);
+
+    let idle = !status.busy;
+    let has_file = !status.install_file.trim().is_empty();
+    let installed_copy = status.version.is_some();
+
+    let actions = row![
+        action_button("Install File", (idle && has_file).then_some(Message::InstallYtDlpFromFile), radius),
+        action_button("Download Latest", idle.then_some(Message::InstallYtDlpFromMirror), radius),
+        action_button("Update", (idle && installed_copy).then_some(Message::UpdateYtDlp), radius),
+        action_button("Rollback", (idle && status.has_previous).then_some(Message::RollbackYtDlp), radius),
+    ]
+    .spacing(8);
+
+    let result: Element<'a, Message> = if status.busy {
+        text("Working...").size(12).into()
+    } else {
+        match status.last_result {
+            Some(Ok(ref version)) => text(format!("yt-dlp {} is ready", version))
+                .size(12)
+                .style(success_text_style)
+                .into(),
+            Some(Err(ref message)) => text(message.clone())
+                .size(12)
+                .style(error_detail_text_style)
+                .into(),
+            None => Space::new().into(),
+        }
+    };
+
+    column![
+        labeled_toggle(
+            "Use Managed yt-dlp",
+            settings.use_managed_ytdlp,
+            |b| Message::SettingsChanged(SettingsField::UseManagedYtDlp(b)),
+            radius,
+        ),
+        text(installed).size(13).style(muted_text_style),
+        channel_picker,
+        labeled_input(
+            "Mirror URL",
+            "Leave empty for the channel's GitHub releases",
+            &settings.ytdlp_mirror_url,
+            |s| Message::SettingsChanged(SettingsField::YtDlpMirrorUrl(s)),
+            radius,
+        ),
+        labeled_input(
+            "Install From File",
+            "Path to a yt-dlp binary",
+            &status.install_file,
+            Message::YtDlpInstallFileChanged,
+            radius,
+        ),
+        labeled_toggle(
+            "Allow Files Without SHA2-256SUMS",
+            status.allow_unverified,
+            Message::YtDlpAllowUnverifiedToggled,
+            radius,
+        ),
+        actions,
+        result,
+    ]
+    .spacing(12)
+    .into()
+}
+
+/// Most entries the viewer lists at once; searching narrows it down.
+const ARCHIVE_ROWS: usize = 100;
+
+fn archive_viewer<'a>(scope: ArchiveScope, viewer: &'a ArchiveViewer, radius: f32) -> Element<'a, Message> {
+    if scope == ArchiveScope::Off {
+        return text("Enable the download archive under Output to skip videos that were already downloaded.")
+            .size(13)
+            .style(muted_text_style)
+            .into();
+    }
+
+    let search = viewer.search.trim().to_lowercase();
+    let matches: Vec<&String> = viewer
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| search.is_empty() || entry.to_lowercase().contains(&search))
+        .collect();
+
+    let format_picker: Element<'a, Message> = if scope == ArchiveScope::PerFormat {
+        pick_list(
+            Format::ALL.as_slice(),
+            Some(viewer.format),
+            Message::ArchiveFormatSelected,
+        )
+        .padding(8)
+        .width(110)
+        .style(pick_list_style(radius))
+        .into()
+    } else {
+        Space::new().into()
+    };
+
+    let summary = if search.is_empty() {
+        format!("{} entries", viewer.entries.len())
+    } else {
+        format!("{} of {} entries", matches.len(), viewer.entries.len())
+    };
+
+    let rows = column(matches.iter().take(ARCHIVE_ROWS).map(|entry| {
+        row![
+            text(entry.as_str()).size(13),
+            horizontal_space(),
+            button(text("\u{2715}").size(12))
+                .on_press(Message::RemoveArchiveEntry(entry.to_string()))
+                .padding([2, 8])
+                .style(secondary_button_style(radius)),
+        ]
+        .align_y(Alignment::Center)
+        .into()
+    }))
+    .spacing(4);
+
+    let status: Element<'a, Message> = match viewer.last_result {
+        Some(Ok(ref message)) => text(message.as_str())
+            .size(13)
+            .style(success_text_style)
+            .into(),
+        Some(Err(ref message)) => text(message.as_str())
+            .size(13)
+            .style(error_text_style)
+            .into(),
+        None => Space::new().into(),
+    };
+
+    column![
+        row![
+            text_input("Search entries...", &viewer.search)
+                .on_input(Message::ArchiveSearchChanged)
+                .padding(8)
+                .size(14)
+                .style(text_input_style(radius)),
+            format_picker,
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        text(summary).size(13).style(muted_text_style),
+        rows,
+        row![
+            text_input("Archive file to import...", &viewer.import_file)
+                .on_input(Message::ArchiveImportFileChanged)
+                .padding(8)
+                .size(14)
+                .style(text_input_style(radius)),
+            action_button(
+                "Import",
+                (!viewer.import_file.trim().is_empty()).then_some(Message::ImportArchive),
+                radius,
+            ),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        status,
+    ]
+    .spacing(10)
+    .into()
+}
+
+fn validation_error<'a>(error: Option<String>) -> Element<'a, Message> {
+    match error {
+        Some(message) => text(message)
+            .size(12)
+            .style(error_text_style)
+            .into(),
+        None => Space::new().into(),
+    }
+}
+
+fn action_button<'a>(label: &'a str, on_press: Option<Message>, radius: f32) -> Element<'a, Message> {
+    button(text(label).size(13))
+        .on_press_maybe(on_press)
+        .padding([6, 10])
+        .style(secondary_button_style(radius))
+        .into()
+}
+
+fn appearance<'a>(settings: &AdvancedSettings, themes: &UserThemes, radius: f32) -> Element<'a, Message> {
+    let folder = config::themes_dir()
+        .map(|dir| format!("Theme files (.toml or .json) are read from {}", dir.display()))
+        .unwrap_or_else(|| "Could not determine the themes folder".to_string());
+
+    let mut content = column![
+        labeled_pick_list(
+            "Theme",
+            themes.choices(),
+            settings.theme.clone(),
+            |v| Message::SettingsChanged(SettingsField::Theme(v)),
+            radius,
+        ),
+        text(folder).size(12).style(muted_text_style),
+    ]
+    .spacing(10);
+
+    for error in &themes.errors {
+        content = content.push(text(error.clone()).size(12).style(error_text_style));
+    }
+    content.into()
+}
+
+fn tray<'a>(settings: &AdvancedSettings, error: Option<&str>, radius: f32) -> Element<'a, Message> {
+    let mut content = column![
+        labeled_toggle(
+            "Show Tray Icon",
+            settings.tray_icon,
+            |b| Message::SettingsChanged(SettingsField::TrayIcon(b)),
+            radius,
+        ),
+        labeled_toggle(
+            "Minimize to Tray",
+            settings.minimize_to_tray,
+            |b| Message::SettingsChanged(SettingsField::MinimizeToTray(b)),
+            radius,
+        ),
+        labeled_pick_list(
+            "Close Button",
+            CloseAction::ALL.as_slice(),
+            settings.close_action,
+            |v| Message::SettingsChanged(SettingsField::CloseAction(v)),
+            radius,
+        ),
+    ]
+    .spacing(12);
+
+    // Without a tray the window is minimized or closed as usual.
+    if settings.tray_icon
+        && let Some(error) = error
+    {
+        content = content.push(text(error.to_string()).size(12).style(warning_text_style));
+    }
+    content.into()
+}
+
+fn notifications<'a>(settings: &AdvancedSettings, error: Option<&str>, radius: f32) -> Element<'a, Message> {
+    let mut content = column![
+        labeled_toggle(
+            "Notify When a Download Completes",
+            settings.notify_on_success,
+            |b| Message::SettingsChanged(SettingsField::NotifyOnSuccess(b)),
+            radius,
+        ),
+        labeled_toggle(
+            "Notify When a Download Fails",
+            settings.notify_on_failure,
+            |b| Message::SettingsChanged(SettingsField::NotifyOnFailure(b)),
+            radius,
+        ),
+        labeled_toggle(
+            "Only While the Window Is in the Background",
+            settings.notify_in_background_only,
+            |b| Message::SettingsChanged(SettingsField::NotifyInBackgroundOnly(b)),
+            radius,
+        ),
+    ]
+    .spacing(12);
+
+    if (settings.notify_on_success || settings.notify_on_failure)
+        && let Some(error) = error
+    {
+        content = content.push(text(error.to_string()).size(12).style(warning_text_style));
+    }
+    content.into()
+}
+
+fn section<'a>(title: &'a str, content: impl Into<Element<'a, Message>>, radius: f32) -> Element<'a, Message> {
     column![
-        text(title).size(16).color(iced::Color::from_rgb(0.7, 0.7, 0.7)),
+        text(title).size(16).style(heading_text_style),
         Space::new().height(8),
-        container(content).padding(15).style(section_style),
+        container(content).padding(15).style(section_style(radius)),
     ]
     .spacing(4)
     .into()
@@ -254,23 +733,183 @@ fn labeled_input<'a>(
     placeholder: &'a str,
     value: &str,
     on_change: impl Fn(String) -> Message + 'a,
+    radius: f32,
 ) -> Element<'a, Message> {
     column![
-        text(label).size(13).color(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+        text(label).size(13).style(muted_text_style),
         text_input(placeholder, value)
             .on_input(on_change)
             .padding(10)
             .size(14)
-            .style(text_input_style),
+            .style(text_input_style(radius)),
     ]
     .spacing(4)
     .into()
 }
 
+/// A path field with a Browse… button, with the error from its last check
+/// under it.
+fn path_input<'a>(
+    label: &'a str,
+    placeholder: &'a str,
+    value: &str,
+    field: PathField,
+    error: Option<&'a str>,
+    radius: f32,
+) -> Element<'a, Message> {
+    let valid = error.is_none();
+    let style = move |theme: &iced::Theme, status| {
+        if valid {
+            text_input_style(radius)(theme, status)
+        } else {
+            invalid_text_input_style(radius)(theme, status)
+        }
+    };
+
+    column![
+        text(label).size(13).style(muted_text_style),
+        row![
+            text_input(placeholder, value)
+                .on_input(move |s| Message::SettingsChanged(field.setting(s)))
+                .padding(10)
+                .size(14)
+                .style(style),
+            button(text("Browse\u{2026}").size(13))
+                .on_press(Message::BrowsePath(field))
+                .padding([10, 12])
+                .style(secondary_button_style(radius)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        validation_error(error.map(str::to_string)),
+    ]
+    .spacing(4)
+    .into()
+}
+
+/// The filename template with field chips, presets and a live preview.
+fn template_editor<'a>(value: &str, editor: &'a TemplateEditor, radius: f32) -> Element<'a, Message> {
+    let check = template::validate(value);
+    let valid = check.is_ok();
+    let style = move |theme: &iced::Theme, status| {
+        if valid {
+            text_input_style(radius)(theme, status)
+        } else {
+            invalid_text_input_style(radius)(theme, status)
+        }
+    };
+
+    let chip = |label: &'a str, snippet: Message| {
+        button(text(label).size(12))
+            .on_press(snippet)
+            .padding([3, 8])
+            .style(secondary_button_style(radius))
+    };
+    let fields = row(template::FIELDS
+        .iter()
+        .map(|field| chip(field, Message::InsertTemplateField(field.to_string())).into()))
+    .spacing(6)
+    .wrap();
+    let folders = row(template::FOLDERS
+        .iter()
+        .map(|(label, field)| chip(label, Message::InsertTemplateFolder(field.to_string())).into()))
+    .spacing(6)
+    .wrap();
+
+    let metadata = editor.metadata.clone().unwrap_or_else(template::sample_metadata);
+    let preview: Element<'a, Message> = match check.and_then(|_| template::render(value, &metadata)) {
+        Ok(rendered) => text(format!("Preview: {}", rendered)).size(12).into(),
+        Err(e) => text(e).size(12).style(error_text_style).into(),
+    };
+
+    let source = if editor.probing {
+        "Fetching metadata\u{2026}".to_string()
+    } else if let Some(ref error) = editor.probe_error {
+        error.clone()
+    } else if let Some(ref probed) = editor.metadata {
+        format!(
+            "Using metadata of \"{}\"",
+            probed.get("title").map(String::as_str).unwrap_or("the probed video")
+        )
+    } else {
+        "Using sample metadata; probe a URL to preview a real video".to_string()
+    };
+    let source_style = if editor.probe_error.is_some() && !editor.probing {
+        error_text_style
+    } else {
+        muted_text_style
+    };
+
+    column![
+        text("Filename Template").size(13).style(muted_text_style),
+        row![
+            text_input("%(title)s.%(ext)s", value)
+                .on_input(|s| Message::SettingsChanged(SettingsField::FilenameTemplate(s)))
+                .padding(10)
+                .size(14)
+                .style(style),
+            pick_list(template::PRESETS.as_slice(), None::<TemplatePreset>, Message::TemplatePresetSelected)
+                .placeholder("Presets")
+                .padding(10)
+                .width(110)
+                .style(pick_list_style(radius)),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text("Insert field").size(12).style(muted_text_style),
+        fields,
+        text("Add folder").size(12).style(muted_text_style),
+        folders,
+        preview,
+        row![
+            text_input("Video URL to preview with", &editor.probe_url)
+                .on_input(Message::TemplateProbeUrlChanged)
+                .on_submit(Message::ProbeTemplateMetadata)
+                .padding(8)
+                .size(13)
+                .style(text_input_style(radius)),
+            action_button(
+                "Probe",
+                (!editor.probing && !editor.probe_url.trim().is_empty())
+                    .then_some(Message::ProbeTemplateMetadata),
+                radius,
+            ),
+        ]
+        .spacing(8)
+        .align_y(Alignment::Center),
+        text(source).size(12).style(source_style),
+    ]
+    .spacing(6)
+    .into()
+}
+
+fn labeled_pick_list<'a, T>(
+    label: &'a str,
+    options: impl Borrow<[T]> + 'a,
+    selected: T,
+    on_select: impl Fn(T) -> Message + 'a,
+    radius: f32,
+) -> Element<'a, Message>
+where
+    T: ToString + PartialEq + Clone + 'a,
+{
+    row![
+        text(label).size(14),
+        horizontal_space(),
+        pick_list(options, Some(selected), on_select)
+            .padding(8)
+            .width(120)
+            .style(pick_list_style(radius)),
+    ]
+    .align_y(Alignment::Center)
+    .into()
+}
+
 fn labeled_toggle<'a>(
     label: &'a str,
     value: bool,
     on_toggle: impl Fn(bool) -> Message + 'a,
+    radius: f32,
 ) -> Element<'a, Message> {
     row![
         text(label).size(14),
@@ -278,7 +917,7 @@ fn labeled_toggle<'a>(
         toggler(value)
             .on_toggle(on_toggle)
             .size(20)
-            .style(toggler_style),
+            .style(toggler_style(radius)),
     ]
     .align_y(Alignment::Center)
     .into()
@@ -0,0 +1,180 @@
+use iced::widget::{button, column, container, pick_list, row, rule, scrollable, text, text_input, Space};
+use iced::{Alignment, Element, Fill};
+
+use crate::app::SubscriptionForm;
+use crate::download::subscriptions::ChannelSubscription;
+use crate::message::{Format, Message};
+use crate::theme::{
+    danger_button_style, error_text_style, horizontal_rule_style, muted_text_style, pick_list_style,
+    primary_button_style, scrollable_style, secondary_button_style, section_style,
+    success_text_style, text_input_style,
+};
+
+fn horizontal_space() -> Space {
+    Space::new().width(Fill)
+}
+
+pub fn subscriptions_dialog<'a>(
+    subscriptions: &'a [ChannelSubscription],
+    form: &SubscriptionForm,
+    radius: f32,
+) -> Element<'a, Message> {
+    let header = text("Subscriptions").size(20);
+
+    let add_form = column![
+        text_input("Channel or playlist URL...", &form.url)
+            .on_input(Message::SubscriptionUrlChanged)
+            .on_submit(Message::AddSubscription)
+            .padding(10)
+            .size(14)
+            .style(text_input_style(radius)),
+        row![
+            pick_list(
+                Format::ALL.as_slice(),
+                Some(form.format),
+                Message::SubscriptionFormatSelected,
+            )
+            .padding(8)
+            .width(110)
+            .style(pick_list_style(radius)),
+            text("Check every").size(14),
+            text_input("24", &form.interval)
+                .on_input(Message::SubscriptionIntervalChanged)
+                .on_submit(Message::AddSubscription)
+                .padding(8)
+                .size(14)
+                .width(60)
+                .style(text_input_style(radius)),
+            text("hours").size(14),
+            horizontal_space(),
+            button(text("Subscribe").size(14))
+                .on_press_maybe((!form.url.trim().is_empty()).then_some(Message::AddSubscription))
+                .padding([8, 16])
+                .style(primary_button_style(radius)),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+        match form.error {
+            Some(ref error) => text(error.clone())
+                .size(12)
+                .style(error_text_style),
+            None => text("Uploads that already exist are skipped; only new ones are downloaded.")
+                .size(12)
+                .style(muted_text_style),
+        },
+    ]
+    .spacing(10);
+
+    let list: Element<'a, Message> = if subscriptions.is_empty() {
+        text("No subscriptions yet.")
+            .size(14)
+            .style(muted_text_style)
+            .into()
+    } else {
+        column(subscriptions.iter().map(|s| subscription_row(s, radius)))
+            .spacing(10)
+            .into()
+    };
+
+    let content = scrollable(
+        column![
+            container(add_form).padding(15).style(section_style(radius)),
+            list,
+        ]
+        .spacing(15)
+        .padding(20),
+    )
+    .height(Fill)
+    .style(scrollable_style(radius));
+
+    let footer = row![
+        horizontal_space(),
+        button(text("Close").size(14))
+            .on_press(Message::CloseSubscriptions)
+            .padding([8, 16])
+            .style(secondary_button_style(radius)),
+    ]
+    .padding([15, 20])
+    .align_y(Alignment::Center);
+
+    column![
+        container(header).padding([15, 20]),
+        content,
+        rule::horizontal(1).style(horizontal_rule_style),
+        footer,
+    ]
+    .into()
+}
+
+fn subscription_row(subscription: &ChannelSubscription, radius: f32) -> Element<'_, Message> {
+    let status = if subscription.checking {
+        "Checking\u{2026}".to_string()
+    } else {
+        match subscription.last_checked {
+            Some(checked) => format!(
+                "{} \u{2022} every {}h \u{2022} last checked {}",
+                subscription.format,
+                subscription.check_interval_hours,
+                checked.format("%a %d %b, %H:%M")
+            ),
+            None => format!(
+                "{} \u{2022} every {}h \u{2022} not checked yet",
+                subscription.format, subscription.check_interval_hours
+            ),
+        }
+    };
+
+    let badge: Element<'_, Message> = if subscription.new_uploads > 0 {
+        text(format!(
+            "{} new {}",
+            subscription.new_uploads,
+            if subscription.new_uploads == 1 { "video" } else { "videos" }
+        ))
+        .size(13)
+        .style(success_text_style)
+        .into()
+    } else {
+        Space::new().into()
+    };
+
+    let error: Element<'_, Message> = match subscription.last_error {
+        Some(ref error) => text(error)
+            .size(12)
+            .style(error_text_style)
+            .into(),
+        None => Space::new().into(),
+    };
+
+    let id = subscription.id;
+    container(
+        column![
+            row![
+                text(&subscription.url).size(14),
+                horizontal_space(),
+                badge,
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+            row![
+                text(status).size(12).style(muted_text_style),
+                horizontal_space(),
+                button(text("Check Now").size(12))
+                    .on_press_maybe((!subscription.checking).then_some(Message::CheckSubscription(id)))
+                    .padding([4, 10])
+                    .style(secondary_button_style(radius)),
+                button(text("Remove").size(12))
+                    .on_press(Message::RemoveSubscription(id))
+                    .padding([4, 10])
+                    .style(danger_button_style(radius)),
+            ]
+            .spacing(8)
+            .align_y(Alignment::Center),
+            error,
+        ]
+        .spacing(6),
+    )
+    .padding(12)
+    .width(Fill)
+    .style(section_style(radius))
+    .into()
+}
@@ -4,20 +4,26 @@ use iced::{Alignment, Element, Fill};
 use crate::message::Message;
 use crate::theme::{close_button_style, title_bar_button_style, title_bar_style};
 
-pub fn title_bar<'a>() -> Element<'a, Message> {
+pub fn title_bar<'a>(maximized: bool, radius: f32) -> Element<'a, Message> {
     let title = text("yt-dlgui").size(14);
 
     let minimize_btn = button(text("\u{2212}").size(16))
         .on_press(Message::WindowMinimize)
         .padding([4, 12])
-        .style(title_bar_button_style);
+        .style(title_bar_button_style(radius));
+
+    // Restore shows two overlapping squares, maximize a single one.
+    let maximize_btn = button(text(if maximized { "\u{2750}" } else { "\u{25A1}" }).size(14))
+        .on_press(Message::WindowToggleMaximize)
+        .padding([4, 12])
+        .style(title_bar_button_style(radius));
 
     let close_btn = button(text("\u{2715}").size(14))
         .on_press(Message::WindowClose)
         .padding([4, 12])
-        .style(close_button_style);
+        .style(close_button_style(radius));
 
-    let controls = row![minimize_btn, close_btn].spacing(2);
+    let controls = row![minimize_btn, maximize_btn, close_btn].spacing(2);
 
     let bar_content = row![title, Space::new().width(Fill), controls]
         .align_y(Alignment::Center)
@@ -29,5 +35,6 @@ pub fn title_bar<'a>() -> Element<'a, Message> {
 
     mouse_area(bar)
         .on_press(Message::WindowDrag)
+        .on_double_click(Message::WindowToggleMaximize)
         .into()
 }
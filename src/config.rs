@@ -1,4 +1,5 @@
 use crate::settings::AdvancedSettings;
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
 
@@ -6,6 +7,15 @@ fn config_path() -> Option<PathBuf> {
     dirs::config_dir().map(|p| p.join("yt-dlgui").join("config.json"))
 }
 
+/// Folder scanned for user theme files.
+pub fn themes_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|p| p.join("yt-dlgui").join("themes"))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|p| p.join("yt-dlgui"))
+}
+
 pub fn load_settings() -> Option<AdvancedSettings> {
     let path = config_path()?;
     let content = fs::read_to_string(path).ok()?;
@@ -28,10 +38,57 @@ pub fn save_settings(settings: &AdvancedSettings) -> Result<(), String> {
 }
 
 pub fn delete_settings() -> Result<(), String> {
-    if let Some(path) = config_path() {
-        if path.exists() {
-            fs::remove_file(path).map_err(|e| format!("Failed to delete config file: {}", e))?;
-        }
+    if let Some(path) = config_path()
+        && path.exists()
+    {
+        fs::remove_file(path).map_err(|e| format!("Failed to delete config file: {}", e))?;
     }
     Ok(())
 }
+
+/// Window size and position from the last session.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub width: f32,
+    pub height: f32,
+    /// `None` until the window has been moved, leaving placement to the system.
+    pub x: Option<f32>,
+    pub y: Option<f32>,
+    pub maximized: bool,
+}
+
+impl Default for WindowGeometry {
+    fn default() -> Self {
+        Self {
+            width: 500.0,
+            height: 380.0,
+            x: None,
+            y: None,
+            maximized: false,
+        }
+    }
+}
+
+fn window_path() -> Option<PathBuf> {
+    data_dir().map(|p| p.join("window.json"))
+}
+
+pub fn load_window_geometry() -> WindowGeometry {
+    window_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_window_geometry(geometry: &WindowGeometry) -> Result<(), String> {
+    let path = window_path().ok_or("Could not determine data directory")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(geometry)
+        .map_err(|e| format!("Failed to serialize window geometry: {}", e))?;
+
+    fs::write(path, content).map_err(|e| format!("Failed to write window file: {}", e))
+}
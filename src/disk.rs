@@ -0,0 +1,137 @@
+//! Free space on the filesystems downloads are written to.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// What happens when a download probably won't fit on the disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpaceCheck {
+    Off,
+    #[default]
+    Warn,
+    Block,
+}
+
+impl SpaceCheck {
+    pub const ALL: [SpaceCheck; 3] = [SpaceCheck::Off, SpaceCheck::Warn, SpaceCheck::Block];
+}
+
+impl std::fmt::Display for SpaceCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpaceCheck::Off => write!(f, "Don't check"),
+            SpaceCheck::Warn => write!(f, "Warn"),
+            SpaceCheck::Block => write!(f, "Don't start the download"),
+        }
+    }
+}
+
+/// Closest existing folder to `path`, which may not have been created yet.
+/// Relative paths resolve against the working directory, as yt-dlp does.
+fn existing_ancestor(path: &Path) -> PathBuf {
+    path.ancestors()
+        .map(|p| if p.as_os_str().is_empty() { Path::new(".") } else { p })
+        .find(|p| p.exists())
+        .unwrap_or(Path::new("."))
+        .to_path_buf()
+}
+
+/// Bytes available to this user on the filesystem holding `path`.
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)] // field types differ between platforms
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(existing_ancestor(path).as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    // SAFETY: `path` is NUL-terminated and `stat` is a valid out pointer.
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/// Bytes available to this user on the filesystem holding `path`.
+#[cfg(windows)]
+pub fn free_space(path: &Path) -> Option<u64> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let wide: Vec<u16> = existing_ancestor(path)
+        .as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0))
+        .collect();
+    let mut available = 0u64;
+    // SAFETY: `wide` is NUL-terminated; the unused totals may be null.
+    let ok = unsafe {
+        GetDiskFreeSpaceExW(wide.as_ptr(), &mut available, std::ptr::null_mut(), std::ptr::null_mut())
+    };
+    (ok != 0).then_some(available)
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn free_space(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Whether two folders live on the same filesystem, so they share its free
+/// space. Unknown counts as different, which checks both.
+pub fn same_filesystem(a: &Path, b: &Path) -> bool {
+    let (a, b) = (existing_ancestor(a), existing_ancestor(b));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(a), Ok(b)) => a.dev() == b.dev(),
+            _ => false,
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let root = |p: PathBuf| {
+            std::path::absolute(p)
+                .ok()
+                .and_then(|p| p.components().next().map(|c| c.as_os_str().to_ascii_lowercase()))
+        };
+        matches!((root(a), root(b)), (Some(a), Some(b)) if a == b)
+    }
+}
+
+/// Whether an error from yt-dlp or ffmpeg means the disk filled up.
+pub fn is_disk_full(message: &str) -> bool {
+    const PATTERNS: [&str; 5] = [
+        "no space left on device",
+        "errno 28",
+        "not enough space on the disk",
+        "disk quota exceeded",
+        "disk full",
+    ];
+
+    let message = message.to_lowercase();
+    PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_full_errors_are_recognised() {
+        assert!(is_disk_full("ERROR: unable to write data: [Errno 28] No space left on device"));
+        assert!(is_disk_full("There is not enough space on the disk"));
+        assert!(is_disk_full("OSError: Disk quota exceeded"));
+        assert!(!is_disk_full("ERROR: HTTP Error 403: Forbidden"));
+    }
+
+    #[test]
+    fn free_space_is_found_for_folders_not_created_yet() {
+        let missing = std::env::temp_dir().join("yt-dlgui-missing").join("nested");
+        assert!(free_space(&missing).is_some());
+        assert!(same_filesystem(&missing, &std::env::temp_dir()));
+    }
+}
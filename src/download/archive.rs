@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+use crate::message::Format;
+
+/// Which managed `--download-archive` file downloads are recorded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveScope {
+    #[default]
+    Off,
+    /// One archive shared by every format.
+    Shared,
+    /// A separate archive per format, so a video kept as MP4 can still be
+    /// downloaded again as MP3.
+    PerFormat,
+}
+
+impl ArchiveScope {
+    pub const ALL: [ArchiveScope; 3] = [
+        ArchiveScope::Off,
+        ArchiveScope::Shared,
+        ArchiveScope::PerFormat,
+    ];
+}
+
+impl std::fmt::Display for ArchiveScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveScope::Off => write!(f, "Off"),
+            ArchiveScope::Shared => write!(f, "Shared"),
+            ArchiveScope::PerFormat => write!(f, "Per Format"),
+        }
+    }
+}
+
+/// The archive file downloads of `format` use, if archiving is on.
+pub fn path(scope: ArchiveScope, format: Format) -> Option<PathBuf> {
+    let file = match scope {
+        ArchiveScope::Off => return None,
+        ArchiveScope::Shared => "archive.txt".to_string(),
+        ArchiveScope::PerFormat => format!("archive-{}.txt", format.to_string().to_lowercase()),
+    };
+    config::data_dir().map(|p| p.join(file))
+}
+
+/// Subscriptions always need an archive to tell new uploads apart, so they
+/// fall back to the shared one when archiving is off.
+pub fn subscription_path(scope: ArchiveScope, format: Format) -> Option<PathBuf> {
+    match scope {
+        ArchiveScope::Off => path(ArchiveScope::Shared, format),
+        scope => path(scope, format),
+    }
+}
+
+/// Archive entries are `<extractor> <id>`, with the extractor key lowercased
+/// the way yt-dlp writes it.
+pub fn entry(extractor: &str, id: &str) -> String {
+    format!("{} {}", extractor.to_lowercase(), id)
+}
+
+pub fn load(path: &Path) -> HashSet<String> {
+    entries(path).into_iter().collect()
+}
+
+/// Archive entries in file order.
+pub fn entries(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| parse(&content))
+        .unwrap_or_default()
+}
+
+/// Keeps the lines that look like archive entries: an extractor and an id.
+fn parse(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.split_whitespace().count() == 2)
+        .map(str::to_string)
+        .collect()
+}
+
+pub fn remove(path: &Path, entry: &str) -> Result<(), String> {
+    let remaining: Vec<String> = entries(path).into_iter().filter(|e| e != entry).collect();
+    let mut content = remaining.join("\n");
+    if !content.is_empty() {
+        content.push('\n');
+    }
+    fs::write(path, content).map_err(|e| format!("Failed to write download archive: {}", e))
+}
+
+/// Merges the entries of another archive file, e.g. one kept by a yt-dlp
+/// command line setup. Returns how many entries were new.
+pub fn import(path: &Path, source: &Path) -> Result<usize, String> {
+    let content = fs::read_to_string(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let known = load(path);
+
+    let mut new_entries: Vec<String> = Vec::new();
+    for entry in parse(&content) {
+        if !known.contains(&entry) && !new_entries.contains(&entry) {
+            new_entries.push(entry);
+        }
+    }
+
+    append(path, &new_entries)?;
+    Ok(new_entries.len())
+}
+
+pub fn append(path: &Path, entries: &[String]) -> Result<(), String> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open download archive: {}", e))?;
+    for entry in entries {
+        writeln!(file, "{}", entry).map_err(|e| format!("Failed to write download archive: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-archive-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn entries_keep_extractor_and_id_lines() {
+        assert_eq!(entry("Youtube", "dQw4w9WgXcQ"), "youtube dQw4w9WgXcQ");
+        assert_eq!(
+            parse("youtube abc\n\n  vimeo 123  \n# a comment here\nbroken\n"),
+            ["youtube abc", "vimeo 123"]
+        );
+    }
+
+    #[test]
+    fn import_adds_only_new_entries() {
+        let dir = scratch("import");
+        let archive = dir.join("archive.txt");
+        let source = dir.join("other.txt");
+        append(&archive, &["youtube a".to_string()]).unwrap();
+        fs::write(&source, "youtube a\nyoutube b\nyoutube b\nvimeo 1\n").unwrap();
+
+        assert_eq!(import(&archive, &source), Ok(2));
+        assert_eq!(entries(&archive), ["youtube a", "youtube b", "vimeo 1"]);
+        assert!(import(&archive, &dir.join("missing.txt")).is_err());
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn remove_drops_one_entry() {
+        let dir = scratch("remove");
+        let archive = dir.join("archive.txt");
+        append(&archive, &["youtube a".to_string(), "youtube b".to_string()]).unwrap();
+
+        remove(&archive, "youtube a").unwrap();
+        assert_eq!(fs::read_to_string(&archive).unwrap(), "youtube b\n");
+        remove(&archive, "youtube b").unwrap();
+        assert_eq!(fs::read_to_string(&archive).unwrap(), "");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::units;
+use chrono::NaiveTime;
+
+/// A time-of-day window with its own global bandwidth budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleRule {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// Bytes per second, `None` for full speed.
+    pub limit: Option<u64>,
+}
+
+impl ScheduleRule {
+    /// Whether `time` falls in this window. Windows may wrap past midnight,
+    /// e.g. `22:00-06:00`.
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            time >= self.start && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Parses a yt-dlp style rate such as `500K`, `1.5M` or `2000` into bytes per
+/// second. Units are binary, as in `--limit-rate`.
+pub fn parse_rate(rate: &str) -> Option<u64> {
+    units::parse_size(rate)
+}
+
+/// Parses a schedule like `09:00-17:00=1M; 23:00-07:00=full`. Entries that do
+/// not parse are reported by their position.
+pub fn parse_schedule(schedule: &str) -> Result<Vec<ScheduleRule>, String> {
+    schedule
+        .split([';', '\n'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .enumerate()
+        .map(|(i, entry)| {
+            parse_rule(entry).ok_or_else(|| format!("Invalid schedule entry {}: \"{}\"", i + 1, entry))
+        })
+        .collect()
+}
+
+fn parse_rule(entry: &str) -> Option<ScheduleRule> {
+    let (window, limit) = entry.split_once('=')?;
+    let (start, end) = window.split_once('-')?;
+
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").ok()?;
+    let end = NaiveTime::parse_from_str(end.trim(), "%H:%M").ok()?;
+
+    let limit = match limit.trim().to_ascii_lowercase().as_str() {
+        "" | "0" | "full" | "unlimited" => None,
+        rate => Some(parse_rate(rate)?),
+    };
+
+    Some(ScheduleRule { start, end, limit })
+}
+
+/// The global budget in effect at `time`: the first matching schedule rule,
+/// otherwise the default global limit.
+pub fn budget_at(rules: &[ScheduleRule], default: Option<u64>, time: NaiveTime) -> Option<u64> {
+    rules
+        .iter()
+        .find(|rule| rule.contains(time))
+        .map(|rule| rule.limit)
+        .unwrap_or(default)
+}
+
+/// Splits a budget evenly between `active` downloads.
+pub fn share(budget: Option<u64>, active: usize) -> Option<u64> {
+    budget.map(|b| (b / active.max(1) as u64).max(1))
+}
+
+/// Whether a running download should be restarted to apply a new limit.
+/// Small changes are ignored because a restart costs a new extraction.
+pub fn needs_restart(current: Option<u64>, target: Option<u64>) -> bool {
+    match (current, target) {
+        (None, None) => false,
+        (Some(current), Some(target)) => {
+            let difference = current.abs_diff(target) as f64;
+            difference / current.max(target) as f64 > 0.25
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn schedule_entries_and_limits() {
+        let rules = parse_schedule("09:00-17:00=1M; 23:00-07:00=full\n12:00-13:00 = 500K").unwrap();
+        assert_eq!(
+            rules,
+            vec![
+                ScheduleRule { start: time(9, 0), end: time(17, 0), limit: Some(1024 * 1024) },
+                ScheduleRule { start: time(23, 0), end: time(7, 0), limit: None },
+                ScheduleRule { start: time(12, 0), end: time(13, 0), limit: Some(500 * 1024) },
+            ]
+        );
+        assert_eq!(parse_schedule("").unwrap(), []);
+    }
+
+    #[test]
+    fn bad_entries_are_reported_by_position() {
+        assert_eq!(
+            parse_schedule("09:00-17:00=1M; 9-17=fast").unwrap_err(),
+            "Invalid schedule entry 2: \"9-17=fast\""
+        );
+    }
+
+    #[test]
+    fn budget_follows_the_first_matching_window() {
+        let rules = parse_schedule("09:00-17:00=1M; 22:00-06:00=full").unwrap();
+        let default = Some(100);
+        assert_eq!(budget_at(&rules, default, time(9, 0)), Some(1024 * 1024));
+        assert_eq!(budget_at(&rules, default, time(17, 0)), default);
+        // Wraps past midnight
+        assert_eq!(budget_at(&rules, default, time(23, 30)), None);
+        assert_eq!(budget_at(&rules, default, time(5, 59)), None);
+    }
+
+    #[test]
+    fn shares_and_restarts() {
+        assert_eq!(share(Some(900), 3), Some(300));
+        assert_eq!(share(Some(2), 0), Some(2));
+        assert_eq!(share(Some(1), 4), Some(1));
+        assert_eq!(share(None, 2), None);
+
+        assert!(!needs_restart(Some(1000), Some(800)));
+        assert!(needs_restart(Some(1000), Some(500)));
+        assert!(needs_restart(None, Some(500)));
+        assert!(!needs_restart(None, None));
+    }
+}
@@ -1,4 +1,12 @@
+pub mod archive;
+pub mod bandwidth;
 pub mod progress;
+pub mod queue;
+pub mod schedule;
+pub mod space;
+pub mod stats;
+pub mod subscriptions;
 pub mod task;
+pub mod template;
 
 pub use task::*;
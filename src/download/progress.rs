@@ -6,92 +6,302 @@ struct YtDlpProgress {
     status: Option<String>,
     #[serde(rename = "_percent_str")]
     percent_str: Option<String>,
-    #[serde(rename = "_speed_str")]
-    speed_str: Option<String>,
-    #[serde(rename = "_eta_str")]
-    eta_str: Option<String>,
     filename: Option<String>,
-    #[serde(rename = "downloaded_bytes")]
-    downloaded_bytes: Option<u64>,
-    #[serde(rename = "total_bytes")]
-    total_bytes: Option<u64>,
-    #[serde(rename = "total_bytes_estimate")]
-    total_bytes_estimate: Option<u64>,
+    downloaded_bytes: Option<f64>,
+    total_bytes: Option<f64>,
+    total_bytes_estimate: Option<f64>,
+    speed: Option<f64>,
+    eta: Option<f64>,
+    elapsed: Option<f64>,
+    fragment_index: Option<u64>,
+    fragment_count: Option<u64>,
+    postprocessor: Option<String>,
+}
+
+/// Numeric snapshot of a running transfer, as reported by yt-dlp.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TransferStats {
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    /// Bytes per second.
+    pub speed: Option<f64>,
+    /// Seconds remaining.
+    pub eta: Option<u64>,
+    pub fragment_index: Option<u64>,
+    pub fragment_count: Option<u64>,
+    /// Seconds since this transfer started.
+    pub elapsed: Option<f64>,
+    /// Completion reported by yt-dlp, used when neither the size nor the
+    /// fragment count is known.
+    pub reported_fraction: Option<f32>,
+}
+
+impl TransferStats {
+    /// Completion between 0.0 and 1.0.
+    pub fn fraction(&self) -> f32 {
+        if let Some(total) = self.total_bytes.filter(|t| *t > 0) {
+            return (self.downloaded_bytes as f64 / total as f64).clamp(0.0, 1.0) as f32;
+        }
+        if let (Some(index), Some(count)) = (self.fragment_index, self.fragment_count)
+            && count > 0
+        {
+            return (index as f32 / count as f32).clamp(0.0, 1.0);
+        }
+        self.reported_fraction.unwrap_or(0.0)
+    }
+}
+
+impl From<&YtDlpProgress> for TransferStats {
+    fn from(progress: &YtDlpProgress) -> Self {
+        let total = progress.total_bytes.or(progress.total_bytes_estimate);
+
+        Self {
+            downloaded_bytes: progress.downloaded_bytes.unwrap_or(0.0).max(0.0) as u64,
+            total_bytes: total.filter(|t| *t > 0.0).map(|t| t as u64),
+            speed: progress.speed.filter(|s| s.is_finite() && *s >= 0.0),
+            eta: progress.eta.filter(|e| e.is_finite() && *e >= 0.0).map(|e| e as u64),
+            fragment_index: progress.fragment_index,
+            fragment_count: progress.fragment_count,
+            elapsed: progress.elapsed,
+            reported_fraction: parse_percent(progress.percent_str.as_deref()),
+        }
+    }
+}
+
+/// Prefix of the lines produced by [`DOWNLOAD_TEMPLATE`].
+const PROGRESS_PREFIX: &str = "[progress] ";
+
+/// Prefix of the lines produced by [`POSTPROCESS_TEMPLATE`].
+const POSTPROCESS_PREFIX: &str = "[postprocess] ";
+
+/// `--progress-template` for downloads: the id of the format being
+/// downloaded followed by yt-dlp's progress dict as JSON.
+pub const DOWNLOAD_TEMPLATE: &str = "download:[progress] %(info.format_id)s %(progress)j";
+
+/// `--progress-template` for post-processors: the progress dict as JSON,
+/// carrying `status` and the `postprocessor` key.
+pub const POSTPROCESS_TEMPLATE: &str = "postprocess:[postprocess] %(progress)j";
+
+/// A post-processing step, identified by yt-dlp's post-processor key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PostProcessStage {
+    Merging,
+    ExtractingAudio,
+    Converting,
+    EmbeddingSubtitles,
+    EmbeddingThumbnail,
+    WritingMetadata,
+    RemovingSponsorSegments,
+    FixingUp,
+    Other(String),
+}
+
+impl PostProcessStage {
+    /// Maps a post-processor key such as `FFmpegMerger`. Bookkeeping steps
+    /// that are not worth showing return `None`.
+    pub fn from_key(key: &str) -> Option<Self> {
+        let stage = match key {
+            "MoveFiles" | "MoveFilesAfterDownload" => return None,
+            "FFmpegMerger" | "Merger" => PostProcessStage::Merging,
+            "FFmpegExtractAudio" | "ExtractAudio" => PostProcessStage::ExtractingAudio,
+            "FFmpegVideoConvertor" | "FFmpegVideoRemuxer" | "VideoConvertor" | "VideoRemuxer" => {
+                PostProcessStage::Converting
+            }
+            "FFmpegEmbedSubtitle" | "EmbedSubtitle" => PostProcessStage::EmbeddingSubtitles,
+            "EmbedThumbnail" => PostProcessStage::EmbeddingThumbnail,
+            "FFmpegMetadata" | "Metadata" => PostProcessStage::WritingMetadata,
+            "SponsorBlock" | "ModifyChapters" => PostProcessStage::RemovingSponsorSegments,
+            key if key.starts_with("FFmpegFixup") => PostProcessStage::FixingUp,
+            key => PostProcessStage::Other(key.to_string()),
+        };
+        Some(stage)
+    }
+}
+
+impl std::fmt::Display for PostProcessStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostProcessStage::Merging => write!(f, "Merging formats"),
+            PostProcessStage::ExtractingAudio => write!(f, "Extracting audio"),
+            PostProcessStage::Converting => write!(f, "Converting video"),
+            PostProcessStage::EmbeddingSubtitles => write!(f, "Embedding subtitles"),
+            PostProcessStage::EmbeddingThumbnail => write!(f, "Embedding thumbnail"),
+            PostProcessStage::WritingMetadata => write!(f, "Writing metadata"),
+            PostProcessStage::RemovingSponsorSegments => write!(f, "Removing sponsor segments"),
+            PostProcessStage::FixingUp => write!(f, "Fixing up container"),
+            PostProcessStage::Other(key) => write!(f, "{}", key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostProcessStep {
+    pub stage: PostProcessStage,
+    pub finished: bool,
+}
+
+/// Records a post-processor status update, starting a new step or marking
+/// the running one as finished.
+pub fn apply_postprocess(steps: &mut Vec<PostProcessStep>, stage: PostProcessStage, finished: bool) {
+    match steps.iter_mut().rev().find(|s| s.stage == stage && !s.finished) {
+        Some(step) => step.finished = finished,
+        None => steps.push(PostProcessStep { stage, finished }),
+    }
+}
+
+/// Per-stream and overall progress of one video, which may be downloaded as
+/// several formats (e.g. `bestvideo+bestaudio`) before they are merged.
+#[derive(Debug, Clone, Default)]
+pub struct CombinedProgress {
+    pub streams: Vec<StreamProgress>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StreamProgress {
+    pub format_id: String,
+    pub stats: TransferStats,
+    pub finished: bool,
+}
+
+impl CombinedProgress {
+    /// Starts tracking a new video with the given requested formats.
+    pub fn set_formats(&mut self, format_ids: Vec<String>) {
+        self.streams = format_ids
+            .into_iter()
+            .map(|format_id| StreamProgress {
+                format_id,
+                ..Default::default()
+            })
+            .collect();
+    }
+
+    pub fn update(&mut self, format_id: Option<&str>, stats: TransferStats) {
+        let index = self.stream_index(format_id);
+        // Streams are downloaded one after another, so earlier ones are done.
+        for stream in &mut self.streams[..index] {
+            stream.finished = true;
+        }
+        self.streams[index].stats = stats;
+    }
+
+    pub fn finish(&mut self, format_id: Option<&str>) {
+        let index = self.stream_index(format_id);
+        let stream = &mut self.streams[index];
+        stream.finished = true;
+        if let Some(total) = stream.stats.total_bytes {
+            stream.stats.downloaded_bytes = total;
+        }
+    }
+
+    /// Bytes downloaded so far across all streams.
+    pub fn downloaded_bytes(&self) -> u64 {
+        self.streams.iter().map(|s| s.stats.downloaded_bytes).sum()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        !self.streams.is_empty() && self.streams.iter().all(|s| s.finished)
+    }
+
+    /// The stream currently being downloaded.
+    pub fn current(&self) -> Option<&StreamProgress> {
+        self.streams
+            .iter()
+            .find(|s| !s.finished)
+            .or(self.streams.last())
+    }
+
+    /// Completion of all streams, weighted by their size. Streams whose size
+    /// is not known yet are assumed to be as large as the average known one.
+    pub fn overall_fraction(&self) -> f32 {
+        let known: Vec<u64> = self
+            .streams
+            .iter()
+            .filter_map(|s| s.stats.total_bytes)
+            .collect();
+
+        if known.is_empty() {
+            let done: f32 = self
+                .streams
+                .iter()
+                .map(|s| if s.finished { 1.0 } else { s.stats.fraction() })
+                .sum();
+            return done / self.streams.len().max(1) as f32;
+        }
+
+        let average = known.iter().sum::<u64>() as f64 / known.len() as f64;
+        let (done, total) = self.streams.iter().fold((0.0, 0.0), |(done, total), s| {
+            let size = s.stats.total_bytes.map(|t| t as f64).unwrap_or(average);
+            let fraction = if s.finished { 1.0 } else { f64::from(s.stats.fraction()) };
+            (done + size * fraction, total + size)
+        });
+
+        if total > 0.0 {
+            (done / total).clamp(0.0, 1.0) as f32
+        } else {
+            0.0
+        }
+    }
+
+    fn stream_index(&mut self, format_id: Option<&str>) -> usize {
+        let found = format_id.and_then(|id| self.streams.iter().position(|s| s.format_id == id));
+        match found {
+            Some(index) => index,
+            None => {
+                if let Some(index) = self.streams.iter().position(|s| !s.finished) {
+                    return index;
+                }
+                // A format we were not told about; track it as its own stream.
+                self.streams.push(StreamProgress {
+                    format_id: format_id.unwrap_or_default().to_string(),
+                    ..Default::default()
+                });
+                self.streams.len() - 1
+            }
+        }
+    }
 }
 
 pub fn parse_progress_line(line: &str) -> Option<DownloadProgress> {
-    // Try to parse JSON progress output
-    if let Ok(progress) = serde_json::from_str::<YtDlpProgress>(line) {
-        let status = progress.status.as_deref().unwrap_or("");
+    if let Some(rest) = line.strip_prefix(PROGRESS_PREFIX) {
+        let (format_id, json) = rest.split_once(' ')?;
+        let progress = serde_json::from_str::<YtDlpProgress>(json).ok()?;
+        let format_id = (format_id != "NA").then(|| format_id.to_string());
 
-        match status {
+        return match progress.status.as_deref().unwrap_or("") {
             "downloading" => {
-                let percent = parse_percent(&progress);
-                let speed = progress
-                    .speed_str
-                    .clone()
-                    .unwrap_or_else(|| "N/A".to_string())
-                    .trim()
-                    .to_string();
-                let eta = progress
-                    .eta_str
-                    .clone()
-                    .unwrap_or_else(|| "N/A".to_string())
-                    .trim()
-                    .to_string();
                 let filename = progress
                     .filename
                     .clone()
                     .unwrap_or_else(|| "Unknown".to_string());
 
-                return Some(DownloadProgress::Downloading {
-                    progress: percent,
-                    speed,
-                    eta,
+                Some(DownloadProgress::Downloading {
+                    format_id,
+                    stats: TransferStats::from(&progress),
                     filename,
-                });
-            }
-            "finished" => {
-                return Some(DownloadProgress::PostProcessing {
-                    status: "Processing...".to_string(),
-                });
+                })
             }
-            "error" => {
-                return Some(DownloadProgress::Error("Download failed".to_string()));
-            }
-            _ => {}
-        }
+            "finished" => Some(DownloadProgress::StreamFinished { format_id }),
+            "error" => Some(DownloadProgress::Error("Download failed".to_string())),
+            _ => None,
+        };
     }
 
-    // Handle non-JSON output for post-processing messages
-    let line_lower = line.to_lowercase();
-    if line_lower.contains("[merger]")
-        || line_lower.contains("[ffmpeg]")
-        || line_lower.contains("merging")
-    {
-        return Some(DownloadProgress::PostProcessing {
-            status: "Merging formats...".to_string(),
-        });
-    }
-
-    if line_lower.contains("[embedthumbnail]") {
-        return Some(DownloadProgress::PostProcessing {
-            status: "Embedding thumbnail...".to_string(),
-        });
+    if let Some(rest) = line.strip_prefix(POSTPROCESS_PREFIX) {
+        let progress = serde_json::from_str::<YtDlpProgress>(rest).ok()?;
+        let stage = PostProcessStage::from_key(progress.postprocessor.as_deref()?)?;
+        let finished = progress.status.as_deref() == Some("finished");
+        return Some(DownloadProgress::PostProcessing { stage, finished });
     }
 
-    if line_lower.contains("[metadata]") {
-        return Some(DownloadProgress::PostProcessing {
-            status: "Writing metadata...".to_string(),
-        });
+    if let Some(format_ids) = parse_format_selection(line) {
+        return Some(DownloadProgress::FormatsSelected(format_ids));
     }
 
-    if line_lower.contains("[sponsorblock]") {
-        return Some(DownloadProgress::PostProcessing {
-            status: "Removing sponsor segments...".to_string(),
-        });
+    if let Some(skipped) = parse_skipped(line) {
+        return Some(skipped);
     }
 
+    let line_lower = line.to_lowercase();
     if line_lower.contains("error") || line_lower.contains("error:") {
         return Some(DownloadProgress::Error(line.to_string()));
     }
@@ -99,25 +309,227 @@ pub fn parse_progress_line(line: &str) -> Option<DownloadProgress> {
     None
 }
 
-fn parse_percent(progress: &YtDlpProgress) -> f32 {
-    // Try to parse from percent_str first
-    if let Some(ref pct) = progress.percent_str {
-        let cleaned = pct.trim().trim_end_matches('%');
-        if let Ok(val) = cleaned.parse::<f32>() {
-            return val / 100.0;
+/// Parses yt-dlp's format selection announcement, e.g.
+/// `[info] dQw4w9WgXcQ: Downloading 1 format(s): 137+140`.
+fn parse_format_selection(line: &str) -> Option<Vec<String>> {
+    if !line.starts_with("[info]") {
+        return None;
+    }
+    let (_, formats) = line.split_once("format(s): ")?;
+    let ids: Vec<String> = formats
+        .trim()
+        .split('+')
+        .filter(|id| !id.is_empty())
+        .map(|id| id.to_string())
+        .collect();
+    (!ids.is_empty()).then_some(ids)
+}
+
+/// Recognises yt-dlp's notices for videos it does not download again, e.g.
+/// `[download] Some Title has already been recorded in the archive`.
+fn parse_skipped(line: &str) -> Option<DownloadProgress> {
+    let rest = line.strip_prefix("[download] ")?;
+    if let Some(title) = rest.strip_suffix(" has already been recorded in the archive") {
+        return Some(DownloadProgress::Skipped {
+            title: title.to_string(),
+            reason: "Already in the download archive".to_string(),
+        });
+    }
+    if let Some(filename) = rest.strip_suffix(" has already been downloaded") {
+        return Some(DownloadProgress::Skipped {
+            title: filename.to_string(),
+            reason: "Already downloaded".to_string(),
+        });
+    }
+    None
+}
+
+fn parse_percent(percent_str: Option<&str>) -> Option<f32> {
+    let cleaned = percent_str?.trim().trim_end_matches('%');
+    cleaned.parse::<f32>().ok().map(|val| (val / 100.0).clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn download_lines_carry_numeric_stats() {
+        let line = r#"[progress] 137 {"status": "downloading", "filename": "Clip.f137.mp4", "downloaded_bytes": 2500000, "total_bytes": null, "total_bytes_estimate": 10000000.0, "speed": 1250000.5, "eta": 6.2, "elapsed": 2.0, "fragment_index": 3, "fragment_count": 12, "_percent_str": " 25.0%"}"#;
+        let Some(DownloadProgress::Downloading { format_id, stats, filename }) = parse_progress_line(line) else {
+            panic!("not parsed as downloading");
+        };
+
+        assert_eq!(format_id.as_deref(), Some("137"));
+        assert_eq!(filename, "Clip.f137.mp4");
+        assert_eq!(
+            stats,
+            TransferStats {
+                downloaded_bytes: 2_500_000,
+                total_bytes: Some(10_000_000),
+                speed: Some(1_250_000.5),
+                eta: Some(6),
+                fragment_index: Some(3),
+                fragment_count: Some(12),
+                elapsed: Some(2.0),
+                reported_fraction: Some(0.25),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_format_and_values_are_left_out() {
+        let line = r#"[progress] NA {"status": "downloading", "downloaded_bytes": 10, "speed": null, "eta": -1}"#;
+        let Some(DownloadProgress::Downloading { format_id, stats, .. }) = parse_progress_line(line) else {
+            panic!("not parsed as downloading");
+        };
+        assert_eq!(format_id, None);
+        assert_eq!(stats.speed, None);
+        assert_eq!(stats.eta, None);
+        assert_eq!(stats.total_bytes, None);
+    }
+
+    #[test]
+    fn fraction_prefers_bytes_then_fragments_then_percent() {
+        let stats = TransferStats {
+            downloaded_bytes: 30,
+            total_bytes: Some(120),
+            fragment_index: Some(9),
+            fragment_count: Some(10),
+            reported_fraction: Some(0.5),
+            ..Default::default()
+        };
+        assert_eq!(stats.fraction(), 0.25);
+
+        let stats = TransferStats { total_bytes: None, ..stats };
+        assert_eq!(stats.fraction(), 0.9);
+
+        let stats = TransferStats { fragment_count: None, ..stats };
+        assert_eq!(stats.fraction(), 0.5);
+        assert_eq!(TransferStats::default().fraction(), 0.0);
+    }
+
+    #[test]
+    fn finished_and_error_lines() {
+        assert!(matches!(
+            parse_progress_line(r#"[progress] 140 {"status": "finished"}"#),
+            Some(DownloadProgress::StreamFinished { format_id: Some(id) }) if id == "140"
+        ));
+        assert!(matches!(
+            parse_progress_line("ERROR: [youtube] abc: Video unavailable"),
+            Some(DownloadProgress::Error(message)) if message.contains("Video unavailable")
+        ));
+        assert!(parse_progress_line("[youtube] abc: Downloading webpage").is_none());
+        assert!(parse_progress_line("[progress] 140 not json").is_none());
+    }
+
+    #[test]
+    fn format_selection_lists_each_stream() {
+        assert!(matches!(
+            parse_progress_line("[info] dQw4w9WgXcQ: Downloading 1 format(s): 137+140"),
+            Some(DownloadProgress::FormatsSelected(ids)) if ids == ["137", "140"]
+        ));
+        assert!(parse_format_selection("[download] Downloading 1 format(s): 18").is_none());
+    }
+
+    fn stats(downloaded: u64, total: Option<u64>) -> TransferStats {
+        TransferStats {
+            downloaded_bytes: downloaded,
+            total_bytes: total,
+            ..Default::default()
         }
     }
 
-    // Fall back to calculating from bytes
-    let downloaded = progress.downloaded_bytes.unwrap_or(0) as f64;
-    let total = progress
-        .total_bytes
-        .or(progress.total_bytes_estimate)
-        .unwrap_or(0) as f64;
+    #[test]
+    fn combined_progress_weighs_streams_by_size() {
+        let mut progress = CombinedProgress::default();
+        progress.set_formats(vec!["137".to_string(), "140".to_string()]);
+
+        progress.update(Some("137"), stats(50, Some(300)));
+        // The audio size isn't known yet, so it counts as the average: 300
+        assert_eq!(progress.overall_fraction(), 50.0 / 600.0);
+        assert_eq!(progress.current().unwrap().format_id, "137");
+
+        // Starting the second stream finishes the first
+        progress.update(Some("140"), stats(25, Some(100)));
+        assert!(progress.streams[0].finished);
+        assert_eq!(progress.overall_fraction(), 325.0 / 400.0);
+        assert_eq!(progress.downloaded_bytes(), 50 + 25);
+
+        progress.finish(Some("140"));
+        assert!(progress.is_finished());
+        assert_eq!(progress.streams[1].stats.downloaded_bytes, 100);
+    }
+
+    #[test]
+    fn unannounced_formats_become_their_own_stream() {
+        let mut progress = CombinedProgress::default();
+        progress.update(Some("18"), stats(1, Some(2)));
+        progress.finish(Some("18"));
+        progress.update(Some("251"), stats(0, None));
+
+        let ids: Vec<_> = progress.streams.iter().map(|s| s.format_id.as_str()).collect();
+        assert_eq!(ids, ["18", "251"]);
+        assert!(!progress.is_finished());
+    }
+
+    #[test]
+    fn postprocess_lines_name_the_stage() {
+        let line = r#"[postprocess] {"status": "started", "postprocessor": "FFmpegMerger"}"#;
+        assert!(matches!(
+            parse_progress_line(line),
+            Some(DownloadProgress::PostProcessing { stage: PostProcessStage::Merging, finished: false })
+        ));
+        let line = r#"[postprocess] {"status": "finished", "postprocessor": "FFmpegExtractAudio"}"#;
+        assert!(matches!(
+            parse_progress_line(line),
+            Some(DownloadProgress::PostProcessing { stage: PostProcessStage::ExtractingAudio, finished: true })
+        ));
+        // Moving files is bookkeeping, not a step worth showing
+        assert!(parse_progress_line(r#"[postprocess] {"status": "started", "postprocessor": "MoveFiles"}"#).is_none());
+    }
+
+    #[test]
+    fn stage_keys() {
+        assert_eq!(PostProcessStage::from_key("FFmpegFixupM3u8"), Some(PostProcessStage::FixingUp));
+        assert_eq!(PostProcessStage::from_key("SponsorBlock"), Some(PostProcessStage::RemovingSponsorSegments));
+        assert_eq!(PostProcessStage::from_key("EmbedThumbnail"), Some(PostProcessStage::EmbeddingThumbnail));
+        assert_eq!(
+            PostProcessStage::from_key("FFmpegSplitChapters"),
+            Some(PostProcessStage::Other("FFmpegSplitChapters".to_string()))
+        );
+        assert_eq!(PostProcessStage::from_key("MoveFilesAfterDownload"), None);
+    }
+
+    #[test]
+    fn steps_finish_the_running_one() {
+        let mut steps = Vec::new();
+        apply_postprocess(&mut steps, PostProcessStage::Merging, false);
+        apply_postprocess(&mut steps, PostProcessStage::Merging, true);
+        apply_postprocess(&mut steps, PostProcessStage::WritingMetadata, false);
+        assert_eq!(
+            steps,
+            vec![
+                PostProcessStep { stage: PostProcessStage::Merging, finished: true },
+                PostProcessStep { stage: PostProcessStage::WritingMetadata, finished: false },
+            ]
+        );
+    }
+
+    #[test]
+    fn skipped_videos_say_why() {
+        let Some(DownloadProgress::Skipped { title, reason }) =
+            parse_progress_line("[download] Some Title has already been recorded in the archive")
+        else {
+            panic!("not parsed as skipped");
+        };
+        assert_eq!(title, "Some Title");
+        assert_eq!(reason, "Already in the download archive");
 
-    if total > 0.0 {
-        (downloaded / total) as f32
-    } else {
-        0.0
+        assert!(matches!(
+            parse_skipped("[download] /videos/Clip.mp4 has already been downloaded"),
+            Some(DownloadProgress::Skipped { title, .. }) if title == "/videos/Clip.mp4"
+        ));
+        assert!(parse_skipped("[download] Destination: Clip.mp4").is_none());
     }
 }
@@ -0,0 +1,416 @@
+use tokio::sync::mpsc;
+
+use chrono::{DateTime, Local};
+
+use super::progress::{apply_postprocess, CombinedProgress, PostProcessStep};
+use super::schedule::{ScheduledDownload, StartCondition};
+use super::stats::TransferHistory;
+use crate::message::{DownloadProgress, Format};
+use crate::urls;
+
+/// Stable identifier of a queue entry.
+pub type DownloadId = u64;
+
+/// Identifier of one yt-dlp process. A download gets a new run every time it
+/// is (re)started, so messages from a superseded process can be ignored.
+pub type RunId = u64;
+
+#[derive(Debug, Clone, Default)]
+pub enum DownloadState {
+    #[default]
+    Queued,
+    /// Deferred until `condition` is met. `input` is the schedule editor's
+    /// text, which may not parse yet.
+    Scheduled {
+        condition: StartCondition,
+        input: String,
+    },
+    /// Held back until resumed; a stopped transfer continues from its
+    /// partial file.
+    Paused,
+    Downloading {
+        progress: CombinedProgress,
+        filename: String,
+    },
+    PostProcessing {
+        steps: Vec<PostProcessStep>,
+    },
+    Completed {
+        output_path: String,
+    },
+    Skipped {
+        title: String,
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+impl DownloadState {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DownloadState::Completed { .. } | DownloadState::Skipped { .. } | DownloadState::Error { .. }
+        )
+    }
+}
+
+/// The yt-dlp process currently working on a download.
+#[derive(Debug)]
+pub struct ActiveRun {
+    pub id: RunId,
+    pub cancel: mpsc::Sender<()>,
+    /// Bytes per second passed via `--limit-rate`, if any.
+    pub rate_limit: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct Download {
+    pub id: DownloadId,
+    pub url: String,
+    pub format: Format,
+    pub state: DownloadState,
+    pub history: TransferHistory,
+    pub notice: Option<String>,
+    /// Enqueued by a channel subscription; recorded in the download archive.
+    pub from_subscription: bool,
+    pub run: Option<ActiveRun>,
+}
+
+impl Download {
+    pub fn is_running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// Stops the running process, if any. Its completion message will no
+    /// longer match this download and is dropped.
+    pub fn stop(&mut self) {
+        if let Some(run) = self.run.take() {
+            let _ = run.cancel.try_send(());
+        }
+    }
+
+    fn to_scheduled(&self, condition: StartCondition) -> ScheduledDownload {
+        ScheduledDownload {
+            url: self.url.clone(),
+            format: self.format,
+            condition,
+            from_subscription: self.from_subscription,
+        }
+    }
+
+    pub fn apply(&mut self, progress: DownloadProgress) {
+        match progress {
+            DownloadProgress::FormatsSelected(format_ids) => {
+                let mut progress = CombinedProgress::default();
+                progress.set_formats(format_ids);
+                self.state = DownloadState::Downloading {
+                    progress,
+                    filename: String::new(),
+                };
+            }
+            DownloadProgress::Downloading {
+                format_id,
+                stats,
+                filename: name,
+            } => {
+                if !matches!(self.state, DownloadState::Downloading { .. }) {
+                    self.state = DownloadState::Downloading {
+                        progress: CombinedProgress::default(),
+                        filename: String::new(),
+                    };
+                }
+                if let DownloadState::Downloading {
+                    ref mut progress,
+                    ref mut filename,
+                } = self.state
+                {
+                    let speed = stats.speed;
+                    progress.update(format_id.as_deref(), stats);
+                    *filename = name;
+                    self.history.record(speed, progress.downloaded_bytes());
+                }
+            }
+            DownloadProgress::StreamFinished { format_id } => {
+                if let DownloadState::Downloading { ref mut progress, .. } = self.state {
+                    progress.finish(format_id.as_deref());
+                    if progress.is_finished() {
+                        self.state = DownloadState::PostProcessing { steps: Vec::new() };
+                    }
+                }
+            }
+            DownloadProgress::PostProcessing { stage, finished } => {
+                if !matches!(self.state, DownloadState::PostProcessing { .. }) {
+                    self.state = DownloadState::PostProcessing { steps: Vec::new() };
+                }
+                if let DownloadState::PostProcessing { ref mut steps } = self.state {
+                    apply_postprocess(steps, stage, finished);
+                }
+            }
+            DownloadProgress::Skipped { title, reason } => {
+                self.state = DownloadState::Skipped { title, reason };
+            }
+            DownloadProgress::Error(msg) => {
+                self.state = DownloadState::Error { message: msg };
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct DownloadQueue {
+    items: Vec<Download>,
+    next_id: u64,
+}
+
+impl DownloadQueue {
+    pub fn push(&mut self, url: String, format: Format) -> DownloadId {
+        self.push_with_state(url, format, DownloadState::Queued)
+    }
+
+    pub fn push_scheduled(&mut self, url: String, format: Format, condition: StartCondition) -> DownloadId {
+        let input = condition.editor_text();
+        self.push_with_state(url, format, DownloadState::Scheduled { condition, input })
+    }
+
+    /// Adds a download saved by [`Self::scheduled`] in an earlier session.
+    pub fn restore(&mut self, scheduled: ScheduledDownload) -> DownloadId {
+        let id = self.push_scheduled(scheduled.url, scheduled.format, scheduled.condition);
+        if let Some(download) = self.get_mut(id) {
+            download.from_subscription = scheduled.from_subscription;
+        }
+        id
+    }
+
+    fn push_with_state(&mut self, url: String, format: Format, state: DownloadState) -> DownloadId {
+        let id = self.next_id();
+        self.items.push(Download {
+            id,
+            url,
+            format,
+            state,
+            history: TransferHistory::default(),
+            notice: None,
+            from_subscription: false,
+            run: None,
+        });
+        id
+    }
+
+    /// Hands out ids for both downloads and runs so they never collide.
+    pub fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// Whether `url` is anywhere in the list, in any state.
+    pub fn contains_url(&self, url: &str) -> bool {
+        let url = urls::normalize(url);
+        self.items.iter().any(|d| urls::normalize(&d.url) == url)
+    }
+
+    pub fn get_mut(&mut self, id: DownloadId) -> Option<&mut Download> {
+        self.items.iter_mut().find(|d| d.id == id)
+    }
+
+    pub fn by_run_mut(&mut self, run: RunId) -> Option<&mut Download> {
+        self.items
+            .iter_mut()
+            .find(|d| d.run.as_ref().is_some_and(|r| r.id == run))
+    }
+
+    pub fn remove(&mut self, id: DownloadId) -> Option<Download> {
+        let index = self.items.iter().position(|d| d.id == id)?;
+        Some(self.items.remove(index))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Download> {
+        self.items.iter()
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.items.iter().filter(|d| d.is_running()).count()
+    }
+
+    /// Entries waiting for a free slot, oldest first.
+    pub fn waiting(&self) -> impl Iterator<Item = DownloadId> + '_ {
+        self.items
+            .iter()
+            .filter(|d| !d.is_running() && matches!(d.state, DownloadState::Queued))
+            .map(|d| d.id)
+    }
+
+    /// Deferred entries in the form they are persisted.
+    pub fn scheduled(&self) -> Vec<ScheduledDownload> {
+        self.items
+            .iter()
+            .filter_map(|d| match d.state {
+                DownloadState::Scheduled { condition, .. } => Some(d.to_scheduled(condition)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// What [`Self::scheduled`] returns after [`Self::suspend_all`], so it
+    /// can be saved before anything is stopped.
+    pub fn suspended(&self) -> Vec<ScheduledDownload> {
+        self.items
+            .iter()
+            .filter_map(|d| match d.state {
+                DownloadState::Scheduled { condition, .. } => Some(d.to_scheduled(condition)),
+                ref state if !state.is_finished() => Some(d.to_scheduled(StartCondition::WhenIdle)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn has_scheduled(&self) -> bool {
+        self.items
+            .iter()
+            .any(|d| matches!(d.state, DownloadState::Scheduled { .. }))
+    }
+
+    /// Moves scheduled entries whose time has come into the queue. Only one
+    /// "when idle" entry is released at a time so they run one after another.
+    /// Returns whether anything was released.
+    pub fn release_due(&mut self, now: DateTime<Local>) -> bool {
+        let mut idle = self.running_count() == 0 && self.waiting().next().is_none();
+        let mut released = false;
+
+        for download in &mut self.items {
+            let DownloadState::Scheduled { condition, .. } = download.state else {
+                continue;
+            };
+            if condition.is_due(now, idle) {
+                download.state = DownloadState::Queued;
+                idle = false;
+                released = true;
+            }
+        }
+        released
+    }
+
+    /// Stops every running download and turns everything unfinished into a
+    /// "when idle" entry, so it is persisted with the schedule and picks up
+    /// again next launch. Returns the runs that were stopped.
+    pub fn suspend_all(&mut self) -> Vec<RunId> {
+        let mut stopped = Vec::new();
+        for download in &mut self.items {
+            if let Some(run) = download.run.as_ref() {
+                stopped.push(run.id);
+            }
+            download.stop();
+            if !download.state.is_finished() && !matches!(download.state, DownloadState::Scheduled { .. }) {
+                download.state = DownloadState::Scheduled {
+                    condition: StartCondition::WhenIdle,
+                    input: StartCondition::WhenIdle.editor_text(),
+                };
+            }
+        }
+        stopped
+    }
+
+    /// Stops running downloads and holds back queued ones.
+    pub fn pause_all(&mut self) {
+        for download in &mut self.items {
+            if download.is_running() || matches!(download.state, DownloadState::Queued) {
+                download.stop();
+                download.state = DownloadState::Paused;
+            }
+        }
+    }
+
+    pub fn resume_all(&mut self) {
+        for download in &mut self.items {
+            if matches!(download.state, DownloadState::Paused) {
+                download.state = DownloadState::Queued;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    fn state(queue: &DownloadQueue, id: DownloadId) -> &DownloadState {
+        &queue.iter().find(|d| d.id == id).unwrap().state
+    }
+
+    #[test]
+    fn due_entries_are_released_and_idle_ones_one_at_a_time() {
+        let now = Local::now();
+        let mut queue = DownloadQueue::default();
+        let in_an_hour = StartCondition::At(now + TimeDelta::hours(1));
+        let later = queue.push_scheduled("https://a.example/1".into(), Format::Mp4, in_an_hour);
+        let idle_a = queue.push_scheduled("https://a.example/2".into(), Format::Mp4, StartCondition::WhenIdle);
+        let idle_b = queue.push_scheduled("https://a.example/3".into(), Format::Mp4, StartCondition::WhenIdle);
+
+        assert!(queue.release_due(now));
+        assert!(matches!(state(&queue, later), DownloadState::Scheduled { .. }));
+        assert!(matches!(state(&queue, idle_a), DownloadState::Queued));
+        assert!(matches!(state(&queue, idle_b), DownloadState::Scheduled { .. }));
+
+        // The released entry is still waiting, so the queue is not idle
+        assert!(!queue.release_due(now));
+
+        assert!(queue.release_due(now + TimeDelta::hours(2)));
+        assert!(matches!(state(&queue, later), DownloadState::Queued));
+        assert_eq!(queue.scheduled().len(), 1);
+        assert!(queue.has_scheduled());
+    }
+
+    #[test]
+    fn suspending_keeps_unfinished_entries_for_next_launch() {
+        let mut queue = DownloadQueue::default();
+        let queued = queue.push("https://a.example/1".into(), Format::Mp4);
+        let done = queue.push("https://a.example/2".into(), Format::Mp4);
+        queue.get_mut(done).unwrap().state = DownloadState::Completed {
+            output_path: "/tmp/video.mp4".into(),
+        };
+
+        let saved = queue.suspended();
+        assert!(queue.suspend_all().is_empty());
+        assert_eq!(queue.scheduled(), saved);
+        assert!(matches!(
+            state(&queue, queued),
+            DownloadState::Scheduled {
+                condition: StartCondition::WhenIdle,
+                ..
+            }
+        ));
+        assert!(matches!(state(&queue, done), DownloadState::Completed { .. }));
+    }
+
+    #[test]
+    fn restored_entries_keep_their_subscription() {
+        let mut queue = DownloadQueue::default();
+        let id = queue.restore(ScheduledDownload {
+            url: "https://a.example/1".into(),
+            format: Format::Mp4,
+            condition: StartCondition::WhenIdle,
+            from_subscription: true,
+        });
+        assert!(queue.get_mut(id).unwrap().from_subscription);
+
+        let saved = queue.suspended();
+        assert_eq!(saved.len(), 1);
+        assert!(saved[0].from_subscription);
+    }
+
+    #[test]
+    fn pausing_holds_back_queued_entries() {
+        let mut queue = DownloadQueue::default();
+        let id = queue.push("https://a.example/1".into(), Format::Mp4);
+
+        queue.pause_all();
+        assert!(matches!(state(&queue, id), DownloadState::Paused));
+        assert_eq!(queue.waiting().count(), 0);
+
+        queue.resume_all();
+        assert_eq!(queue.waiting().collect::<Vec<_>>(), [id]);
+        assert!(queue.contains_url("https://A.example/1/#top"));
+    }
+}
@@ -0,0 +1,168 @@
+use chrono::{DateTime, Days, Local, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::message::Format;
+
+/// When a deferred download may start.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartCondition {
+    At(DateTime<Local>),
+    /// As soon as nothing else is downloading or queued.
+    WhenIdle,
+}
+
+impl StartCondition {
+    /// Parses what the user typed: `idle`, a time of day (`02:00`, the next
+    /// time the clock reads that) or a full `2024-05-01 02:00`.
+    pub fn parse(input: &str, now: DateTime<Local>) -> Result<Self, String> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("idle") || input.eq_ignore_ascii_case("when idle") {
+            return Ok(StartCondition::WhenIdle);
+        }
+
+        if let Ok(time) = NaiveTime::parse_from_str(input, "%H:%M") {
+            return next_occurrence(time, now)
+                .map(StartCondition::At)
+                .ok_or_else(|| format!("{} does not exist in the local time zone", input));
+        }
+
+        let datetime = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M")
+            .map_err(|_| format!("Invalid start time \"{}\", expected HH:MM or YYYY-MM-DD HH:MM", input))?;
+        datetime
+            .and_local_timezone(Local)
+            .earliest()
+            .map(StartCondition::At)
+            .ok_or_else(|| format!("{} does not exist in the local time zone", input))
+    }
+
+    pub fn is_due(&self, now: DateTime<Local>, idle: bool) -> bool {
+        match self {
+            StartCondition::At(start) => now >= *start,
+            StartCondition::WhenIdle => idle,
+        }
+    }
+
+    /// Text for the schedule editor, the inverse of [`StartCondition::parse`].
+    pub fn editor_text(&self) -> String {
+        match self {
+            StartCondition::At(start) => start.format("%Y-%m-%d %H:%M").to_string(),
+            StartCondition::WhenIdle => "idle".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for StartCondition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartCondition::At(start) if start.date_naive() == Local::now().date_naive() => {
+                write!(f, "Starts at {}", start.format("%H:%M"))
+            }
+            StartCondition::At(start) => write!(f, "Starts {}", start.format("%a %d %b, %H:%M")),
+            StartCondition::WhenIdle => write!(f, "Starts when idle"),
+        }
+    }
+}
+
+fn next_occurrence(time: NaiveTime, now: DateTime<Local>) -> Option<DateTime<Local>> {
+    let today = now.date_naive().and_time(time);
+    let date = if today > now.naive_local() {
+        today
+    } else {
+        today.checked_add_days(Days::new(1))?
+    };
+    date.and_local_timezone(Local).earliest()
+}
+
+/// A deferred download as stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledDownload {
+    pub url: String,
+    pub format: Format,
+    pub condition: StartCondition,
+    /// Found by a subscription, so it goes through the subscription archive.
+    #[serde(default)]
+    pub from_subscription: bool,
+}
+
+fn schedule_path() -> Option<PathBuf> {
+    config::data_dir().map(|p| p.join("scheduled.json"))
+}
+
+pub fn load() -> Vec<ScheduledDownload> {
+    schedule_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(scheduled: &[ScheduledDownload]) -> Result<(), String> {
+    let path = schedule_path().ok_or("Could not determine data directory")?;
+
+    if scheduled.is_empty() {
+        if path.exists() {
+            fs::remove_file(path).map_err(|e| format!("Failed to delete schedule file: {}", e))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(scheduled)
+        .map_err(|e| format!("Failed to serialize schedule: {}", e))?;
+
+    fs::write(path, content).map_err(|e| format!("Failed to write schedule file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn time_of_day_is_the_next_time_the_clock_reads_it() {
+        let now = at(1, 12, 0);
+        assert_eq!(StartCondition::parse("18:30", now), Ok(StartCondition::At(at(1, 18, 30))));
+        assert_eq!(StartCondition::parse(" 02:00 ", now), Ok(StartCondition::At(at(2, 2, 0))));
+        // The current minute has already begun, so it means tomorrow
+        assert_eq!(StartCondition::parse("12:00", now), Ok(StartCondition::At(at(2, 12, 0))));
+    }
+
+    #[test]
+    fn full_dates_and_idle() {
+        let now = at(1, 12, 0);
+        assert_eq!(StartCondition::parse("2024-05-03 08:15", now), Ok(StartCondition::At(at(3, 8, 15))));
+        assert_eq!(StartCondition::parse("When Idle", now), Ok(StartCondition::WhenIdle));
+        assert!(StartCondition::parse("tomorrow", now).unwrap_err().contains("expected HH:MM"));
+        assert!(StartCondition::parse("25:00", now).is_err());
+    }
+
+    #[test]
+    fn entries_saved_before_subscriptions_load() {
+        let saved = r#"[{"url": "https://a.example/1", "format": "Mp4", "condition": "when_idle"}]"#;
+        let scheduled: Vec<ScheduledDownload> = serde_json::from_str(saved).unwrap();
+        assert_eq!(scheduled.len(), 1);
+        assert!(!scheduled[0].from_subscription);
+    }
+
+    #[test]
+    fn due_and_editor_text() {
+        let start = StartCondition::At(at(1, 18, 30));
+        assert!(!start.is_due(at(1, 18, 29), true));
+        assert!(start.is_due(at(1, 18, 30), false));
+        assert!(StartCondition::WhenIdle.is_due(at(1, 0, 0), true));
+        assert!(!StartCondition::WhenIdle.is_due(at(1, 0, 0), false));
+
+        assert_eq!(start.editor_text(), "2024-05-01 18:30");
+        assert_eq!(StartCondition::parse(&start.editor_text(), at(1, 12, 0)), Ok(start));
+    }
+}
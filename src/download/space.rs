@@ -0,0 +1,100 @@
+//! Checks that a download fits on the disk before it starts.
+
+use std::path::PathBuf;
+
+use super::task::DownloadTask;
+use crate::disk;
+use crate::units::format_bytes;
+
+/// How much of the download's size is on disk at once when yt-dlp merges or
+/// converts: the downloaded parts stay until the final file is written.
+const MERGE_FACTOR: f64 = 2.0;
+
+/// Returns a warning when the download probably won't fit, keeping `reserve`
+/// bytes free. Downloads whose size can't be found out are let through.
+pub async fn check(task: DownloadTask, reserve: u64) -> Option<String> {
+    let size = task.estimate_size().await.ok().flatten()?;
+    shortfall(&task, size, reserve)
+}
+
+fn shortfall(task: &DownloadTask, size: u64, reserve: u64) -> Option<String> {
+    let working = if task.requires_ffmpeg() {
+        (size as f64 * MERGE_FACTOR) as u64
+    } else {
+        size
+    };
+
+    let destinations = task.destinations();
+    let folder = |wanted: &str| {
+        destinations
+            .iter()
+            .find(|(kind, _)| *kind == wanted)
+            .map(|(_, dir)| dir.clone())
+    };
+    // yt-dlp runs in the working directory when no output folder is set
+    let home = folder("home")
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    // With a temporary folder on another drive, the parts and merging stay
+    // there and only the finished file moves to the output folder
+    let needs = match folder("temp") {
+        Some(temp) if !disk::same_filesystem(&temp, &home) => vec![(temp, working), (home, size)],
+        _ => vec![(home, working)],
+    };
+
+    needs.into_iter().find_map(|(dir, needed)| {
+        let free = disk::free_space(&dir)?;
+        (free < needed.saturating_add(reserve)).then(|| {
+            let reserve_note = if reserve > 0 {
+                format!(" while keeping {} free", format_bytes(reserve))
+            } else {
+                String::new()
+            };
+            format!(
+                "This download needs about {} on the drive holding {}, but only {} is available{}.",
+                format_bytes(needed),
+                dir.display(),
+                format_bytes(free),
+                reserve_note
+            )
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::AdvancedSettings;
+    use crate::message::Format;
+
+    fn task(format: Format) -> DownloadTask {
+        let settings = AdvancedSettings {
+            output_directory: std::env::temp_dir().to_string_lossy().to_string(),
+            temp_directory: String::new(),
+            embed_thumbnail: false,
+            embed_metadata: false,
+            embed_subtitles: false,
+            enable_sponsorblock: false,
+            preferred_quality: String::new(),
+            ..Default::default()
+        };
+        DownloadTask::new("https://example.com/v".to_string(), format, settings)
+    }
+
+    #[test]
+    fn small_downloads_fit() {
+        assert_eq!(shortfall(&task(Format::Default), 1, 0), None);
+    }
+
+    #[test]
+    fn merging_doubles_the_space_needed() {
+        let gib = 1024 * 1024 * 1024;
+        let plain = shortfall(&task(Format::Default), gib, u64::MAX).unwrap();
+        assert!(plain.starts_with(&format!("This download needs about {} ", format_bytes(gib))));
+        assert!(plain.contains(&format!("while keeping {} free", format_bytes(u64::MAX))));
+
+        let merged = shortfall(&task(Format::Mp4), gib, u64::MAX).unwrap();
+        assert!(merged.starts_with(&format!("This download needs about {} ", format_bytes(2 * gib))));
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How far back the speed graph reaches.
+pub const GRAPH_WINDOW: Duration = Duration::from_secs(120);
+
+/// Throughput samples and totals for the current download.
+#[derive(Debug, Clone, Default)]
+pub struct TransferHistory {
+    samples: VecDeque<(Instant, f64)>,
+    started: Option<Instant>,
+    last_update: Option<Instant>,
+    peak_speed: f64,
+    total_bytes: u64,
+}
+
+impl TransferHistory {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Records the current speed (bytes/sec) and the bytes downloaded so far
+    /// across all streams.
+    pub fn record(&mut self, speed: Option<f64>, downloaded: u64) {
+        let now = Instant::now();
+        self.started.get_or_insert(now);
+        self.last_update = Some(now);
+        self.total_bytes = self.total_bytes.max(downloaded);
+
+        if let Some(speed) = speed {
+            self.peak_speed = self.peak_speed.max(speed);
+            self.samples.push_back((now, speed));
+        }
+
+        while let Some(&(time, _)) = self.samples.front() {
+            if now.duration_since(time) > GRAPH_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Samples within [`GRAPH_WINDOW`] as (seconds before the latest sample,
+    /// bytes/sec), oldest first.
+    pub fn graph_points(&self) -> Vec<(f32, f64)> {
+        let Some(&(latest, _)) = self.samples.back() else {
+            return Vec::new();
+        };
+        self.samples
+            .iter()
+            .map(|&(time, speed)| (latest.duration_since(time).as_secs_f32(), speed))
+            .collect()
+    }
+
+    pub fn has_samples(&self) -> bool {
+        !self.samples.is_empty()
+    }
+
+    pub fn peak_speed(&self) -> f64 {
+        self.peak_speed
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// Total bytes over the time spent downloading.
+    pub fn average_speed(&self) -> Option<f64> {
+        let elapsed = self.last_update?.duration_since(self.started?).as_secs_f64();
+        (elapsed > 0.0).then(|| self.total_bytes as f64 / elapsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_keeps_peak_and_total() {
+        let mut history = TransferHistory::default();
+        history.record(Some(100.0), 1_000);
+        history.record(Some(400.0), 5_000);
+        // A second stream restarts its own count; the total doesn't go back
+        history.record(Some(200.0), 3_000);
+        history.record(None, 6_000);
+
+        assert_eq!(history.peak_speed(), 400.0);
+        assert_eq!(history.total_bytes(), 6_000);
+
+        let points = history.graph_points();
+        let speeds: Vec<f64> = points.iter().map(|&(_, speed)| speed).collect();
+        assert_eq!(speeds, [100.0, 400.0, 200.0]);
+        assert!(points.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+        assert_eq!(points.last().unwrap().0, 0.0);
+    }
+
+    #[test]
+    fn reset_forgets_everything() {
+        let mut history = TransferHistory::default();
+        history.record(Some(100.0), 1_000);
+        history.reset();
+        assert!(!history.has_samples());
+        assert_eq!(history.total_bytes(), 0);
+        assert_eq!(history.average_speed(), None);
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::{DateTime, Local, TimeDelta};
+use serde::{Deserialize, Serialize};
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::archive;
+use crate::config;
+use crate::message::Format;
+use crate::tools::hide_window;
+use crate::urls;
+
+/// How many of the most recent uploads a check looks at.
+const CHECK_DEPTH: u32 = 50;
+
+/// A channel or playlist that is checked periodically for new uploads.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelSubscription {
+    pub id: u64,
+    pub url: String,
+    pub format: Format,
+    pub check_interval_hours: u32,
+    pub last_checked: Option<DateTime<Local>>,
+    /// Whether the uploads that existed when subscribing were recorded.
+    #[serde(default)]
+    pub seeded: bool,
+    /// Uploads enqueued by the last check that the user has not seen yet.
+    #[serde(skip)]
+    pub new_uploads: usize,
+    #[serde(skip)]
+    pub checking: bool,
+    #[serde(skip)]
+    pub last_error: Option<String>,
+}
+
+impl ChannelSubscription {
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        !self.checking
+            && self.last_checked.is_none_or(|checked| {
+                now - checked >= TimeDelta::hours(i64::from(self.check_interval_hours.max(1)))
+            })
+    }
+}
+
+/// An upload found by a check that is not in the download archive.
+#[derive(Debug, Clone)]
+pub struct NewUpload {
+    pub url: String,
+    pub archive_entry: String,
+}
+
+/// Lists the most recent uploads of `url` with a flat-playlist probe and
+/// returns the ones not in the archive, oldest first.
+///
+/// A subscription's first check only records what already exists, so
+/// subscribing to a channel does not download its back catalogue.
+///
+/// `access` holds the proxy, cookies and similar options downloads use, so
+/// members-only or region-locked channels can be listed too.
+pub fn check(
+    ytdlp: &Path,
+    url: &str,
+    access: &[OsString],
+    archive_path: &Path,
+    seed: bool,
+) -> Result<Vec<NewUpload>, String> {
+    let known = archive::load(archive_path);
+
+    let mut cmd = Command::new(ytdlp);
+    cmd.arg("--flat-playlist")
+        .arg("--playlist-end")
+        .arg(CHECK_DEPTH.to_string())
+        .arg("--print")
+        .arg("%(ie_key)s %(id)s %(webpage_url,url)s")
+        .args(access)
+        .arg(urls::channel_uploads(url))
+        .stdin(Stdio::null());
+    hide_window(&mut cmd);
+
+    let output = cmd.output().map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .rfind(|line| line.contains("ERROR"))
+            .unwrap_or("Checking for new uploads failed")
+            .to_string());
+    }
+
+    let mut uploads = parse_listing(&String::from_utf8_lossy(&output.stdout))?;
+    uploads.retain(|upload| !known.contains(&upload.archive_entry));
+    uploads.reverse();
+
+    if seed {
+        let entries: Vec<String> = uploads.into_iter().map(|u| u.archive_entry).collect();
+        archive::append(archive_path, &entries)?;
+        return Ok(Vec::new());
+    }
+    Ok(uploads)
+}
+
+/// Parses the `--print` output of a check. A listing made only of nested
+/// playlists is an error, or the check would quietly find nothing forever.
+fn parse_listing(output: &str) -> Result<Vec<NewUpload>, String> {
+    let lines: Vec<&str> = output.lines().filter(|line| !line.trim().is_empty()).collect();
+    let uploads: Vec<NewUpload> = lines.iter().copied().filter_map(parse_entry).collect();
+    if uploads.is_empty() && lines.iter().any(|line| is_tab(line)) {
+        return Err(
+            "This link lists a channel's tabs rather than its uploads; subscribe to the Videos, Shorts or Live tab instead"
+                .to_string(),
+        );
+    }
+    Ok(uploads)
+}
+
+fn is_tab(line: &str) -> bool {
+    line.split(' ').next().is_some_and(|extractor| extractor.ends_with("Tab"))
+}
+
+/// Parses one `--print` line, `<ie_key> <id> <url>`. Nested playlists such as
+/// a channel's tabs are skipped; subscribe to the tab itself instead.
+fn parse_entry(line: &str) -> Option<NewUpload> {
+    let mut parts = line.trim().splitn(3, ' ');
+    let extractor = parts.next()?;
+    let id = parts.next()?;
+    let url = parts.next()?;
+
+    if extractor == "NA" || id == "NA" || url == "NA" || extractor.ends_with("Tab") {
+        return None;
+    }
+
+    Some(NewUpload {
+        url: url.to_string(),
+        archive_entry: archive::entry(extractor, id),
+    })
+}
+
+fn subscriptions_path() -> Option<PathBuf> {
+    config::data_dir().map(|p| p.join("subscriptions.json"))
+}
+
+pub fn load() -> Vec<ChannelSubscription> {
+    subscriptions_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(subscriptions: &[ChannelSubscription]) -> Result<(), String> {
+    let path = subscriptions_path().ok_or("Could not determine data directory")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(subscriptions)
+        .map_err(|e| format!("Failed to serialize subscriptions: {}", e))?;
+
+    fs::write(path, content).map_err(|e| format!("Failed to write subscriptions file: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_keeps_videos_and_skips_tabs() {
+        let output = "Youtube abc123 https://www.youtube.com/watch?v=abc123\n\
+                      YoutubeTab UCx https://www.youtube.com/@name/shorts\n\
+                      Youtube NA NA\n";
+        let uploads = parse_listing(output).unwrap();
+        assert_eq!(uploads.len(), 1);
+        assert_eq!(uploads[0].url, "https://www.youtube.com/watch?v=abc123");
+        assert_eq!(uploads[0].archive_entry, archive::entry("Youtube", "abc123"));
+    }
+
+    #[test]
+    fn listing_of_only_tabs_is_an_error() {
+        let output = "YoutubeTab UCx https://www.youtube.com/@name/videos\n\
+                      YoutubeTab UCx https://www.youtube.com/@name/shorts\n";
+        assert!(parse_listing(output).unwrap_err().contains("tabs"));
+        assert!(parse_listing("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn due_after_interval() {
+        let now = Local::now();
+        let mut subscription = ChannelSubscription {
+            id: 1,
+            url: "https://www.youtube.com/@name".to_string(),
+            format: Format::Default,
+            check_interval_hours: 6,
+            last_checked: None,
+            seeded: false,
+            new_uploads: 0,
+            checking: false,
+            last_error: None,
+        };
+        assert!(subscription.is_due(now));
+        subscription.last_checked = Some(now - TimeDelta::hours(5));
+        assert!(!subscription.is_due(now));
+        subscription.last_checked = Some(now - TimeDelta::hours(6));
+        assert!(subscription.is_due(now));
+        subscription.checking = true;
+        assert!(!subscription.is_due(now));
+    }
+}
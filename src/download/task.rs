@@ -1,16 +1,26 @@
+use crate::disk;
 use crate::message::{DownloadProgress, Format};
+use crate::paths;
 use crate::settings::AdvancedSettings;
+use crate::tools::capabilities::Capabilities;
+use crate::tools::{hide_window, ytdlp};
+use std::ffi::OsString;
+use std::path::PathBuf;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::mpsc;
 
-use super::progress::parse_progress_line;
+use super::progress::{parse_progress_line, DOWNLOAD_TEMPLATE, POSTPROCESS_TEMPLATE};
 
 pub struct DownloadTask {
     url: String,
     format: Format,
     settings: AdvancedSettings,
+    capabilities: Option<Capabilities>,
+    global_rate_limit: Option<u64>,
+    download_archive: Option<PathBuf>,
 }
 
 impl DownloadTask {
@@ -19,46 +29,176 @@ impl DownloadTask {
             url,
             format,
             settings,
+            capabilities: None,
+            global_rate_limit: None,
+            download_archive: None,
         }
     }
 
-    pub fn build_command(&self) -> Command {
-        let ytdlp_path = if self.settings.ytdlp_path.is_empty() {
-            "yt-dlp"
-        } else {
-            &self.settings.ytdlp_path
-        };
+    /// Records finished downloads in a yt-dlp download archive, and skips
+    /// videos already listed there.
+    pub fn with_download_archive(mut self, archive: Option<PathBuf>) -> Self {
+        self.download_archive = archive;
+        self
+    }
 
-        let mut cmd = Command::new(ytdlp_path);
+    /// Applies this download's share of the global bandwidth budget (bytes
+    /// per second), replacing the per-download rate limit setting.
+    pub fn with_rate_limit(mut self, rate_limit: Option<u64>) -> Self {
+        self.global_rate_limit = rate_limit;
+        self
+    }
+
+    /// Restricts the generated command to options the installed yt-dlp
+    /// understands. Without capabilities every option is assumed supported.
+    pub fn with_capabilities(mut self, capabilities: Option<Capabilities>) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    fn supports(&self, option: &str) -> bool {
+        self.capabilities
+            .as_ref()
+            .is_none_or(|c| c.supports(option))
+    }
+
+    /// Folders set for each `--paths` type, expanded.
+    pub fn destinations(&self) -> Vec<(&'static str, PathBuf)> {
+        [
+            ("home", &self.settings.output_directory),
+            ("temp", &self.settings.temp_directory),
+            ("subtitle", &self.settings.subtitle_directory),
+            ("thumbnail", &self.settings.thumbnail_directory),
+        ]
+        .into_iter()
+        .filter(|(_, dir)| !dir.trim().is_empty())
+        .map(|(kind, dir)| (kind, paths::expand(dir)))
+        .collect()
+    }
+
+    /// Options the current format and settings ask for, excluding the ones
+    /// every yt-dlp release has.
+    fn requested_options(&self) -> Vec<&'static str> {
+        let mut options = Vec::new();
+
+        match self.format {
+            Format::Default => {}
+            Format::Mp4 | Format::Mkv => options.push("--merge-output-format"),
+            Format::Mp3 | Format::Aac => options.extend(["--extract-audio", "--audio-format"]),
+        }
+        if self.settings.download_subtitles {
+            options.push("--write-subs");
+            if !self.settings.subtitle_languages.is_empty() {
+                options.push("--sub-langs");
+            }
+            if self.settings.embed_subtitles {
+                options.push("--embed-subs");
+            }
+        }
+        if self.settings.embed_thumbnail {
+            options.push("--embed-thumbnail");
+        }
+        if self.settings.embed_metadata {
+            options.push("--embed-metadata");
+        }
+        if self.settings.enable_sponsorblock {
+            options.push("--sponsorblock-remove");
+        }
+        if self.global_rate_limit.is_some() || !self.settings.rate_limit.is_empty() {
+            options.push("--limit-rate");
+        }
+        if !self.settings.concurrent_fragments.is_empty()
+            && self.settings.concurrent_fragments != "1"
+        {
+            options.push("--concurrent-fragments");
+        }
+        if !self.settings.ffmpeg_location.is_empty() {
+            options.push("--ffmpeg-location");
+        }
+        if !self.settings.js_runtimes.is_empty() {
+            options.push("--js-runtimes");
+        }
+        if self.download_archive.is_some() {
+            options.push("--download-archive");
+        }
+        // Only the temporary and per-type folders are lost without it.
+        if self.destinations().iter().any(|(kind, _)| *kind != "home") {
+            options.push("--paths");
+        }
+
+        options
+    }
+
+    /// Requested options the installed yt-dlp does not know about. These are
+    /// left out of [`Self::build_command`] instead of failing the download.
+    pub fn unsupported_options(&self) -> Vec<&'static str> {
+        self.requested_options()
+            .into_iter()
+            .filter(|option| !self.supports(option))
+            .collect()
+    }
+
+    /// Whether this download relies on ffmpeg for merging, conversion or
+    /// embedding, i.e. would fail or silently degrade without it.
+    pub fn requires_ffmpeg(&self) -> bool {
+        self.format.requires_postprocessing()
+            || self.settings.embed_thumbnail
+            || self.settings.embed_metadata
+            || (self.settings.download_subtitles && self.settings.embed_subtitles)
+            || self.settings.enable_sponsorblock
+            || self.settings.preferred_quality.contains('+')
+    }
+
+    pub fn build_command(&self) -> Command {
+        let mut cmd = Command::new(ytdlp::resolve_path(&self.settings));
 
         // Progress output format
-        cmd.arg("--progress-template")
-            .arg("download:%(progress)j");
+        cmd.arg("--progress-template").arg(DOWNLOAD_TEMPLATE);
+        cmd.arg("--progress-template").arg(POSTPROCESS_TEMPLATE);
         cmd.arg("--newline");
 
         // Format selection
         match self.format {
             Format::Default => {}
-            Format::Mp4 => {
+            Format::Mp4 if self.supports("--merge-output-format") => {
                 cmd.arg("--merge-output-format").arg("mp4");
             }
-            Format::Mkv => {
+            Format::Mkv if self.supports("--merge-output-format") => {
                 cmd.arg("--merge-output-format").arg("mkv");
             }
-            Format::Mp3 => {
+            Format::Mp3 if self.supports("--audio-format") => {
                 cmd.arg("-x").arg("--audio-format").arg("mp3");
             }
-            Format::Aac => {
+            Format::Aac if self.supports("--audio-format") => {
                 cmd.arg("-x").arg("--audio-format").arg("aac");
             }
+            Format::Mp4 | Format::Mkv | Format::Mp3 | Format::Aac => {}
         }
 
-        // Output settings
-        if !self.settings.output_directory.is_empty() {
-            cmd.arg("-o")
-                .arg(format!("{}/{}", self.settings.output_directory, self.settings.filename_template));
-        } else if self.settings.filename_template != "%(title)s.%(ext)s" {
-            cmd.arg("-o").arg(&self.settings.filename_template);
+        // Output settings. Folders go through --paths so yt-dlp joins them
+        // with the template itself.
+        let destinations = self.destinations();
+        if self.supports("--paths") {
+            for (kind, dir) in &destinations {
+                let mut arg = OsString::from(format!("{}:", kind));
+                arg.push(dir);
+                cmd.arg("-P").arg(arg);
+            }
+            if self.settings.filename_template != "%(title)s.%(ext)s" {
+                cmd.arg("-o").arg(&self.settings.filename_template);
+            }
+        } else {
+            // Releases without --paths only get the output directory.
+            let home = destinations.iter().find(|(kind, _)| *kind == "home");
+            match home {
+                Some((_, dir)) => {
+                    cmd.arg("-o").arg(dir.join(&self.settings.filename_template));
+                }
+                None if self.settings.filename_template != "%(title)s.%(ext)s" => {
+                    cmd.arg("-o").arg(&self.settings.filename_template);
+                }
+                None => {}
+            }
         }
 
         // Quality settings
@@ -67,26 +207,26 @@ impl DownloadTask {
         }
 
         // Subtitles
-        if self.settings.download_subtitles {
+        if self.settings.download_subtitles && self.supports("--write-subs") {
             cmd.arg("--write-subs");
-            if !self.settings.subtitle_languages.is_empty() {
+            if !self.settings.subtitle_languages.is_empty() && self.supports("--sub-langs") {
                 cmd.arg("--sub-langs").arg(&self.settings.subtitle_languages);
             }
-            if self.settings.embed_subtitles {
+            if self.settings.embed_subtitles && self.supports("--embed-subs") {
                 cmd.arg("--embed-subs");
             }
         }
 
         // Metadata
-        if self.settings.embed_thumbnail {
+        if self.settings.embed_thumbnail && self.supports("--embed-thumbnail") {
             cmd.arg("--embed-thumbnail");
         }
-        if self.settings.embed_metadata {
+        if self.settings.embed_metadata && self.supports("--embed-metadata") {
             cmd.arg("--embed-metadata");
         }
 
         // SponsorBlock
-        if self.settings.enable_sponsorblock {
+        if self.settings.enable_sponsorblock && self.supports("--sponsorblock-remove") {
             cmd.arg("--sponsorblock-remove");
             if !self.settings.sponsorblock_categories.is_empty() {
                 cmd.arg(&self.settings.sponsorblock_categories);
@@ -95,28 +235,33 @@ impl DownloadTask {
             }
         }
 
-        // Network
-        if !self.settings.proxy.is_empty() {
-            cmd.arg("--proxy").arg(&self.settings.proxy);
-        }
-        if !self.settings.rate_limit.is_empty() {
-            cmd.arg("--limit-rate").arg(&self.settings.rate_limit);
+        // Network, authentication and JS runtimes
+        cmd.args(access_args(&self.settings, self.capabilities.as_ref()));
+        if self.supports("--limit-rate") {
+            if let Some(limit) = self.global_rate_limit {
+                cmd.arg("--limit-rate").arg(limit.to_string());
+            } else if !self.settings.rate_limit.is_empty() {
+                cmd.arg("--limit-rate").arg(&self.settings.rate_limit);
+            }
         }
         if !self.settings.concurrent_fragments.is_empty()
             && self.settings.concurrent_fragments != "1"
+            && self.supports("--concurrent-fragments")
         {
             cmd.arg("--concurrent-fragments")
                 .arg(&self.settings.concurrent_fragments);
         }
 
-        // Authentication
-        if !self.settings.cookies_file.is_empty() {
-            cmd.arg("--cookies").arg(&self.settings.cookies_file);
+        // ffmpeg
+        if !self.settings.ffmpeg_location.is_empty() && self.supports("--ffmpeg-location") {
+            cmd.arg("--ffmpeg-location").arg(paths::expand(&self.settings.ffmpeg_location));
         }
 
-        // JS Runtimes (for YouTube JS challenges)
-        if !self.settings.js_runtimes.is_empty() {
-            cmd.arg("--js-runtimes").arg(&self.settings.js_runtimes);
+        // Download archive
+        if let Some(ref archive) = self.download_archive
+            && self.supports("--download-archive")
+        {
+            cmd.arg("--download-archive").arg(archive);
         }
 
         // Extra arguments
@@ -132,14 +277,64 @@ impl DownloadTask {
         cmd
     }
 
+    /// Asks yt-dlp for the size of the formats it would pick, without
+    /// downloading. `None` when the site reports no size.
+    pub async fn estimate_size(&self) -> Result<Option<u64>, String> {
+        let mut cmd = Command::new(ytdlp::resolve_path(&self.settings));
+        cmd.arg("--dump-json").arg("--no-playlist").arg("--no-warnings");
+        if matches!(self.format, Format::Mp3 | Format::Aac) {
+            cmd.arg("-x");
+        }
+        if !self.settings.preferred_quality.is_empty() {
+            cmd.arg("-f").arg(&self.settings.preferred_quality);
+        }
+        cmd.args(access_args(&self.settings, self.capabilities.as_ref()));
+        cmd.arg(&self.url).stdin(Stdio::null()).kill_on_drop(true);
+        hide_window(cmd.as_std_mut());
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .rfind(|line| line.contains("ERROR"))
+                .unwrap_or("Fetching the file size failed")
+                .to_string());
+        }
+
+        let info: serde_json::Value = serde_json::from_slice(&output.stdout)
+            .map_err(|e| format!("Unexpected yt-dlp output: {}", e))?;
+        Ok(format_size(&info).or_else(|| {
+            // Merged downloads list the video and audio formats separately
+            info["requested_formats"]
+                .as_array()
+                .and_then(|formats| formats.iter().map(format_size).sum())
+        })
+        .filter(|&size| size > 0))
+    }
+
     pub async fn run(
         self,
         progress_tx: mpsc::UnboundedSender<DownloadProgress>,
     ) -> Result<String, String> {
         let mut cmd = self.build_command();
 
+        // yt-dlp reports the final file once merging, conversion and the
+        // move out of the temporary folder are done. The last progress line
+        // only names an intermediate file.
+        let final_paths = self.supports("--print-to-file").then(FinalPaths::new);
+        if let Some(ref final_paths) = final_paths {
+            cmd.arg("--print-to-file")
+                .arg("after_move:filepath")
+                .arg(final_paths.template());
+        }
+
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
+        // Cancelling or restarting a download drops this future; take yt-dlp with it
+        cmd.kill_on_drop(true);
 
         // Prevent window popup on Windows
         #[cfg(windows)]
@@ -176,7 +371,7 @@ impl DownloadTask {
                 if let Some(progress) = parse_progress_line(&line) {
                     let _ = progress_tx_clone.send(progress);
                 }
-                if line.to_lowercase().contains("error") {
+                if line.to_lowercase().contains("error") || disk::is_disk_full(&line) {
                     errors.push(line);
                 }
             }
@@ -196,7 +391,20 @@ impl DownloadTask {
             .map_err(|e| format!("Stderr task failed: {}", e))?;
 
         if status.success() {
-            Ok(filename)
+            Ok(final_paths.and_then(|paths| paths.last()).unwrap_or(filename))
+        } else if errors.iter().any(|line| disk::is_disk_full(line)) {
+            // ffmpeg and Python report this in ways that are easy to miss
+            let destinations = self.destinations();
+            let folder = ["temp", "home"]
+                .iter()
+                .find_map(|wanted| destinations.iter().find(|(kind, _)| kind == wanted))
+                .map(|(_, dir)| dir.display().to_string())
+                .unwrap_or_else(|| "the output folder".to_string());
+            Err(format!(
+                "The disk is full. Free up space on the drive holding {} or choose another folder under Output, then retry.\n{}",
+                folder,
+                errors.join("\n")
+            ))
         } else {
             let error_msg = if errors.is_empty() {
                 format!("yt-dlp exited with code: {:?}", status.code())
@@ -207,3 +415,219 @@ impl DownloadTask {
         }
     }
 }
+
+/// File yt-dlp appends the path of each finished video to. Removed when the
+/// run ends, including when it is cancelled.
+struct FinalPaths(PathBuf);
+
+impl FinalPaths {
+    fn new() -> Self {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let name = format!(
+            "yt-dlgui-{}-{}.paths",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        );
+        Self(std::env::temp_dir().join(name))
+    }
+
+    /// The file name as an output template, where `%` is special.
+    fn template(&self) -> OsString {
+        self.0.to_string_lossy().replace('%', "%%").into()
+    }
+
+    fn last(&self) -> Option<String> {
+        let content = std::fs::read_to_string(&self.0).ok()?;
+        content.lines().rfind(|line| !line.trim().is_empty()).map(str::to_string)
+    }
+}
+
+impl Drop for FinalPaths {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Proxy, cookies and JS runtime options. Anything that asks a site about
+/// a video passes them, so it sees what the download would.
+pub fn access_args(settings: &AdvancedSettings, capabilities: Option<&Capabilities>) -> Vec<OsString> {
+    let supports = |option| capabilities.is_none_or(|c| c.supports(option));
+    let mut args = Vec::new();
+
+    if !settings.proxy.is_empty() {
+        args.push("--proxy".into());
+        args.push(settings.proxy.clone().into());
+    }
+    if !settings.cookies_file.is_empty() {
+        args.push("--cookies".into());
+        args.push(paths::expand(&settings.cookies_file).into());
+    }
+    // JS Runtimes (for YouTube JS challenges)
+    if !settings.js_runtimes.is_empty() && supports("--js-runtimes") {
+        args.push("--js-runtimes".into());
+        args.push(settings.js_runtimes.clone().into());
+    }
+    args
+}
+
+/// Exact size of a format, or yt-dlp's estimate from its bitrate.
+fn format_size(format: &serde_json::Value) -> Option<u64> {
+    format["filesize"]
+        .as_u64()
+        .or_else(|| format["filesize_approx"].as_f64().map(|size| size as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(settings: AdvancedSettings) -> DownloadTask {
+        DownloadTask::new("https://example.com/v".to_string(), Format::Mp4, settings)
+    }
+
+    fn args(task: &DownloadTask) -> Vec<String> {
+        task.build_command()
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn destinations_go_through_paths() {
+        let settings = AdvancedSettings {
+            output_directory: "/media/videos".to_string(),
+            temp_directory: "/scratch".to_string(),
+            ..AdvancedSettings::default()
+        };
+        let args = args(&task(settings));
+        assert!(args.windows(2).any(|w| w == ["-P", "home:/media/videos"]));
+        assert!(args.windows(2).any(|w| w == ["-P", "temp:/scratch"]));
+        assert!(!args.contains(&"-o".to_string()), "default template needs no -o");
+    }
+
+    #[test]
+    fn unsupported_options_are_dropped_and_reported() {
+        let settings = AdvancedSettings {
+            embed_thumbnail: true,
+            embed_metadata: true,
+            rate_limit: "2M".to_string(),
+            js_runtimes: "deno".to_string(),
+            ..AdvancedSettings::default()
+        };
+        let capabilities = Capabilities::with_options(&["--merge-output-format", "--paths", "--embed-metadata"]);
+        let task = task(settings).with_capabilities(Some(capabilities));
+
+        let args = args(&task);
+        assert!(args.contains(&"--embed-metadata".to_string()));
+        for option in ["--embed-thumbnail", "--limit-rate", "--js-runtimes"] {
+            assert!(!args.contains(&option.to_string()), "{} passed", option);
+        }
+        assert_eq!(task.unsupported_options(), ["--embed-thumbnail", "--limit-rate", "--js-runtimes"]);
+    }
+
+    #[test]
+    fn unknown_capabilities_pass_every_option() {
+        let settings = AdvancedSettings {
+            embed_thumbnail: true,
+            js_runtimes: "deno".to_string(),
+            ..AdvancedSettings::default()
+        };
+        let task = task(settings);
+
+        let args = args(&task);
+        assert!(args.contains(&"--embed-thumbnail".to_string()));
+        assert!(args.windows(2).any(|w| w == ["--js-runtimes", "deno"]));
+        assert!(task.unsupported_options().is_empty());
+    }
+
+    #[test]
+    fn every_format_but_default_requires_ffmpeg() {
+        for format in Format::ALL {
+            let task = DownloadTask::new("https://example.com/v".to_string(), format, AdvancedSettings::default());
+            assert_eq!(task.requires_ffmpeg(), format != Format::Default, "{}", format);
+        }
+    }
+
+    #[test]
+    fn embedding_and_merging_require_ffmpeg() {
+        let cases = [
+            AdvancedSettings {
+                embed_thumbnail: true,
+                ..AdvancedSettings::default()
+            },
+            AdvancedSettings {
+                embed_metadata: true,
+                ..AdvancedSettings::default()
+            },
+            AdvancedSettings {
+                enable_sponsorblock: true,
+                ..AdvancedSettings::default()
+            },
+            AdvancedSettings {
+                download_subtitles: true,
+                embed_subtitles: true,
+                ..AdvancedSettings::default()
+            },
+            AdvancedSettings {
+                preferred_quality: "bestvideo+bestaudio".to_string(),
+                ..AdvancedSettings::default()
+            },
+        ];
+        for settings in cases {
+            let task = DownloadTask::new("https://example.com/v".to_string(), Format::Default, settings);
+            assert!(task.requires_ffmpeg());
+        }
+
+        let separate_subtitles = AdvancedSettings {
+            download_subtitles: true,
+            ..AdvancedSettings::default()
+        };
+        let task = DownloadTask::new("https://example.com/v".to_string(), Format::Default, separate_subtitles);
+        assert!(!task.requires_ffmpeg());
+    }
+
+    #[test]
+    fn access_args_carry_proxy_and_cookies() {
+        let settings = AdvancedSettings {
+            proxy: "socks5://127.0.0.1:1080".to_string(),
+            cookies_file: "/tmp/cookies.txt".to_string(),
+            ..AdvancedSettings::default()
+        };
+        let args: Vec<String> = access_args(&settings, None)
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(args, ["--proxy", "socks5://127.0.0.1:1080", "--cookies", "/tmp/cookies.txt"]);
+    }
+
+    // Runs a shell script standing in for yt-dlp
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn run_reports_final_path_after_merge() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = std::env::temp_dir().join(format!("yt-dlgui-fake-ytdlp-{}", std::process::id()));
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             while [ $# -gt 0 ]; do\n\
+             if [ \"$1\" = \"--print-to-file\" ]; then echo /videos/Clip.mp4 >> \"$3\"; fi\n\
+             shift\n\
+             done\n\
+             echo '[progress] 137 {\"status\":\"downloading\",\"filename\":\"/videos/Clip.f137.mp4\"}'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let settings = AdvancedSettings {
+            ytdlp_path: script.display().to_string(),
+            ..AdvancedSettings::default()
+        };
+        let (progress_tx, _progress_rx) = mpsc::unbounded_channel();
+        let result = task(settings).run(progress_tx).await;
+
+        assert_eq!(result, Ok("/videos/Clip.mp4".to_string()));
+        let _ = std::fs::remove_file(script);
+    }
+}
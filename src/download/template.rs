@@ -0,0 +1,432 @@
+//! yt-dlp output templates: the fields and presets offered by the template
+//! editor, syntax checking, and a preview renderer.
+
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::tools::hide_window;
+
+/// Fields offered as chips in the editor.
+pub const FIELDS: [&str; 10] = [
+    "title",
+    "uploader",
+    "channel",
+    "upload_date",
+    "id",
+    "playlist_title",
+    "playlist_index",
+    "resolution",
+    "format_id",
+    "ext",
+];
+
+/// Folder segments offered in the editor, as (label, field).
+pub const FOLDERS: [(&str, &str); 3] = [
+    ("uploader", "uploader"),
+    ("playlist", "playlist_title,uploader"),
+    ("year", "upload_date>%Y"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplatePreset {
+    pub name: &'static str,
+    pub template: &'static str,
+}
+
+impl std::fmt::Display for TemplatePreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+pub const PRESETS: [TemplatePreset; 6] = [
+    TemplatePreset {
+        name: "Title",
+        template: "%(title)s.%(ext)s",
+    },
+    TemplatePreset {
+        name: "Title [ID]",
+        template: "%(title)s [%(id)s].%(ext)s",
+    },
+    TemplatePreset {
+        name: "Uploader / Title",
+        template: "%(uploader)s/%(title)s.%(ext)s",
+    },
+    TemplatePreset {
+        name: "Date - Title",
+        template: "%(upload_date>%Y-%m-%d)s - %(title)s.%(ext)s",
+    },
+    TemplatePreset {
+        name: "Playlist / Number - Title",
+        template: "%(playlist_title,uploader)s/%(playlist_index)03d - %(title)s.%(ext)s",
+    },
+    TemplatePreset {
+        name: "Uploader / Year / Title",
+        template: "%(uploader)s/%(upload_date>%Y)s/%(title)s.%(ext)s",
+    },
+];
+
+/// Metadata used for the preview until real metadata has been probed.
+pub fn sample_metadata() -> HashMap<String, String> {
+    [
+        ("title", "Big Buck Bunny"),
+        ("uploader", "Blender Foundation"),
+        ("channel", "Blender"),
+        ("upload_date", "20080529"),
+        ("id", "aqz-KE-bpKQ"),
+        ("playlist_title", "Open Movies"),
+        ("playlist_index", "3"),
+        ("resolution", "1920x1080"),
+        ("format_id", "137+140"),
+        ("ext", "mp4"),
+        ("height", "1080"),
+        ("width", "1920"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect()
+}
+
+/// Conversion types Python's `%` formatting accepts, plus yt-dlp's own.
+const CONVERSIONS: &str = "diouxXeEfFgGcrsaBjlqDSU";
+
+/// One `%(...)X` reference within a template.
+struct Reference<'a> {
+    /// Everything between the parentheses.
+    key: &'a str,
+    /// Flags, width and precision, e.g. `03` in `%(playlist_index)03d`.
+    spec: &'a str,
+    conversion: char,
+}
+
+enum Piece<'a> {
+    Literal(&'a str),
+    Percent,
+    Field(Reference<'a>),
+}
+
+fn parse(template: &str) -> Result<Vec<Piece<'_>>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('%') {
+        if start > 0 {
+            pieces.push(Piece::Literal(&rest[..start]));
+        }
+        let after = &rest[start + 1..];
+
+        if let Some(after) = after.strip_prefix('%') {
+            pieces.push(Piece::Percent);
+            rest = after;
+            continue;
+        }
+        // yt-dlp keeps a % that starts no field as it is
+        let Some(inner) = after.strip_prefix('(') else {
+            pieces.push(Piece::Percent);
+            rest = after;
+            continue;
+        };
+
+        // Keys can hold parentheses of their own, so match by depth.
+        let mut depth = 1;
+        let close = inner.char_indices().find_map(|(i, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => {}
+            }
+            (depth == 0).then_some(i)
+        });
+        let Some(close) = close else {
+            return Err(format!("Unclosed \"%({}\"", inner));
+        };
+
+        let key = &inner[..close];
+        if key.trim().is_empty() {
+            return Err("Empty field name in \"%()\"".to_string());
+        }
+
+        let tail = &inner[close + 1..];
+        let spec_len = tail
+            .find(|c: char| !matches!(c, '#' | '0'..='9' | '-' | '+' | ' ' | '.'))
+            .unwrap_or(tail.len());
+        let spec = &tail[..spec_len];
+        let conversion = tail[spec_len..]
+            .chars()
+            .next()
+            .filter(|c| CONVERSIONS.contains(*c))
+            .ok_or_else(|| format!("\"%({})\" needs a type after it, such as s", key))?;
+
+        pieces.push(Piece::Field(Reference { key, spec, conversion }));
+        rest = &tail[spec_len + conversion.len_utf8()..];
+    }
+
+    if !rest.is_empty() {
+        pieces.push(Piece::Literal(rest));
+    }
+    Ok(pieces)
+}
+
+/// Inserts a field into the file name part of the template, before a
+/// trailing `.%(ext)s` so the extension stays last.
+pub fn insert_field(template: &str, field: &str) -> String {
+    let (stem, suffix) = match template.strip_suffix(".%(ext)s") {
+        Some(stem) => (stem, ".%(ext)s"),
+        None => (template, ""),
+    };
+    // Keep fields apart unless the name already ends in a separator.
+    let gap = if stem.is_empty() || stem.ends_with([' ', '/', '-', '_', '.', '[', '(']) {
+        ""
+    } else {
+        " "
+    };
+    format!("{}{}%({})s{}", stem, gap, field, suffix)
+}
+
+/// Adds a folder level after any existing ones.
+pub fn insert_folder(template: &str, field: &str) -> String {
+    let split = folder_end(template);
+    format!("{}%({})s/{}", &template[..split], field, &template[split..])
+}
+
+/// Where the file name starts: just past the last separator outside
+/// `%(...)` fields, whose date formats and defaults may contain slashes.
+/// The platform separator counts too, as a `\` typed on Windows.
+fn folder_end(template: &str) -> usize {
+    let mut end = 0;
+    let mut depth = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '%' if depth == 0 => match chars.peek() {
+                Some((_, '%')) => {
+                    chars.next();
+                }
+                Some((_, '(')) => {
+                    chars.next();
+                    depth = 1;
+                }
+                _ => {}
+            },
+            '(' if depth > 0 => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            c if depth == 0 && (c == '/' || c == std::path::MAIN_SEPARATOR) => end = i + 1,
+            _ => {}
+        }
+    }
+    end
+}
+
+/// Checks the template's `%(...)` syntax.
+pub fn validate(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("The template is empty".to_string());
+    }
+    parse(template).map(|_| ())
+}
+
+/// Renders the template the way yt-dlp would for the preview. Supports
+/// alternatives (`a,b`), defaults (`|text`), date formats (`>%Y-%m-%d`) on
+/// `YYYYMMDD` dates and zero-padded numbers; anything else missing shows as
+/// `NA`, yt-dlp's placeholder.
+pub fn render(template: &str, metadata: &HashMap<String, String>) -> Result<String, String> {
+    let mut output = String::new();
+
+    for piece in parse(template)? {
+        match piece {
+            Piece::Literal(text) => output.push_str(text),
+            Piece::Percent => output.push('%'),
+            Piece::Field(reference) => {
+                let value = lookup(reference.key, metadata).unwrap_or_else(|| "NA".to_string());
+                // Separators inside values would create folders; yt-dlp
+                // swaps them for a look-alike character.
+                let value = value.replace(['/', '\\'], "\u{29F8}");
+                output.push_str(&apply_spec(&value, reference.spec, reference.conversion));
+            }
+        }
+    }
+    Ok(output)
+}
+
+fn lookup(key: &str, metadata: &HashMap<String, String>) -> Option<String> {
+    let (key, default) = match key.split_once('|') {
+        Some((key, default)) => (key, Some(default)),
+        None => (key, None),
+    };
+
+    key.split(',')
+        .find_map(|alternative| {
+            let (name, date_format) = match alternative.split_once('>') {
+                Some((name, format)) => (name.trim(), Some(format)),
+                None => (alternative.trim(), None),
+            };
+            let value = metadata.get(name).filter(|v| !v.is_empty() && *v != "NA")?;
+            Some(match date_format {
+                Some(format) => format_date(value, format),
+                None => value.clone(),
+            })
+        })
+        .or_else(|| default.map(str::to_string))
+}
+
+fn format_date(value: &str, format: &str) -> String {
+    chrono::NaiveDate::parse_from_str(value, "%Y%m%d")
+        .map(|date| date.format(format).to_string())
+        .unwrap_or_else(|_| value.to_string())
+}
+
+/// Applies width and zero padding, the only parts of the spec that matter
+/// for typical templates.
+fn apply_spec(value: &str, spec: &str, conversion: char) -> String {
+    let zero = spec.starts_with('0');
+    let width: usize = spec
+        .trim_start_matches(['#', '0', '-', '+', ' '])
+        .split('.')
+        .next()
+        .and_then(|w| w.parse().ok())
+        .unwrap_or(0);
+
+    if matches!(conversion, 'd' | 'i') && zero && value.parse::<i64>().is_ok() {
+        format!("{:0>width$}", value, width = width)
+    } else if spec.starts_with('-') {
+        format!("{:<width$}", value, width = width)
+    } else {
+        format!("{:>width$}", value, width = width)
+    }
+}
+
+/// Fetches a video's metadata for the preview with `--dump-json`, keeping
+/// the top-level text and number fields. `access` holds the proxy, cookies
+/// and similar options downloads use.
+pub fn probe(ytdlp: &Path, access: &[OsString], url: &str) -> Result<HashMap<String, String>, String> {
+    let mut cmd = Command::new(ytdlp);
+    cmd.arg("--dump-json")
+        .arg("--no-playlist")
+        .arg("--no-warnings")
+        .args(access)
+        .arg(url)
+        .stdin(Stdio::null());
+    hide_window(&mut cmd);
+
+    let output = cmd.output().map_err(|e| format!("Failed to start yt-dlp: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .rfind(|line| line.contains("ERROR"))
+            .unwrap_or("Fetching metadata failed")
+            .to_string());
+    }
+
+    let info: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Unexpected yt-dlp output: {}", e))?;
+
+    Ok(info
+        .into_iter()
+        .filter_map(|(key, value)| match value {
+            serde_json::Value::String(s) => Some((key, s)),
+            serde_json::Value::Number(n) => Some((key, n.to_string())),
+            serde_json::Value::Bool(b) => Some((key, b.to_string())),
+            _ => None,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn lone_percent_is_literal() {
+        assert_eq!(validate("100% %(title)s.%(ext)s"), Ok(()));
+        let info = metadata(&[("title", "Clip"), ("ext", "mp4")]);
+        assert_eq!(render("100% %(title)s.%(ext)s", &info).unwrap(), "100% Clip.mp4");
+        assert_eq!(render("50%% off %", &info).unwrap(), "50% off %");
+    }
+
+    #[test]
+    fn validate_reports_syntax_errors() {
+        assert!(validate("").is_err());
+        assert_eq!(validate("%(title.%(ext)s").unwrap_err(), "Unclosed \"%(title.%(ext)s\"");
+        assert_eq!(validate("%().%(ext)s").unwrap_err(), "Empty field name in \"%()\"");
+        assert!(validate("%(title).%(ext)s").unwrap_err().contains("needs a type"));
+        for preset in PRESETS {
+            assert_eq!(validate(preset.template), Ok(()), "{}", preset.name);
+        }
+    }
+
+    #[test]
+    fn render_applies_alternatives_defaults_dates_and_padding() {
+        let info = metadata(&[
+            ("title", "A/B"),
+            ("upload_date", "20240131"),
+            ("playlist_index", "7"),
+            ("ext", "mkv"),
+        ]);
+        assert_eq!(
+            render("%(playlist_index)03d - %(track,title)s [%(upload_date>%Y-%m-%d)s].%(ext)s", &info).unwrap(),
+            "007 - A\u{29F8}B [2024-01-31].mkv"
+        );
+        assert_eq!(render("%(uploader|Unknown)s/%(id)s", &info).unwrap(), "Unknown/NA");
+    }
+
+    #[test]
+    fn insert_keeps_extension_last() {
+        assert_eq!(insert_field("%(title)s.%(ext)s", "id"), "%(title)s %(id)s.%(ext)s");
+        assert_eq!(insert_field("[", "id"), "[%(id)s");
+        assert_eq!(
+            insert_folder("%(uploader)s/%(title)s.%(ext)s", "playlist"),
+            "%(uploader)s/%(playlist)s/%(title)s.%(ext)s"
+        );
+    }
+
+    #[test]
+    fn folders_split_outside_fields() {
+        assert_eq!(
+            insert_folder("%(upload_date>%Y/%m)s %(title)s.%(ext)s", "uploader"),
+            "%(uploader)s/%(upload_date>%Y/%m)s %(title)s.%(ext)s"
+        );
+        assert_eq!(
+            insert_folder("%(channel|a/b)s/%(title)s.%(ext)s", "playlist"),
+            "%(channel|a/b)s/%(playlist)s/%(title)s.%(ext)s"
+        );
+        let sep = std::path::MAIN_SEPARATOR;
+        assert_eq!(
+            insert_folder(&format!("Music{}%(title)s.%(ext)s", sep), "uploader"),
+            format!("Music{}%(uploader)s/%(title)s.%(ext)s", sep)
+        );
+    }
+
+    // Runs a shell script standing in for yt-dlp
+    #[cfg(unix)]
+    #[test]
+    fn probe_passes_access_options() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = std::env::temp_dir().join(format!("yt-dlgui-probe-ytdlp-{}", std::process::id()));
+        std::fs::write(
+            &script,
+            "#!/bin/sh\n\
+             case \"$*\" in\n\
+             *\"--proxy socks5://127.0.0.1:1080 https://example.com/v\"*) echo '{\"title\": \"Clip\", \"duration\": 61}' ;;\n\
+             *) echo 'ERROR: no proxy' >&2; exit 1 ;;\n\
+             esac\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let access = ["--proxy", "socks5://127.0.0.1:1080"].map(OsString::from);
+        let info = probe(&script, &access, "https://example.com/v");
+        let _ = std::fs::remove_file(script);
+
+        let info = info.unwrap();
+        assert_eq!(info.get("title").map(String::as_str), Some("Clip"));
+        assert_eq!(info.get("duration").map(String::as_str), Some("61"));
+    }
+}
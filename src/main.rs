@@ -2,24 +2,43 @@
 mod app;
 mod components;
 mod config;
+mod disk;
 mod download;
 mod message;
+mod notify;
+mod opener;
+mod paths;
+mod picker;
 mod settings;
 mod theme;
+mod tools;
+mod tray;
+mod units;
+mod urls;
 mod widgets;
 
 use app::App;
-use iced::{window, Size};
+use iced::{window, Point, Size};
 
 fn main() -> iced::Result {
+    let geometry = config::load_window_geometry();
+    let position = match (geometry.x, geometry.y) {
+        (Some(x), Some(y)) => window::Position::Specific(Point::new(x, y)),
+        _ => window::Position::default(),
+    };
+
     iced::application(App::new, App::update, App::view)
         .title("yt-dlgui")
         .theme(App::theme)
         .subscription(App::subscription)
         .window(window::Settings {
-            size: Size::new(500.0, 380.0),
+            size: Size::new(geometry.width, geometry.height),
+            position,
+            maximized: geometry.maximized,
             min_size: Some(Size::new(400.0, 330.0)),
             decorations: false,
+            // Closing goes through the close guard in `App::update`.
+            exit_on_close_request: false,
             ..Default::default()
         })
         .run()
@@ -1,3 +1,23 @@
+use iced::widget::text_editor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::disk::SpaceCheck;
+use crate::download::archive::ArchiveScope;
+use crate::download::progress::{PostProcessStage, TransferStats};
+use crate::download::queue::{DownloadId, RunId};
+use crate::download::subscriptions::NewUpload;
+use crate::download::template::TemplatePreset;
+use crate::notify::NotifyEvent;
+use crate::paths;
+use crate::settings::AdvancedSettings;
+use crate::theme::ThemeChoice;
+use crate::tools::capabilities::Capabilities;
+use crate::tools::ffmpeg::FfmpegInfo;
+use crate::tools::ytdlp::UpdateChannel;
+use crate::tray::{CloseAction, TrayEvent};
+
 #[derive(Debug, Clone)]
 pub enum Message {
     // URL input
@@ -7,11 +27,65 @@ pub enum Message {
     // Format selection
     FormatSelected(Format),
 
+    // Start time
+    StartModeSelected(StartMode),
+    StartTimeChanged(String),
+
     // Download actions
     StartDownload,
-    CancelDownload,
-    DownloadProgress(DownloadProgress),
-    DownloadComplete(Result<String, String>),
+    ConfirmDownload,
+    DismissPreflight,
+    /// Result of the free space check for a URL: a warning, or `None` to
+    /// go ahead.
+    DiskSpaceChecked(String, Option<String>),
+    CancelDownload(DownloadId),
+    DownloadProgress(RunId, DownloadProgress),
+    DownloadComplete(RunId, Result<String, String>),
+    BandwidthTick,
+
+    // Completed downloads
+    OpenDownload(DownloadId),
+    RevealDownload(DownloadId),
+    CopyDownloadPath(DownloadId),
+    DeleteDownload(DownloadId),
+    ConfirmDeleteDownload(DownloadId),
+    CancelDeleteDownload,
+    OpenerFinished(Option<DownloadId>, Result<(), String>),
+
+    // Scheduled downloads
+    ScheduleInputChanged(DownloadId, String),
+    RescheduleDownload(DownloadId),
+    StartDownloadNow(DownloadId),
+    ScheduleTick,
+
+    // Clipboard watcher
+    ClipboardPoll,
+    ClipboardRead(Option<String>),
+    AcceptClipboardUrl,
+    DismissClipboardUrl,
+
+    // Batch input
+    ToggleBatchMode,
+    BatchEdited(text_editor::Action),
+    BatchImportFileChanged(String),
+    ImportBatchFile,
+
+    // Drag and drop
+    FileHovered,
+    FilesHoveredLeft,
+    FileDropped(PathBuf),
+
+    // Channel subscriptions
+    OpenSubscriptions,
+    CloseSubscriptions,
+    SubscriptionUrlChanged(String),
+    SubscriptionFormatSelected(Format),
+    SubscriptionIntervalChanged(String),
+    AddSubscription,
+    RemoveSubscription(u64),
+    CheckSubscription(u64),
+    SubscriptionChecked(u64, Result<Vec<NewUpload>, String>),
+    SubscriptionTick,
 
     // Settings modal
     OpenSettings,
@@ -22,6 +96,40 @@ pub enum Message {
 
     // Settings field updates
     SettingsChanged(SettingsField),
+    BrowsePath(PathField),
+    PathPicked(PathField, Result<Option<PathBuf>, String>),
+
+    // Filename template editor
+    InsertTemplateField(String),
+    InsertTemplateFolder(String),
+    TemplatePresetSelected(TemplatePreset),
+    TemplateProbeUrlChanged(String),
+    ProbeTemplateMetadata,
+    TemplateMetadataProbed(Result<HashMap<String, String>, String>),
+
+    // Download archive viewer
+    ArchiveFormatSelected(Format),
+    ArchiveSearchChanged(String),
+    RemoveArchiveEntry(String),
+    ArchiveImportFileChanged(String),
+    ImportArchive,
+
+    // yt-dlp management
+    YtDlpInstallFileChanged(String),
+    YtDlpAllowUnverifiedToggled(bool),
+    InstallYtDlpFromFile,
+    InstallYtDlpFromMirror,
+    UpdateYtDlp,
+    RollbackYtDlp,
+    YtDlpVersionLoaded(Option<String>),
+    YtDlpManagerFinished(Result<String, String>),
+
+    // Tool detection
+    FfmpegDetected(FfmpegInfo),
+    CapabilitiesProbed(Option<Capabilities>),
+
+    // Appearance
+    SystemThemeChanged(iced::theme::Mode),
 
     // Modal backdrop click
     ModalBackdropClicked,
@@ -30,9 +138,27 @@ pub enum Message {
     WindowMinimize,
     WindowClose,
     WindowDrag,
+    WindowToggleMaximize,
+    WindowMaximized(bool),
+    WindowResize(iced::window::Direction),
+    WindowResized(iced::Size),
+    WindowMoved(iced::Point),
+
+    // Tray
+    Tray(TrayEvent),
+    TrayTick,
+
+    // Notifications
+    Notify(NotifyEvent),
+    WindowFocused(bool),
+
+    // Close guard
+    CloseGuardDismissed,
+    QuitResumingNextLaunch,
+    QuitWhenDownloadsFinish,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum Format {
     #[default]
     Default,
@@ -54,6 +180,12 @@ impl Format {
     /*pub fn is_audio(&self) -> bool {
         matches!(self, Format::Mp3 | Format::Aac)
     }*/
+
+    /// Whether yt-dlp needs ffmpeg to produce this format (audio extraction
+    /// or merging into a container).
+    pub fn requires_postprocessing(&self) -> bool {
+        !matches!(self, Format::Default)
+    }
 }
 
 impl std::fmt::Display for Format {
@@ -68,25 +200,144 @@ impl std::fmt::Display for Format {
     }
 }
 
+/// When a newly added download should start.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StartMode {
+    #[default]
+    Now,
+    At,
+    WhenIdle,
+}
+
+impl StartMode {
+    pub const ALL: [StartMode; 3] = [StartMode::Now, StartMode::At, StartMode::WhenIdle];
+}
+
+impl std::fmt::Display for StartMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartMode::Now => write!(f, "Start Now"),
+            StartMode::At => write!(f, "Start At"),
+            StartMode::WhenIdle => write!(f, "When Idle"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum DownloadProgress {
+    FormatsSelected(Vec<String>),
     Downloading {
-        progress: f32,
-        speed: String,
-        eta: String,
+        format_id: Option<String>,
+        stats: TransferStats,
         filename: String,
     },
+    StreamFinished {
+        format_id: Option<String>,
+    },
     PostProcessing {
-        status: String,
+        stage: PostProcessStage,
+        finished: bool,
+    },
+    /// yt-dlp decided not to download the video again.
+    Skipped {
+        title: String,
+        reason: String,
     },
     Error(String),
 }
 
+/// Settings holding a path that can be chosen with a picker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathField {
+    OutputDirectory,
+    TempDirectory,
+    SubtitleDirectory,
+    ThumbnailDirectory,
+    CookiesFile,
+    YtDlpPath,
+}
+
+impl PathField {
+    pub const ALL: [PathField; 6] = [
+        PathField::OutputDirectory,
+        PathField::TempDirectory,
+        PathField::SubtitleDirectory,
+        PathField::ThumbnailDirectory,
+        PathField::CookiesFile,
+        PathField::YtDlpPath,
+    ];
+
+    pub fn setting(self, value: String) -> SettingsField {
+        match self {
+            PathField::OutputDirectory => SettingsField::OutputDirectory(value),
+            PathField::TempDirectory => SettingsField::TempDirectory(value),
+            PathField::SubtitleDirectory => SettingsField::SubtitleDirectory(value),
+            PathField::ThumbnailDirectory => SettingsField::ThumbnailDirectory(value),
+            PathField::CookiesFile => SettingsField::CookiesFile(value),
+            PathField::YtDlpPath => SettingsField::YtDlpPath(value),
+        }
+    }
+
+    pub fn value(self, settings: &AdvancedSettings) -> &str {
+        match self {
+            PathField::OutputDirectory => &settings.output_directory,
+            PathField::TempDirectory => &settings.temp_directory,
+            PathField::SubtitleDirectory => &settings.subtitle_directory,
+            PathField::ThumbnailDirectory => &settings.thumbnail_directory,
+            PathField::CookiesFile => &settings.cookies_file,
+            PathField::YtDlpPath => &settings.ytdlp_path,
+        }
+    }
+
+    /// Checks a value for this field; the error is shown under it.
+    pub fn check(self, value: &str) -> Result<(), String> {
+        match self {
+            PathField::CookiesFile => paths::check_file(value),
+            PathField::YtDlpPath => paths::check_executable(value),
+            _ => paths::check_directory(value),
+        }
+    }
+
+    pub fn is_folder(self) -> bool {
+        !matches!(self, PathField::CookiesFile | PathField::YtDlpPath)
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            PathField::OutputDirectory => "Choose Output Directory",
+            PathField::TempDirectory => "Choose Temporary Directory",
+            PathField::SubtitleDirectory => "Choose Subtitle Directory",
+            PathField::ThumbnailDirectory => "Choose Thumbnail Directory",
+            PathField::CookiesFile => "Choose Cookies File",
+            PathField::YtDlpPath => "Choose yt-dlp Executable",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum SettingsField {
+    // Appearance
+    Theme(ThemeChoice),
+
+    // Tray
+    TrayIcon(bool),
+    MinimizeToTray(bool),
+    CloseAction(CloseAction),
+
+    // Notifications
+    NotifyOnSuccess(bool),
+    NotifyOnFailure(bool),
+    NotifyInBackgroundOnly(bool),
+
     // Output
     OutputDirectory(String),
+    TempDirectory(String),
+    SubtitleDirectory(String),
+    ThumbnailDirectory(String),
     FilenameTemplate(String),
+    DownloadArchive(ArchiveScope),
+    DiskSpaceCheck(SpaceCheck),
+    DiskSpaceReserve(String),
 
     // Quality
     PreferredQuality(String),
@@ -109,12 +360,23 @@ pub enum SettingsField {
     Proxy(String),
     RateLimit(String),
     ConcurrentFragments(String),
+    GlobalRateLimit(String),
+    BandwidthSchedule(String),
+    MaxConcurrentDownloads(String),
 
     // Authentication
     CookiesFile(String),
 
     // Advanced
     YtDlpPath(String),
+    FfmpegLocation(String),
     JsRuntimes(String),
     ExtraArguments(String),
+    WatchClipboard(bool),
+    IdentifyExtractor(bool),
+
+    // yt-dlp Management
+    UseManagedYtDlp(bool),
+    YtDlpUpdateChannel(UpdateChannel),
+    YtDlpMirrorUrl(String),
 }
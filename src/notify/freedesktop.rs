@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use iced::futures::stream::{self, Select};
+use iced::futures::StreamExt;
+use zbus::proxy::SignalStream;
+use zbus::zvariant::Value;
+use zbus::{Connection, Proxy};
+
+use super::{Notification, NotificationAction};
+
+const DESTINATION: &str = "org.freedesktop.Notifications";
+const PATH: &str = "/org/freedesktop/Notifications";
+
+/// How long a notification stays up, in milliseconds.
+const TIMEOUT: i32 = 10_000;
+
+/// Connection to the `org.freedesktop.Notifications` service.
+pub struct Service {
+    proxy: Proxy<'static>,
+    /// `ActionInvoked` and `NotificationClosed` signals.
+    signals: Select<SignalStream<'static>, SignalStream<'static>>,
+    /// Files of shown notifications that have actions, by notification id,
+    /// until the notification goes away.
+    files: HashMap<u32, PathBuf>,
+    /// The server renders markup in bodies, so text has to be escaped.
+    body_markup: bool,
+}
+
+impl Service {
+    pub async fn connect() -> Result<Self, String> {
+        let connection = Connection::session()
+            .await
+            .map_err(|e| format!("Failed to connect to the session bus: {}", e))?;
+        let proxy = Proxy::new(&connection, DESTINATION, PATH, DESTINATION)
+            .await
+            .map_err(|e| format!("Notification service unavailable: {}", e))?;
+
+        // Fails when nothing provides the service.
+        let _: (String, String, String, String) = proxy
+            .call("GetServerInformation", &())
+            .await
+            .map_err(|e| format!("Notification service unavailable: {}", e))?;
+
+        let capabilities: Vec<String> = proxy
+            .call("GetCapabilities", &())
+            .await
+            .map_err(|e| format!("Notification service unavailable: {}", e))?;
+
+        let actions = proxy
+            .receive_signal("ActionInvoked")
+            .await
+            .map_err(|e| format!("Failed to listen for notification actions: {}", e))?;
+        let closed = proxy
+            .receive_signal("NotificationClosed")
+            .await
+            .map_err(|e| format!("Failed to listen for closed notifications: {}", e))?;
+
+        Ok(Self {
+            proxy,
+            signals: stream::select(actions, closed),
+            files: HashMap::new(),
+            body_markup: capabilities.iter().any(|c| c == "body-markup"),
+        })
+    }
+
+    pub async fn show(&mut self, notification: Notification) -> Result<(), String> {
+        // Actions alternate between key and label.
+        let actions: Vec<&str> = if notification.file.is_some() {
+            vec!["open", "Open", "reveal", "Show in folder"]
+        } else {
+            Vec::new()
+        };
+        let hints: HashMap<&str, Value<'_>> = HashMap::new();
+        let body = if self.body_markup {
+            escape_markup(&notification.body)
+        } else {
+            notification.body.clone()
+        };
+
+        let id: Result<u32, _> = self
+            .proxy
+            .call(
+                "Notify",
+                &(
+                    "yt-dlgui",
+                    0u32,
+                    "folder-download",
+                    notification.summary.as_str(),
+                    body.as_str(),
+                    actions,
+                    hints,
+                    TIMEOUT,
+                ),
+            )
+            .await;
+
+        let id = id.map_err(|e| format!("Failed to show notification: {}", e))?;
+        if let Some(file) = notification.file {
+            self.files.insert(id, file);
+        }
+        Ok(())
+    }
+
+    /// Waits for the user to click an action on one of our notifications.
+    /// Closed notifications are forgotten along the way.
+    pub async fn next_action(&mut self) -> Option<(NotificationAction, PathBuf)> {
+        while let Some(message) = self.signals.next().await {
+            let header = message.header();
+            match header.member().map(|m| m.as_str()) {
+                Some("NotificationClosed") => {
+                    if let Ok((id, _reason)) = message.body().deserialize::<(u32, u32)>() {
+                        self.files.remove(&id);
+                    }
+                }
+                Some("ActionInvoked") => {
+                    let Ok((id, key)) = message.body().deserialize::<(u32, String)>() else {
+                        continue;
+                    };
+                    let action = match key.as_str() {
+                        "open" => NotificationAction::Open,
+                        "reveal" => NotificationAction::ShowInFolder,
+                        _ => continue,
+                    };
+                    if let Some(file) = self.files.remove(&id) {
+                        return Some((action, file));
+                    }
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+}
+
+/// Escapes the characters servers with `body-markup` read as markup.
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markup_characters_are_escaped() {
+        assert_eq!(
+            escape_markup("Tom & Jerry <Live> > 1h.mp4"),
+            "Tom &amp; Jerry &lt;Live&gt; &gt; 1h.mp4"
+        );
+        assert_eq!(escape_markup("&amp;"), "&amp;amp;");
+    }
+}
@@ -0,0 +1,111 @@
+//! Desktop notifications. The app talks to a [`Notifier`], so the backend
+//! can be swapped: freedesktop notifications over D-Bus on Linux, nothing
+//! elsewhere or until the notification service has been reached.
+
+#[cfg(target_os = "linux")]
+mod freedesktop;
+
+use std::path::PathBuf;
+
+use iced::futures::Stream;
+use tokio::sync::mpsc;
+
+/// A notification about a finished download.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub summary: String,
+    pub body: String,
+    /// Output file, offered through "Open" and "Show in folder" actions.
+    pub file: Option<PathBuf>,
+}
+
+/// Something that can show notifications.
+pub trait Notifier {
+    fn notify(&self, notification: Notification);
+}
+
+/// Drops every notification.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NullNotifier;
+
+impl Notifier for NullNotifier {
+    fn notify(&self, _notification: Notification) {}
+}
+
+/// Keeps every notification, for tests.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordingNotifier {
+    pub sent: std::sync::Arc<std::sync::Mutex<Vec<Notification>>>,
+}
+
+#[cfg(test)]
+impl Notifier for RecordingNotifier {
+    fn notify(&self, notification: Notification) {
+        self.sent.lock().unwrap().push(notification);
+    }
+}
+
+/// Hands notifications to the backend started by [`run`].
+#[derive(Debug, Clone)]
+pub struct DesktopNotifier {
+    requests: mpsc::UnboundedSender<Notification>,
+}
+
+impl Notifier for DesktopNotifier {
+    fn notify(&self, notification: Notification) {
+        let _ = self.requests.send(notification);
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationAction {
+    Open,
+    ShowInFolder,
+}
+
+#[derive(Debug, Clone)]
+pub enum NotifyEvent {
+    Ready(DesktopNotifier),
+    Unavailable(String),
+    /// A notification could not be shown.
+    Failed(String),
+    /// The user clicked an action on the notification for `file`.
+    Action(NotificationAction, PathBuf),
+}
+
+/// Connects to the notification service and forwards clicked actions for as
+/// long as the stream is polled.
+pub fn run() -> impl Stream<Item = NotifyEvent> {
+    async_stream::stream! {
+        #[cfg(target_os = "linux")]
+        {
+            let (requests_tx, mut requests_rx) = mpsc::unbounded_channel();
+
+            match freedesktop::Service::connect().await {
+                Ok(mut service) => {
+                    yield NotifyEvent::Ready(DesktopNotifier { requests: requests_tx });
+                    loop {
+                        tokio::select! {
+                            request = requests_rx.recv() => match request {
+                                Some(notification) => {
+                                    if let Err(e) = service.show(notification).await {
+                                        yield NotifyEvent::Failed(e);
+                                    }
+                                }
+                                None => break,
+                            },
+                            Some((action, file)) = service.next_action() => {
+                                yield NotifyEvent::Action(action, file);
+                            }
+                        }
+                    }
+                }
+                Err(e) => yield NotifyEvent::Unavailable(e),
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        yield NotifyEvent::Unavailable("Notifications are only supported on Linux".to_string());
+    }
+}
@@ -0,0 +1,140 @@
+//! Opening downloaded files with the desktop's default applications.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Opens files and folders on behalf of the app.
+pub trait Opener: Send + Sync {
+    /// Opens the file with its default application.
+    fn open(&self, path: &Path) -> Result<(), String>;
+
+    /// Shows the file in the file manager, selected where supported.
+    fn reveal(&self, path: &Path) -> Result<(), String>;
+}
+
+/// Uses the platform's own tools: `xdg-open` and the FileManager1 D-Bus
+/// interface on Linux, `open` on macOS and Explorer on Windows.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemOpener;
+
+impl Opener for SystemOpener {
+    fn open(&self, path: &Path) -> Result<(), String> {
+        ensure_exists(path)?;
+
+        #[cfg(target_os = "windows")]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            crate::tools::hide_window(&mut command);
+            command
+        };
+        #[cfg(target_os = "macos")]
+        let mut command = Command::new("open");
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        let mut command = Command::new("xdg-open");
+
+        spawn(command.arg(path), path)
+    }
+
+    fn reveal(&self, path: &Path) -> Result<(), String> {
+        ensure_exists(path)?;
+
+        #[cfg(target_os = "windows")]
+        {
+            let mut select = std::ffi::OsString::from("/select,");
+            select.push(path);
+            spawn(Command::new("explorer").arg(select), path)
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            spawn(Command::new("open").arg("-R").arg(path), path)
+        }
+
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            // File managers implementing FileManager1 open the folder with the
+            // file selected; otherwise just open the folder.
+            let selected = Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--print-reply",
+                    "--dest=org.freedesktop.FileManager1",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                ])
+                .arg(format!("array:string:{}", file_uri(path)?))
+                .arg("string:")
+                .output()
+                .is_ok_and(|output| output.status.success());
+            if selected {
+                return Ok(());
+            }
+
+            let folder = path.parent().unwrap_or(path);
+            spawn(Command::new("xdg-open").arg(folder), folder)
+        }
+    }
+}
+
+fn ensure_exists(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        Ok(())
+    } else {
+        Err(format!("{} no longer exists", path.display()))
+    }
+}
+
+/// Starts a helper without waiting for it. It is reaped from a thread of
+/// its own, so no zombie is left behind once it exits.
+fn spawn(command: &mut Command, path: &Path) -> Result<(), String> {
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    std::thread::spawn(move || {
+        let _ = child.wait();
+    });
+    Ok(())
+}
+
+/// `file://` URI for an absolute path, percent-encoding everything but
+/// unreserved characters and separators.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn file_uri(path: &Path) -> Result<String, String> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let absolute = std::path::absolute(path)
+        .map_err(|e| format!("Failed to resolve {}: {}", path.display(), e))?;
+
+    let mut uri = "file://".to_string();
+    for &byte in absolute.as_os_str().as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            uri.push(char::from(byte));
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    Ok(uri)
+}
+
+/// Records what would have been opened, for tests.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub struct RecordingOpener {
+    pub calls: std::sync::Arc<std::sync::Mutex<Vec<(&'static str, std::path::PathBuf)>>>,
+}
+
+#[cfg(test)]
+impl Opener for RecordingOpener {
+    fn open(&self, path: &Path) -> Result<(), String> {
+        ensure_exists(path)?;
+        self.calls.lock().unwrap().push(("open", path.to_path_buf()));
+        Ok(())
+    }
+
+    fn reveal(&self, path: &Path) -> Result<(), String> {
+        ensure_exists(path)?;
+        self.calls.lock().unwrap().push(("reveal", path.to_path_buf()));
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+//! Paths typed into the settings: expansion of `~` and environment
+//! variables, and checks shown next to the fields.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expands a leading `~` and `$VAR` / `${VAR}` references. Unknown variables
+/// are left as written so the mistake stays visible.
+pub fn expand(input: &str) -> PathBuf {
+    let input = input.trim();
+    let mut expanded = String::with_capacity(input.len());
+    let mut rest = input;
+
+    if let Some(after) = rest.strip_prefix('~')
+        && (after.is_empty() || after.starts_with('/') || after.starts_with(std::path::MAIN_SEPARATOR))
+        && let Some(home) = dirs::home_dir()
+    {
+        expanded.push_str(&home.to_string_lossy());
+        rest = after;
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            },
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+
+        match std::env::var(name).ok().filter(|_| !name.is_empty()) {
+            Some(value) => {
+                expanded.push_str(&value);
+                rest = remainder;
+            }
+            None => {
+                expanded.push('$');
+                rest = after;
+            }
+        }
+    }
+    expanded.push_str(rest);
+
+    PathBuf::from(expanded)
+}
+
+/// Checks an output directory: it must be a writable directory, or not
+/// exist yet with a parent yt-dlp can create it in.
+pub fn check_directory(input: &str) -> Result<(), String> {
+    if input.trim().is_empty() {
+        return Ok(());
+    }
+    let path = expand(input);
+
+    match fs::metadata(&path) {
+        Ok(metadata) if !metadata.is_dir() => Err(format!("{} is not a folder", path.display())),
+        Ok(metadata) if metadata.permissions().readonly() => {
+            Err(format!("{} is read-only", path.display()))
+        }
+        Ok(_) => Ok(()),
+        Err(_) => match nearest_existing(&path) {
+            Some(parent) if parent.is_dir() => Ok(()),
+            _ => Err(format!("{} does not exist", path.display())),
+        },
+    }
+}
+
+/// Checks a file that yt-dlp will read, such as a cookies file.
+pub fn check_file(input: &str) -> Result<(), String> {
+    if input.trim().is_empty() {
+        return Ok(());
+    }
+    let path = expand(input);
+
+    match fs::metadata(&path) {
+        Ok(metadata) if metadata.is_dir() => Err(format!("{} is a folder, not a file", path.display())),
+        Ok(_) => fs::File::open(&path)
+            .map(|_| ())
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e)),
+        Err(_) => Err(format!("{} does not exist", path.display())),
+    }
+}
+
+/// Checks a program path. A bare name is looked up on `PATH` when run, so
+/// only paths with a directory part are checked.
+pub fn check_executable(input: &str) -> Result<(), String> {
+    let path = expand(input);
+    if input.trim().is_empty() || path.parent().is_none_or(|p| p.as_os_str().is_empty()) {
+        return Ok(());
+    }
+
+    let metadata = fs::metadata(&path).map_err(|_| format!("{} does not exist", path.display()))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if metadata.permissions().mode() & 0o111 == 0 {
+            return Err(format!("{} is not executable", path.display()));
+        }
+    }
+    Ok(())
+}
+
+/// Closest ancestor that exists; relative paths end at the working directory.
+fn nearest_existing(path: &Path) -> Option<&Path> {
+    path.ancestors()
+        .skip(1)
+        .map(|p| if p.as_os_str().is_empty() { Path::new(".") } else { p })
+        .find(|p| p.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expand_replaces_home_and_variables() {
+        let home = dirs::home_dir().unwrap();
+        let var = std::env::var("HOME").unwrap();
+        assert_eq!(expand("~"), home);
+        assert_eq!(expand(" ~/Videos "), home.join("Videos"));
+        assert_eq!(expand("~other/Videos"), PathBuf::from("~other/Videos"));
+        assert_eq!(expand("$HOME/a"), PathBuf::from(format!("{}/a", var)));
+        assert_eq!(expand("${HOME}_a"), PathBuf::from(format!("{}_a", var)));
+        assert_eq!(expand("$YT_DLGUI_UNSET/a"), PathBuf::from("$YT_DLGUI_UNSET/a"));
+        assert_eq!(expand("${HOME"), PathBuf::from("${HOME"));
+    }
+
+    #[test]
+    fn checks_report_missing_and_wrong_kinds_of_paths() {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-paths-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("cookies.txt");
+        fs::write(&file, "# Netscape HTTP Cookie File").unwrap();
+        let dir_str = dir.to_string_lossy().to_string();
+        let file_str = file.to_string_lossy().to_string();
+
+        assert_eq!(check_directory(""), Ok(()));
+        assert_eq!(check_directory(&dir_str), Ok(()));
+        assert_eq!(check_directory(&format!("{}/new/nested", dir_str)), Ok(()));
+        assert!(check_directory(&file_str).unwrap_err().ends_with("is not a folder"));
+        assert!(check_directory(&format!("{}/below", file_str)).unwrap_err().ends_with("does not exist"));
+
+        assert_eq!(check_file(&file_str), Ok(()));
+        assert!(check_file(&dir_str).unwrap_err().ends_with("is a folder, not a file"));
+        assert!(check_file(&format!("{}/missing.txt", dir_str)).unwrap_err().ends_with("does not exist"));
+
+        assert_eq!(check_executable("yt-dlp"), Ok(()));
+        assert!(check_executable(&dir_str).unwrap_err().ends_with("is not a file"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert!(check_executable(&file_str).unwrap_err().ends_with("is not executable"));
+            fs::set_permissions(&file, fs::Permissions::from_mode(0o755)).unwrap();
+            assert_eq!(check_executable(&file_str), Ok(()));
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,228 @@
+//! Native file and folder pickers. Linux uses the XDG desktop portal, with
+//! zenity or kdialog as fallbacks; macOS and Windows use the system
+//! dialogs through `osascript` and PowerShell.
+
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickKind {
+    Folder,
+    File,
+}
+
+/// Shows a picker and returns the chosen path, or `None` when cancelled.
+pub async fn pick(kind: PickKind, title: String, start: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    #[cfg(target_os = "linux")]
+    {
+        match portal::pick(kind, &title, start.as_deref()).await {
+            Ok(picked) => Ok(picked),
+            Err(portal_error) => fallback(kind, &title, start)
+                .await
+                .map_err(|_| portal_error),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        fallback(kind, &title, start).await
+    }
+}
+
+/// Runs a dialog program that prints the chosen path, exiting non-zero when
+/// the user cancels.
+async fn run_dialog(program: &str, args: Vec<String>) -> Result<Option<PathBuf>, String> {
+    let mut command = tokio::process::Command::new(program);
+    command.args(&args);
+    #[cfg(windows)]
+    command.creation_flags(0x08000000); // CREATE_NO_WINDOW, as in tools::hide_window
+
+    let output = command
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    let chosen = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || chosen.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(PathBuf::from(chosen)))
+}
+
+#[cfg(target_os = "linux")]
+async fn fallback(kind: PickKind, title: &str, start: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    let start = start.map(|p| p.to_string_lossy().to_string());
+
+    let mut zenity = vec!["--file-selection".to_string(), format!("--title={}", title)];
+    if kind == PickKind::Folder {
+        zenity.push("--directory".to_string());
+    }
+    if let Some(ref start) = start {
+        zenity.push(format!("--filename={}/", start.trim_end_matches('/')));
+    }
+    if let Ok(picked) = run_dialog("zenity", zenity).await {
+        return Ok(picked);
+    }
+
+    let kdialog = vec![
+        match kind {
+            PickKind::Folder => "--getexistingdirectory",
+            PickKind::File => "--getopenfilename",
+        }
+        .to_string(),
+        start.unwrap_or_else(|| ".".to_string()),
+        "--title".to_string(),
+        title.to_string(),
+    ];
+    run_dialog("kdialog", kdialog).await
+}
+
+#[cfg(target_os = "macos")]
+async fn fallback(kind: PickKind, title: &str, _start: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    let chooser = match kind {
+        PickKind::Folder => "choose folder",
+        PickKind::File => "choose file",
+    };
+    let script = format!(
+        "POSIX path of ({} with prompt \"{}\")",
+        chooser,
+        title.replace('"', "\\\"")
+    );
+    run_dialog("osascript", vec!["-e".to_string(), script]).await
+}
+
+#[cfg(target_os = "windows")]
+async fn fallback(kind: PickKind, title: &str, start: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    let title = title.replace('\'', "''");
+    let start = start
+        .map(|p| p.to_string_lossy().replace('\'', "''"))
+        .unwrap_or_default();
+    let dialog = match kind {
+        PickKind::Folder => format!(
+            "$d = New-Object System.Windows.Forms.FolderBrowserDialog; $d.Description = '{}'; \
+             $d.SelectedPath = '{}'; if ($d.ShowDialog() -eq 'OK') {{ $d.SelectedPath }}",
+            title, start
+        ),
+        PickKind::File => format!(
+            "$d = New-Object System.Windows.Forms.OpenFileDialog; $d.Title = '{}'; \
+             $d.InitialDirectory = '{}'; if ($d.ShowDialog() -eq 'OK') {{ $d.FileName }}",
+            title, start
+        ),
+    };
+    let script = format!("Add-Type -AssemblyName System.Windows.Forms; {}", dialog);
+    run_dialog(
+        "powershell",
+        vec!["-NoProfile".to_string(), "-STA".to_string(), "-Command".to_string(), script],
+    )
+    .await
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+async fn fallback(_kind: PickKind, _title: &str, _start: Option<PathBuf>) -> Result<Option<PathBuf>, String> {
+    Err("No file picker is available on this platform".to_string())
+}
+
+#[cfg(target_os = "linux")]
+mod portal {
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use iced::futures::StreamExt;
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+    use zbus::{Connection, Proxy};
+
+    use super::PickKind;
+
+    const DESTINATION: &str = "org.freedesktop.portal.Desktop";
+
+    /// Requests made so far, to tell their handle tokens apart.
+    static REQUESTS: AtomicU64 = AtomicU64::new(0);
+
+    /// Asks the portal's FileChooser. The reply arrives as a `Response`
+    /// signal on a request object whose path is known up front from the
+    /// handle token, so it is subscribed to before the call is made.
+    pub async fn pick(kind: PickKind, title: &str, start: Option<&Path>) -> Result<Option<PathBuf>, String> {
+        let error = |e: zbus::Error| format!("File picker unavailable: {}", e);
+
+        let connection = Connection::session().await.map_err(error)?;
+        let sender = connection
+            .unique_name()
+            .map(|name| name.trim_start_matches(':').replace('.', "_"))
+            .ok_or("File picker unavailable: no bus name")?;
+        // Each request needs its own object path, so the token differs
+        // between calls as well as between processes.
+        let count = REQUESTS.fetch_add(1, Ordering::Relaxed);
+        let token = format!("ytdlgui{}_{}", std::process::id(), count);
+        let handle = format!("/org/freedesktop/portal/desktop/request/{}/{}", sender, token);
+
+        let request = Proxy::new(&connection, DESTINATION, handle, "org.freedesktop.portal.Request")
+            .await
+            .map_err(error)?;
+        let mut responses = request.receive_signal("Response").await.map_err(error)?;
+
+        let mut options: HashMap<&str, Value<'_>> = HashMap::new();
+        options.insert("handle_token", Value::from(token.as_str()));
+        options.insert("modal", Value::from(true));
+        options.insert("directory", Value::from(kind == PickKind::Folder));
+        if let Some(start) = start.filter(|p| p.is_dir()) {
+            // A NUL-terminated byte string.
+            let mut folder = start.to_string_lossy().as_bytes().to_vec();
+            folder.push(0);
+            options.insert("current_folder", Value::from(folder));
+        }
+
+        let chooser = Proxy::new(
+            &connection,
+            DESTINATION,
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.FileChooser",
+        )
+        .await
+        .map_err(error)?;
+        let _: OwnedObjectPath = chooser
+            .call("OpenFile", &("", title, options))
+            .await
+            .map_err(error)?;
+
+        let response = responses
+            .next()
+            .await
+            .ok_or("File picker closed without answering")?;
+        let (code, results): (u32, HashMap<String, OwnedValue>) = response
+            .body()
+            .deserialize()
+            .map_err(|e| format!("Unexpected file picker response: {}", e))?;
+
+        // 0 is success, 1 cancelled by the user.
+        if code != 0 {
+            return Ok(None);
+        }
+
+        let uris: Vec<String> = results
+            .get("uris")
+            .and_then(|uris| Vec::<String>::try_from(uris.try_clone().ok()?).ok())
+            .unwrap_or_default();
+        Ok(uris.first().and_then(|uri| path_from_uri(uri)))
+    }
+
+    fn path_from_uri(uri: &str) -> Option<PathBuf> {
+        let encoded = uri.strip_prefix("file://")?.as_bytes();
+        let mut decoded = Vec::with_capacity(encoded.len());
+        let mut i = 0;
+        while i < encoded.len() {
+            let byte = match encoded[i] {
+                b'%' if i + 2 < encoded.len() => {
+                    let hex = std::str::from_utf8(&encoded[i + 1..i + 3]).ok()?;
+                    i += 2;
+                    u8::from_str_radix(hex, 16).ok()?
+                }
+                byte => byte,
+            };
+            decoded.push(byte);
+            i += 1;
+        }
+
+        use std::os::unix::ffi::OsStringExt;
+        Some(PathBuf::from(std::ffi::OsString::from_vec(decoded)))
+    }
+}
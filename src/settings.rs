@@ -1,10 +1,40 @@
 use serde::{Deserialize, Serialize};
 
+use crate::disk::SpaceCheck;
+use crate::download::archive::ArchiveScope;
+use crate::theme::ThemeChoice;
+use crate::tools::ytdlp::UpdateChannel;
+use crate::tray::CloseAction;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AdvancedSettings {
+    // Appearance
+    pub theme: ThemeChoice,
+
+    // Tray
+    pub tray_icon: bool,
+    pub minimize_to_tray: bool,
+    pub close_action: CloseAction,
+
+    // Notifications
+    pub notify_on_success: bool,
+    pub notify_on_failure: bool,
+    pub notify_in_background_only: bool,
+
     // Output
     pub output_directory: String,
+    /// Where partial downloads and intermediate files go (`-P temp:`).
+    pub temp_directory: String,
+    pub subtitle_directory: String,
+    pub thumbnail_directory: String,
     pub filename_template: String,
+    pub download_archive: ArchiveScope,
+
+    // Disk Space
+    pub disk_space_check: SpaceCheck,
+    /// Free space to leave after a download, e.g. `1G`.
+    pub disk_space_reserve: String,
 
     // Quality
     pub preferred_quality: String,
@@ -27,22 +57,56 @@ pub struct AdvancedSettings {
     pub proxy: String,
     pub rate_limit: String,
     pub concurrent_fragments: String,
+    pub global_rate_limit: String,
+    pub bandwidth_schedule: String,
+    pub max_concurrent_downloads: String,
 
     // Authentication
     pub cookies_file: String,
 
     // Advanced
     pub ytdlp_path: String,
+    pub ffmpeg_location: String,
     pub js_runtimes: String,
     pub extra_arguments: String,
+    pub watch_clipboard: bool,
+    pub identify_extractor: bool,
+
+    // yt-dlp Management
+    pub use_managed_ytdlp: bool,
+    pub ytdlp_update_channel: UpdateChannel,
+    /// Release download URL laid out like GitHub's; empty follows the
+    /// update channel. Updates follow it only when it is a GitHub release.
+    pub ytdlp_mirror_url: String,
 }
 
 impl Default for AdvancedSettings {
     fn default() -> Self {
         Self {
+            // Appearance
+            theme: ThemeChoice::Dark,
+
+            // Tray
+            tray_icon: true,
+            minimize_to_tray: false,
+            close_action: CloseAction::Quit,
+
+            // Notifications
+            notify_on_success: true,
+            notify_on_failure: true,
+            notify_in_background_only: true,
+
             // Output
             output_directory: String::new(),
+            temp_directory: String::new(),
+            subtitle_directory: String::new(),
+            thumbnail_directory: String::new(),
             filename_template: "%(title)s.%(ext)s".to_string(),
+            download_archive: ArchiveScope::Off,
+
+            // Disk Space
+            disk_space_check: SpaceCheck::Warn,
+            disk_space_reserve: "1G".to_string(),
 
             // Quality
             preferred_quality: String::new(),
@@ -65,14 +129,25 @@ impl Default for AdvancedSettings {
             proxy: String::new(),
             rate_limit: String::new(),
             concurrent_fragments: "1".to_string(),
+            global_rate_limit: String::new(),
+            bandwidth_schedule: String::new(),
+            max_concurrent_downloads: "2".to_string(),
 
             // Authentication
             cookies_file: String::new(),
 
             // Advanced
             ytdlp_path: "yt-dlp".to_string(),
+            ffmpeg_location: String::new(),
             js_runtimes: String::new(),
             extra_arguments: String::new(),
+            watch_clipboard: false,
+            identify_extractor: true,
+
+            // yt-dlp Management
+            use_managed_ytdlp: false,
+            ytdlp_update_channel: UpdateChannel::Stable,
+            ytdlp_mirror_url: String::new(),
         }
     }
 }
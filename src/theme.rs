@@ -1,208 +1,493 @@
-use iced::widget::{button, container, pick_list, progress_bar, scrollable, text_input, toggler, rule};
+use iced::widget::{
+    button, container, pick_list, progress_bar, rule, scrollable, text, text_editor, text_input, toggler,
+};
 use iced::{Background, Border, Color, Theme};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
 
-pub fn custom_theme() -> Theme {
-    Theme::custom(
-        "yt-dlgui Dark".to_string(),
-        iced::theme::Palette {
-            background: Color::from_rgb(0.10, 0.10, 0.12),
-            text: Color::from_rgb(0.90, 0.90, 0.90),
-            primary: Color::from_rgb(0.85, 0.20, 0.25),
-            success: Color::from_rgb(0.30, 0.70, 0.35),
-            danger: Color::from_rgb(0.90, 0.25, 0.25),
-            warning: Color::from_rgb(0.95, 0.75, 0.20),
-        },
-    )
+/// Which theme the app uses.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeChoice {
+    /// Light or Dark, following the desktop's preference.
+    System,
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+    /// A user theme file, by its name.
+    Custom(String),
 }
 
-pub fn window_container_style(_theme: &Theme) -> container::Style {
-    container::Style {
-        background: Some(Background::Color(Color::from_rgb(0.10, 0.10, 0.12))),
-        text_color: Some(Color::from_rgb(0.90, 0.90, 0.90)),
-        border: Border {
-            color: Color::from_rgba(0.85, 0.20, 0.25, 0.6),
-            width: 1.0,
-            radius: 8.0.into(),
-        },
-        ..Default::default()
+impl ThemeChoice {
+    pub const BUILT_IN: [ThemeChoice; 4] = [
+        ThemeChoice::System,
+        ThemeChoice::Dark,
+        ThemeChoice::Light,
+        ThemeChoice::HighContrast,
+    ];
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThemeChoice::System => write!(f, "Follow System"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::HighContrast => write!(f, "High Contrast"),
+            ThemeChoice::Custom(name) => write!(f, "{}", name),
+        }
     }
 }
 
-pub fn title_bar_style(_theme: &Theme) -> container::Style {
-    container::Style {
-        background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.2))),
-        text_color: Some(Color::from_rgb(0.90, 0.90, 0.90)),
-        border: Border {
-            color: Color::from_rgba(0.25, 0.25, 0.28, 0.3),
-            width: 0.0,
-            radius: 0.0.into(),
-        },
-        ..Default::default()
+/// A complete theme: the six palette colours every style is derived from,
+/// plus the corner radius. User theme files (TOML or JSON) have the same
+/// fields, with colours as `#rrggbb`; missing fields fall back to Dark.
+#[derive(Debug, Clone)]
+pub struct ThemeSpec {
+    pub name: String,
+    pub palette: iced::theme::Palette,
+    pub radius: f32,
+}
+
+impl ThemeSpec {
+    pub fn dark() -> Self {
+        Self {
+            name: "yt-dlgui Dark".to_string(),
+            palette: iced::theme::Palette {
+                background: Color::from_rgb(0.10, 0.10, 0.12),
+                text: Color::from_rgb(0.90, 0.90, 0.90),
+                primary: Color::from_rgb(0.85, 0.20, 0.25),
+                success: Color::from_rgb(0.30, 0.70, 0.35),
+                danger: Color::from_rgb(0.90, 0.25, 0.25),
+                warning: Color::from_rgb(0.95, 0.75, 0.20),
+            },
+            radius: 4.0,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "yt-dlgui Light".to_string(),
+            palette: iced::theme::Palette {
+                background: Color::from_rgb(0.96, 0.96, 0.97),
+                text: Color::from_rgb(0.12, 0.12, 0.14),
+                primary: Color::from_rgb(0.80, 0.15, 0.20),
+                success: Color::from_rgb(0.15, 0.55, 0.25),
+                danger: Color::from_rgb(0.80, 0.15, 0.15),
+                warning: Color::from_rgb(0.70, 0.45, 0.00),
+            },
+            radius: 4.0,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "yt-dlgui High Contrast".to_string(),
+            palette: iced::theme::Palette {
+                background: Color::BLACK,
+                text: Color::WHITE,
+                primary: Color::from_rgb(1.0, 0.85, 0.0),
+                success: Color::from_rgb(0.0, 1.0, 0.4),
+                danger: Color::from_rgb(1.0, 0.3, 0.3),
+                warning: Color::from_rgb(1.0, 0.6, 0.0),
+            },
+            radius: 2.0,
+        }
+    }
+
+    /// Builds the iced theme. iced themes only carry colours, so the radius
+    /// is passed to the style functions that need it.
+    pub fn apply(&self) -> Theme {
+        Theme::custom(self.name.clone(), self.palette)
     }
 }
 
-pub fn title_bar_button_style(_theme: &Theme, status: button::Status) -> button::Style {
-    let base = button::Style {
-        background: Some(Background::Color(Color::TRANSPARENT)),
-        text_color: Color::from_rgb(0.70, 0.70, 0.70),
-        border: Border {
-            radius: 4.0.into(),
-            ..Default::default()
-        },
-        ..Default::default()
+/// Palette entries of a theme file, as `#rrggbb` strings.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    name: Option<String>,
+    background: Option<String>,
+    text: Option<String>,
+    primary: Option<String>,
+    success: Option<String>,
+    danger: Option<String>,
+    warning: Option<String>,
+    radius: Option<f32>,
+}
+
+/// Reads a user theme from a `.toml` or `.json` file. The file name is used
+/// when the theme has no `name`.
+pub fn load_theme_file(path: &Path) -> Result<ThemeSpec, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let file: ThemeFile = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => serde_json::from_str(&content).map_err(|e| format!("Invalid theme {}: {}", path.display(), e))?,
+        _ => toml::from_str(&content).map_err(|e| format!("Invalid theme {}: {}", path.display(), e))?,
     };
 
-    match status {
-        button::Status::Active => base,
-        button::Status::Hovered => button::Style {
-            background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.1))),
-            text_color: Color::from_rgb(0.90, 0.90, 0.90),
-            ..base
-        },
-        button::Status::Pressed => button::Style {
-            background: Some(Background::Color(Color::from_rgba(1.0, 1.0, 1.0, 0.05))),
-            ..base
-        },
-        button::Status::Disabled => base,
+    let fallback = ThemeSpec::dark();
+    let color = |value: Option<String>, default: Color| -> Result<Color, String> {
+        match value {
+            Some(hex) => parse_hex(hex.trim()).ok_or_else(|| format!("Invalid colour \"{}\" in {}", hex, path.display())),
+            None => Ok(default),
+        }
+    };
+
+    Ok(ThemeSpec {
+        name: file.name.unwrap_or_else(|| {
+            path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default()
+        }),
+        palette: iced::theme::Palette {
+            background: color(file.background, fallback.palette.background)?,
+            text: color(file.text, fallback.palette.text)?,
+            primary: color(file.primary, fallback.palette.primary)?,
+            success: color(file.success, fallback.palette.success)?,
+            danger: color(file.danger, fallback.palette.danger)?,
+            warning: color(file.warning, fallback.palette.warning)?,
+        },
+        radius: file.radius.unwrap_or(fallback.radius).max(0.0),
+    })
+}
+
+/// Themes found in the user's themes folder, with the files that failed
+/// to load.
+#[derive(Debug, Clone, Default)]
+pub struct UserThemes {
+    pub themes: Vec<ThemeSpec>,
+    pub errors: Vec<String>,
+}
+
+impl UserThemes {
+    pub fn load(dir: &Path) -> Self {
+        let mut user = Self::default();
+        let Ok(entries) = fs::read_dir(dir) else {
+            return user;
+        };
+
+        let mut paths: Vec<_> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("toml" | "json")))
+            .collect();
+        paths.sort();
+
+        // Themes are chosen by name, so only the first file with a name counts
+        let mut loaded_from: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            let spec = match load_theme_file(&path) {
+                Ok(spec) => spec,
+                Err(e) => {
+                    user.errors.push(e);
+                    continue;
+                }
+            };
+            if ThemeChoice::BUILT_IN.iter().any(|choice| choice.to_string() == spec.name) {
+                user.errors.push(format!(
+                    "Theme \"{}\" in {} has the name of a built-in theme",
+                    spec.name,
+                    path.display()
+                ));
+            } else if let Some(i) = user.themes.iter().position(|t| t.name == spec.name) {
+                user.errors.push(format!(
+                    "Theme \"{}\" in {} is already defined in {}",
+                    spec.name,
+                    path.display(),
+                    loaded_from[i].display()
+                ));
+            } else {
+                user.themes.push(spec);
+                loaded_from.push(path);
+            }
+        }
+        user
+    }
+
+    /// Resolves a choice to a theme. A user theme that no longer exists
+    /// falls back to Dark, as does System when the desktop has no preference.
+    pub fn resolve(&self, choice: &ThemeChoice, system: iced::theme::Mode) -> ThemeSpec {
+        match choice {
+            ThemeChoice::System => match system {
+                iced::theme::Mode::Light => ThemeSpec::light(),
+                _ => ThemeSpec::dark(),
+            },
+            ThemeChoice::Dark => ThemeSpec::dark(),
+            ThemeChoice::Light => ThemeSpec::light(),
+            ThemeChoice::HighContrast => ThemeSpec::high_contrast(),
+            ThemeChoice::Custom(name) => self
+                .themes
+                .iter()
+                .find(|t| &t.name == name)
+                .cloned()
+                .unwrap_or_else(ThemeSpec::dark),
+        }
+    }
+
+    /// Everything selectable in the theme picker.
+    pub fn choices(&self) -> Vec<ThemeChoice> {
+        ThemeChoice::BUILT_IN
+            .iter()
+            .cloned()
+            .chain(self.themes.iter().map(|t| ThemeChoice::Custom(t.name.clone())))
+            .collect()
     }
 }
 
-pub fn close_button_style(_theme: &Theme, status: button::Status) -> button::Style {
-    let base = button::Style {
-        background: Some(Background::Color(Color::TRANSPARENT)),
-        text_color: Color::from_rgb(0.70, 0.70, 0.70),
-        border: Border {
-            radius: 4.0.into(),
-            ..Default::default()
-        },
-        ..Default::default()
-    };
+/// Parses `#rrggbb` or `#rrggbbaa`.
+fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    if !matches!(hex.len(), 6 | 8) || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    let alpha = if hex.len() == 8 { channel(6)? } else { 255 };
+    Some(Color::from_rgba8(channel(0)?, channel(2)?, channel(4)?, f32::from(alpha) / 255.0))
+}
 
-    match status {
-        button::Status::Active => base,
-        button::Status::Hovered => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.85, 0.20, 0.25))),
-            text_color: Color::WHITE,
-            ..base
-        },
-        button::Status::Pressed => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.70, 0.15, 0.20))),
-            text_color: Color::WHITE,
-            ..base
-        },
-        button::Status::Disabled => base,
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    Color {
+        r: a.r + (b.r - a.r) * t,
+        g: a.g + (b.g - a.g) * t,
+        b: a.b + (b.b - a.b) * t,
+        a: a.a + (b.a - a.a) * t,
     }
 }
 
-pub fn modal_backdrop_style(_theme: &Theme) -> container::Style {
-    container::Style {
-        background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.6))),
-        ..Default::default()
+/// Shades used across the styles, all derived from the theme's palette by
+/// blending the background towards the text colour.
+struct Shades {
+    background: Color,
+    /// Sections and cards.
+    surface: Color,
+    /// Text inputs and pick lists.
+    field: Color,
+    /// Secondary buttons.
+    raised: Color,
+    border: Color,
+    border_hover: Color,
+    placeholder: Color,
+    muted: Color,
+    heading: Color,
+    text: Color,
+    primary: Color,
+    /// Text drawn on top of `primary` or `danger`.
+    on_accent: Color,
+    success: Color,
+    danger: Color,
+    warning: Color,
+}
+
+fn shades(theme: &Theme) -> Shades {
+    let palette = theme.palette();
+    let tone = |t: f32| mix(palette.background, palette.text, t);
+    let luminance = |c: Color| 0.2126 * c.r + 0.7152 * c.g + 0.0722 * c.b;
+
+    Shades {
+        background: palette.background,
+        surface: tone(0.025),
+        field: tone(0.0625),
+        raised: tone(0.1875),
+        border: tone(0.1875),
+        border_hover: tone(0.3125),
+        placeholder: tone(0.5),
+        muted: tone(0.625),
+        heading: tone(0.75),
+        text: palette.text,
+        primary: palette.primary,
+        on_accent: if luminance(palette.primary) > 0.6 { Color::BLACK } else { Color::WHITE },
+        success: palette.success,
+        danger: palette.danger,
+        warning: palette.warning,
     }
 }
 
-pub fn modal_container_style(_theme: &Theme) -> container::Style {
-    container::Style {
-        background: Some(Background::Color(Color::from_rgba(0.15, 0.15, 0.17, 1.0))),
-        text_color: Some(Color::from_rgb(0.90, 0.90, 0.90)),
-        border: Border {
-            color: Color::from_rgb(0.25, 0.25, 0.28),
-            width: 1.0,
-            radius: 8.0.into(),
-        },
-        ..Default::default()
+/// Brightens or darkens `color` towards the text colour, for hover states.
+fn emphasize(shades: &Shades, color: Color) -> Color {
+    mix(color, shades.text, 0.12)
+}
+
+/// Moves `color` towards the background, for pressed states.
+fn subdue(shades: &Shades, color: Color) -> Color {
+    mix(color, shades.background, 0.15)
+}
+
+pub fn muted_text_style(theme: &Theme) -> text::Style {
+    text::Style { color: Some(shades(theme).muted) }
+}
+
+pub fn heading_text_style(theme: &Theme) -> text::Style {
+    text::Style { color: Some(shades(theme).heading) }
+}
+
+pub fn success_text_style(theme: &Theme) -> text::Style {
+    text::Style { color: Some(shades(theme).success) }
+}
+
+pub fn warning_text_style(theme: &Theme) -> text::Style {
+    text::Style { color: Some(shades(theme).warning) }
+}
+
+pub fn error_text_style(theme: &Theme) -> text::Style {
+    text::Style { color: Some(shades(theme).danger) }
+}
+
+/// Secondary error text, such as yt-dlp's message under an error heading.
+pub fn error_detail_text_style(theme: &Theme) -> text::Style {
+    let shades = shades(theme);
+    text::Style { color: Some(mix(shades.danger, shades.muted, 0.5)) }
+}
+
+/// Colours for the speed graph: background, fill and line.
+pub fn graph_colors(theme: &Theme) -> (Color, Color, Color) {
+    let shades = shades(theme);
+    (shades.field, Color { a: 0.2, ..shades.primary }, shades.primary)
+}
+
+pub fn window_container_style(radius: f32) -> impl Fn(&Theme) -> container::Style {
+    move |theme| {
+        let shades = shades(theme);
+        container::Style {
+            background: Some(Background::Color(shades.background)),
+            text_color: Some(shades.text),
+            border: Border {
+                color: Color { a: 0.6, ..shades.primary },
+                width: 1.0,
+                radius: (radius * 2.0).into(),
+            },
+            ..Default::default()
+        }
     }
 }
 
-pub fn section_style(_theme: &Theme) -> container::Style {
+pub fn title_bar_style(theme: &Theme) -> container::Style {
+    let shades = shades(theme);
     container::Style {
-        background: Some(Background::Color(Color::from_rgba(0.12, 0.12, 0.14, 1.0))),
+        background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.2))),
+        text_color: Some(shades.text),
         border: Border {
-            color: Color::from_rgb(0.20, 0.20, 0.22),
-            width: 1.0,
-            radius: 4.0.into(),
+            color: Color { a: 0.3, ..shades.border },
+            width: 0.0,
+            radius: 0.0.into(),
         },
         ..Default::default()
     }
 }
 
-pub fn primary_button_style(theme: &Theme, status: button::Status) -> button::Style {
-    let palette = theme.palette();
-    let base = button::Style {
-        background: Some(Background::Color(palette.primary)),
-        text_color: Color::WHITE,
-        border: Border {
-            radius: 4.0.into(),
+pub fn title_bar_button_style(radius: f32) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base = button::Style {
+            background: Some(Background::Color(Color::TRANSPARENT)),
+            text_color: shades.heading,
+            border: Border {
+                radius: radius.into(),
+                ..Default::default()
+            },
             ..Default::default()
-        },
-        ..Default::default()
-    };
+        };
 
-    match status {
-        button::Status::Active => base,
-        button::Status::Hovered => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.95, 0.30, 0.35))),
-            ..base
-        },
-        button::Status::Pressed => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.75, 0.15, 0.20))),
-            ..base
-        },
-        button::Status::Disabled => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.40, 0.40, 0.42))),
-            text_color: Color::from_rgb(0.60, 0.60, 0.60),
-            ..base
-        },
+        match status {
+            button::Status::Active => base,
+            button::Status::Hovered => button::Style {
+                background: Some(Background::Color(Color { a: 0.1, ..shades.text })),
+                text_color: shades.text,
+                ..base
+            },
+            button::Status::Pressed => button::Style {
+                background: Some(Background::Color(Color { a: 0.05, ..shades.text })),
+                ..base
+            },
+            button::Status::Disabled => base,
+        }
     }
 }
 
-pub fn secondary_button_style(_theme: &Theme, status: button::Status) -> button::Style {
-    let base = button::Style {
-        background: Some(Background::Color(Color::from_rgb(0.25, 0.25, 0.28))),
-        text_color: Color::from_rgb(0.90, 0.90, 0.90),
-        border: Border {
-            color: Color::from_rgba(0.85, 0.20, 0.25, 0.5),
-            width: 1.0,
-            radius: 4.0.into(),
-        },
+pub fn close_button_style(radius: f32) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base = button::Style {
+            background: Some(Background::Color(Color::TRANSPARENT)),
+            text_color: shades.heading,
+            border: Border {
+                radius: radius.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        match status {
+            button::Status::Active => base,
+            button::Status::Hovered => button::Style {
+                background: Some(Background::Color(shades.primary)),
+                text_color: shades.on_accent,
+                ..base
+            },
+            button::Status::Pressed => button::Style {
+                background: Some(Background::Color(subdue(&shades, shades.primary))),
+                text_color: shades.on_accent,
+                ..base
+            },
+            button::Status::Disabled => base,
+        }
+    }
+}
+
+pub fn modal_backdrop_style(_theme: &Theme) -> container::Style {
+    container::Style {
+        background: Some(Background::Color(Color::from_rgba(0.0, 0.0, 0.0, 0.6))),
         ..Default::default()
-    };
+    }
+}
 
-    match status {
-        button::Status::Active => base,
-        button::Status::Hovered => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.30, 0.30, 0.33))),
+pub fn modal_container_style(radius: f32) -> impl Fn(&Theme) -> container::Style {
+    move |theme| {
+        let shades = shades(theme);
+        container::Style {
+            background: Some(Background::Color(shades.field)),
+            text_color: Some(shades.text),
             border: Border {
-                color: Color::from_rgba(0.85, 0.20, 0.25, 0.8),
+                color: shades.border,
                 width: 1.0,
-                radius: 4.0.into(),
+                radius: (radius * 2.0).into(),
             },
-            ..base
-        },
-        button::Status::Pressed => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.20, 0.20, 0.22))),
-            ..base
-        },
-        button::Status::Disabled => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.18, 0.18, 0.20))),
-            text_color: Color::from_rgb(0.50, 0.50, 0.50),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn section_style(radius: f32) -> impl Fn(&Theme) -> container::Style {
+    move |theme| {
+        let shades = shades(theme);
+        container::Style {
+            background: Some(Background::Color(shades.surface)),
             border: Border {
-                color: Color::from_rgba(0.50, 0.20, 0.22, 0.3),
+                color: mix(shades.surface, shades.border, 0.6),
                 width: 1.0,
-                radius: 4.0.into(),
+                radius: radius.into(),
             },
-            ..base
-        },
+            ..Default::default()
+        }
     }
 }
 
-pub fn danger_button_style(theme: &Theme, status: button::Status) -> button::Style {
-    let palette = theme.palette();
+pub fn primary_button_style(radius: f32) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |theme, status| accent_button_style(theme, status, shades(theme).primary, radius)
+}
+
+pub fn danger_button_style(radius: f32) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |theme, status| accent_button_style(theme, status, shades(theme).danger, radius)
+}
+
+fn accent_button_style(theme: &Theme, status: button::Status, accent: Color, radius: f32) -> button::Style {
+    let shades = shades(theme);
     let base = button::Style {
-        background: Some(Background::Color(palette.danger)),
-        text_color: Color::WHITE,
+        background: Some(Background::Color(accent)),
+        text_color: shades.on_accent,
         border: Border {
-            radius: 4.0.into(),
+            radius: radius.into(),
             ..Default::default()
         },
         ..Default::default()
@@ -211,248 +496,402 @@ pub fn danger_button_style(theme: &Theme, status: button::Status) -> button::Sty
     match status {
         button::Status::Active => base,
         button::Status::Hovered => button::Style {
-            background: Some(Background::Color(Color::from_rgb(1.0, 0.35, 0.35))),
+            background: Some(Background::Color(emphasize(&shades, accent))),
             ..base
         },
         button::Status::Pressed => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.80, 0.20, 0.20))),
+            background: Some(Background::Color(subdue(&shades, accent))),
             ..base
         },
         button::Status::Disabled => button::Style {
-            background: Some(Background::Color(Color::from_rgb(0.40, 0.40, 0.42))),
-            text_color: Color::from_rgb(0.60, 0.60, 0.60),
+            background: Some(Background::Color(shades.border_hover)),
+            text_color: shades.muted,
             ..base
         },
     }
 }
 
-pub fn text_input_style(_theme: &Theme, status: text_input::Status) -> text_input::Style {
-    let base = text_input::Style {
-        background: Background::Color(Color::from_rgb(0.15, 0.15, 0.17)),
-        border: Border {
-            color: Color::from_rgb(0.25, 0.25, 0.28),
-            width: 1.0,
-            radius: 4.0.into(),
-        },
-        icon: Color::from_rgb(0.60, 0.60, 0.60),
-        placeholder: Color::from_rgb(0.50, 0.50, 0.50),
-        value: Color::from_rgb(0.90, 0.90, 0.90),
-        selection: Color::from_rgba(0.85, 0.20, 0.25, 0.3),
-    };
+pub fn secondary_button_style(radius: f32) -> impl Fn(&Theme, button::Status) -> button::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base = button::Style {
+            background: Some(Background::Color(shades.raised)),
+            text_color: shades.text,
+            border: Border {
+                color: Color { a: 0.5, ..shades.primary },
+                width: 1.0,
+                radius: radius.into(),
+            },
+            ..Default::default()
+        };
 
-    match status {
-        text_input::Status::Active => base,
-        text_input::Status::Hovered => text_input::Style {
+        match status {
+            button::Status::Active => base,
+            button::Status::Hovered => button::Style {
+                background: Some(Background::Color(emphasize(&shades, shades.raised))),
+                border: Border {
+                    color: Color { a: 0.8, ..shades.primary },
+                    ..base.border
+                },
+                ..base
+            },
+            button::Status::Pressed => button::Style {
+                background: Some(Background::Color(subdue(&shades, shades.raised))),
+                ..base
+            },
+            button::Status::Disabled => button::Style {
+                background: Some(Background::Color(mix(shades.field, shades.raised, 0.3))),
+                text_color: shades.placeholder,
+                border: Border {
+                    color: Color { a: 0.3, ..shades.primary },
+                    ..base.border
+                },
+                ..base
+            },
+        }
+    }
+}
+
+pub fn text_input_style(radius: f32) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base = text_input::Style {
+            background: Background::Color(shades.field),
             border: Border {
-                color: Color::from_rgb(0.35, 0.35, 0.38),
-                ..base.border
+                color: shades.border,
+                width: 1.0,
+                radius: radius.into(),
             },
-            ..base
-        },
-        text_input::Status::Focused { .. } => text_input::Style {
+            icon: shades.muted,
+            placeholder: shades.placeholder,
+            value: shades.text,
+            selection: Color { a: 0.3, ..shades.primary },
+        };
+
+        match status {
+            text_input::Status::Active => base,
+            text_input::Status::Hovered => text_input::Style {
+                border: Border {
+                    color: shades.border_hover,
+                    ..base.border
+                },
+                ..base
+            },
+            text_input::Status::Focused { .. } => text_input::Style {
+                border: Border {
+                    color: shades.primary,
+                    ..base.border
+                },
+                ..base
+            },
+            text_input::Status::Disabled => text_input::Style {
+                background: Background::Color(shades.surface),
+                value: shades.placeholder,
+                ..base
+            },
+        }
+    }
+}
+
+/// [`text_input_style`] with a danger border, for values that fail validation.
+pub fn invalid_text_input_style(radius: f32) -> impl Fn(&Theme, text_input::Status) -> text_input::Style {
+    move |theme, status| {
+        let style = text_input_style(radius)(theme, status);
+        text_input::Style {
             border: Border {
-                color: Color::from_rgb(0.85, 0.20, 0.25),
-                ..base.border
+                color: theme.palette().danger,
+                ..style.border
             },
-            ..base
-        },
-        text_input::Status::Disabled => text_input::Style {
-            background: Background::Color(Color::from_rgb(0.12, 0.12, 0.14)),
-            value: Color::from_rgb(0.50, 0.50, 0.50),
-            ..base
-        },
+            ..style
+        }
     }
 }
 
-pub fn pick_list_style(_theme: &Theme, status: pick_list::Status) -> pick_list::Style {
-    let base = pick_list::Style {
-        background: Background::Color(Color::from_rgb(0.15, 0.15, 0.17)),
-        text_color: Color::from_rgb(0.90, 0.90, 0.90),
-        placeholder_color: Color::from_rgb(0.50, 0.50, 0.50),
-        handle_color: Color::from_rgb(0.70, 0.70, 0.70),
-        border: Border {
-            color: Color::from_rgb(0.25, 0.25, 0.28),
-            width: 1.0,
-            radius: 4.0.into(),
-        },
-    };
+/// Same look as [`text_input_style`] for multi-line editors.
+pub fn text_editor_style(radius: f32) -> impl Fn(&Theme, text_editor::Status) -> text_editor::Style {
+    move |theme, status| {
+        let input_status = match status {
+            text_editor::Status::Active => text_input::Status::Active,
+            text_editor::Status::Hovered => text_input::Status::Hovered,
+            text_editor::Status::Focused { is_hovered } => text_input::Status::Focused { is_hovered },
+            text_editor::Status::Disabled => text_input::Status::Disabled,
+        };
+        let input = text_input_style(radius)(theme, input_status);
 
-    match status {
-        pick_list::Status::Active => base,
-        pick_list::Status::Hovered => pick_list::Style {
+        text_editor::Style {
+            background: input.background,
+            border: input.border,
+            placeholder: input.placeholder,
+            value: input.value,
+            selection: input.selection,
+        }
+    }
+}
+
+pub fn pick_list_style(radius: f32) -> impl Fn(&Theme, pick_list::Status) -> pick_list::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base = pick_list::Style {
+            background: Background::Color(shades.field),
+            text_color: shades.text,
+            placeholder_color: shades.placeholder,
+            handle_color: shades.heading,
             border: Border {
-                color: Color::from_rgb(0.35, 0.35, 0.38),
-                ..base.border
+                color: shades.border,
+                width: 1.0,
+                radius: radius.into(),
             },
-            ..base
-        },
-        pick_list::Status::Opened { .. } => pick_list::Style {
-            border: Border {
-                color: Color::from_rgb(0.85, 0.20, 0.25),
-                ..base.border
+        };
+
+        match status {
+            pick_list::Status::Active => base,
+            pick_list::Status::Hovered => pick_list::Style {
+                border: Border {
+                    color: shades.border_hover,
+                    ..base.border
+                },
+                ..base
             },
-            ..base
-        },
+            pick_list::Status::Opened { .. } => pick_list::Style {
+                border: Border {
+                    color: shades.primary,
+                    ..base.border
+                },
+                ..base
+            },
+        }
     }
 }
 
-pub fn progress_bar_style(_theme: &Theme) -> progress_bar::Style {
-    progress_bar::Style {
-        background: Background::Color(Color::from_rgb(0.20, 0.20, 0.22)),
-        bar: Background::Color(Color::from_rgb(0.85, 0.20, 0.25)),
-        border: Border {
-            radius: 4.0.into(),
-            ..Default::default()
-        },
+pub fn progress_bar_style(radius: f32) -> impl Fn(&Theme) -> progress_bar::Style {
+    move |theme| {
+        let shades = shades(theme);
+        progress_bar::Style {
+            background: Background::Color(mix(shades.field, shades.border, 0.4)),
+            bar: Background::Color(shades.primary),
+            border: Border {
+                radius: radius.into(),
+                ..Default::default()
+            },
+        }
     }
 }
 
-pub fn scrollable_style(_theme: &Theme, status: scrollable::Status) -> scrollable::Style {
-    let base_scroller = scrollable::Scroller {
-        background: Background::Color(Color::from_rgb(0.35, 0.35, 0.38)),
-        border: Border {
-            radius: 4.0.into(),
-            ..Default::default()
-        },
-    };
-
-    let rail = scrollable::Rail {
-        background: Some(Background::Color(Color::from_rgb(0.15, 0.15, 0.17))),
-        border: Border::default(),
-        scroller: base_scroller,
-    };
+pub fn scrollable_style(radius: f32) -> impl Fn(&Theme, scrollable::Status) -> scrollable::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let base_scroller = scrollable::Scroller {
+            background: Background::Color(shades.border_hover),
+            border: Border {
+                radius: radius.into(),
+                ..Default::default()
+            },
+        };
 
-    let base_style = scrollable::Style {
-        container: container::Style::default(),
-        vertical_rail: rail,
-        horizontal_rail: rail,
-        gap: None,
-        auto_scroll: scrollable::AutoScroll {
-            background: Background::Color(Color::from_rgba(0.15, 0.15, 0.17, 0.9)),
+        let rail = scrollable::Rail {
+            background: Some(Background::Color(shades.field)),
             border: Border::default(),
-            shadow: iced::Shadow::default(),
-            icon: Color::from_rgb(0.70, 0.70, 0.70),
-        },
-    };
+            scroller: base_scroller,
+        };
 
-    match status {
-        scrollable::Status::Active { .. } => base_style,
-        scrollable::Status::Hovered {
-            is_horizontal_scrollbar_hovered,
-            is_vertical_scrollbar_hovered,
-            ..
-        } => {
-            let hovered_scroller = scrollable::Scroller {
-                background: Background::Color(Color::from_rgb(0.50, 0.50, 0.52)),
-                ..base_scroller
-            };
-            scrollable::Style {
-                vertical_rail: scrollable::Rail {
-                    scroller: if is_vertical_scrollbar_hovered {
-                        hovered_scroller
-                    } else {
-                        base_scroller
+        let base_style = scrollable::Style {
+            container: container::Style::default(),
+            vertical_rail: rail,
+            horizontal_rail: rail,
+            gap: None,
+            auto_scroll: scrollable::AutoScroll {
+                background: Background::Color(Color { a: 0.9, ..shades.field }),
+                border: Border::default(),
+                shadow: iced::Shadow::default(),
+                icon: shades.heading,
+            },
+        };
+
+        match status {
+            scrollable::Status::Active { .. } => base_style,
+            scrollable::Status::Hovered {
+                is_horizontal_scrollbar_hovered,
+                is_vertical_scrollbar_hovered,
+                ..
+            } => {
+                let hovered_scroller = scrollable::Scroller {
+                    background: Background::Color(shades.placeholder),
+                    ..base_scroller
+                };
+                scrollable::Style {
+                    vertical_rail: scrollable::Rail {
+                        scroller: if is_vertical_scrollbar_hovered {
+                            hovered_scroller
+                        } else {
+                            base_scroller
+                        },
+                        ..rail
                     },
-                    ..rail
-                },
-                horizontal_rail: scrollable::Rail {
-                    scroller: if is_horizontal_scrollbar_hovered {
-                        hovered_scroller
-                    } else {
-                        base_scroller
+                    horizontal_rail: scrollable::Rail {
+                        scroller: if is_horizontal_scrollbar_hovered {
+                            hovered_scroller
+                        } else {
+                            base_scroller
+                        },
+                        ..rail
                     },
-                    ..rail
-                },
-                ..base_style
+                    ..base_style
+                }
             }
-        }
-        scrollable::Status::Dragged {
-            is_horizontal_scrollbar_dragged,
-            is_vertical_scrollbar_dragged,
-            ..
-        } => {
-            let dragged_scroller = scrollable::Scroller {
-                background: Background::Color(Color::from_rgb(0.85, 0.20, 0.25)),
-                ..base_scroller
-            };
-            scrollable::Style {
-                vertical_rail: scrollable::Rail {
-                    scroller: if is_vertical_scrollbar_dragged {
-                        dragged_scroller
-                    } else {
-                        base_scroller
+            scrollable::Status::Dragged {
+                is_horizontal_scrollbar_dragged,
+                is_vertical_scrollbar_dragged,
+                ..
+            } => {
+                let dragged_scroller = scrollable::Scroller {
+                    background: Background::Color(shades.primary),
+                    ..base_scroller
+                };
+                scrollable::Style {
+                    vertical_rail: scrollable::Rail {
+                        scroller: if is_vertical_scrollbar_dragged {
+                            dragged_scroller
+                        } else {
+                            base_scroller
+                        },
+                        ..rail
                     },
-                    ..rail
-                },
-                horizontal_rail: scrollable::Rail {
-                    scroller: if is_horizontal_scrollbar_dragged {
-                        dragged_scroller
-                    } else {
-                        base_scroller
+                    horizontal_rail: scrollable::Rail {
+                        scroller: if is_horizontal_scrollbar_dragged {
+                            dragged_scroller
+                        } else {
+                            base_scroller
+                        },
+                        ..rail
                     },
-                    ..rail
-                },
-                ..base_style
+                    ..base_style
+                }
             }
         }
     }
 }
 
-pub fn toggler_style(_theme: &Theme, status: toggler::Status) -> toggler::Style {
-    match status {
-        toggler::Status::Active { is_toggled } => toggler::Style {
-            background: Background::Color(if is_toggled {
-                Color::from_rgb(0.85, 0.20, 0.25)
-            } else {
-                Color::from_rgb(0.30, 0.30, 0.32)
-            }),
-            background_border_width: 0.0,
-            background_border_color: Color::TRANSPARENT,
-            foreground: Background::Color(Color::WHITE),
-            foreground_border_width: 0.0,
-            foreground_border_color: Color::TRANSPARENT,
-            border_radius: Some(10.0.into()),
-            padding_ratio: 0.1,
-            text_color: None,
-        },
-        toggler::Status::Hovered { is_toggled } => toggler::Style {
-            background: Background::Color(if is_toggled {
-                Color::from_rgb(0.95, 0.30, 0.35)
-            } else {
-                Color::from_rgb(0.40, 0.40, 0.42)
-            }),
-            background_border_width: 0.0,
-            background_border_color: Color::TRANSPARENT,
-            foreground: Background::Color(Color::WHITE),
-            foreground_border_width: 0.0,
-            foreground_border_color: Color::TRANSPARENT,
-            border_radius: Some(10.0.into()),
-            padding_ratio: 0.1,
-            text_color: None,
-        },
-        toggler::Status::Disabled { is_toggled } => toggler::Style {
-            background: Background::Color(if is_toggled {
-                Color::from_rgb(0.50, 0.20, 0.22)
-            } else {
-                Color::from_rgb(0.20, 0.20, 0.22)
-            }),
+pub fn toggler_style(radius: f32) -> impl Fn(&Theme, toggler::Status) -> toggler::Style {
+    move |theme, status| {
+        let shades = shades(theme);
+        let off = mix(shades.raised, shades.border_hover, 0.3);
+        let (background, foreground) = match status {
+            toggler::Status::Active { is_toggled } => {
+                (if is_toggled { shades.primary } else { off }, Color::WHITE)
+            }
+            toggler::Status::Hovered { is_toggled } => (
+                if is_toggled {
+                    emphasize(&shades, shades.primary)
+                } else {
+                    emphasize(&shades, off)
+                },
+                Color::WHITE,
+            ),
+            toggler::Status::Disabled { is_toggled } => (
+                if is_toggled {
+                    mix(shades.primary, shades.background, 0.5)
+                } else {
+                    shades.border
+                },
+                shades.muted,
+            ),
+        };
+
+        toggler::Style {
+            background: Background::Color(background),
             background_border_width: 0.0,
             background_border_color: Color::TRANSPARENT,
-            foreground: Background::Color(Color::from_rgb(0.60, 0.60, 0.60)),
+            foreground: Background::Color(foreground),
             foreground_border_width: 0.0,
             foreground_border_color: Color::TRANSPARENT,
-            border_radius: Some(10.0.into()),
+            border_radius: Some((radius * 2.5).into()),
             padding_ratio: 0.1,
             text_color: None,
-        },
+        }
     }
 }
 
-pub fn horizontal_rule_style(_theme: &Theme) -> rule::Style {
+pub fn horizontal_rule_style(theme: &Theme) -> rule::Style {
     rule::Style {
-        color: Color::from_rgb(0.25, 0.25, 0.28),
+        color: shades(theme).border,
         radius: 0.0.into(),
         fill_mode: rule::FillMode::Full,
         snap: false,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_reads_rgb_and_rgba() {
+        assert_eq!(parse_hex("#ff0080"), Some(Color::from_rgba8(255, 0, 128, 1.0)));
+        assert_eq!(parse_hex("#00000080"), Some(Color::from_rgba8(0, 0, 0, 128.0 / 255.0)));
+        assert_eq!(parse_hex("ff0080"), None);
+        assert_eq!(parse_hex("#fff"), None);
+        assert_eq!(parse_hex("#gg0000"), None);
+    }
+
+    #[test]
+    fn theme_files_load_and_fall_back_to_dark() {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-themes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Ocean.toml"), "primary = \"#0080ff\"\nradius = 8.0\n").unwrap();
+        fs::write(dir.join("mint.json"), r##"{"name": "Mint", "background": "#e0fff0"}"##).unwrap();
+        fs::write(dir.join("broken.toml"), "text = \"blue\"\n").unwrap();
+        fs::write(dir.join("notes.txt"), "not a theme").unwrap();
+
+        let user = UserThemes::load(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        let names: Vec<&str> = user.themes.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Ocean", "Mint"]);
+        assert_eq!(user.errors.len(), 1);
+        assert!(user.errors[0].starts_with("Invalid colour \"blue\""));
+
+        let dark = ThemeSpec::dark();
+        let ocean = &user.themes[0];
+        assert_eq!(ocean.palette.primary, Color::from_rgb8(0, 128, 255));
+        assert_eq!(ocean.palette.background, dark.palette.background);
+        assert_eq!(ocean.radius, 8.0);
+        assert_eq!(user.themes[1].radius, dark.radius);
+    }
+
+    #[test]
+    fn duplicate_theme_names_are_reported() {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-themes-duplicate-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.toml"), "name = \"Ocean\"\nprimary = \"#0080ff\"\n").unwrap();
+        fs::write(dir.join("b.json"), r##"{"name": "Ocean", "primary": "#ff0000"}"##).unwrap();
+        fs::write(dir.join("c.toml"), "name = \"Dark\"\n").unwrap();
+
+        let user = UserThemes::load(&dir);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(user.themes.len(), 1);
+        assert_eq!(user.themes[0].palette.primary, Color::from_rgb8(0, 128, 255));
+        assert_eq!(user.errors.len(), 2);
+        assert!(user.errors[0].starts_with("Theme \"Ocean\""), "{}", user.errors[0]);
+        assert!(user.errors[0].ends_with(&format!("is already defined in {}", dir.join("a.toml").display())));
+        assert!(user.errors[1].ends_with("has the name of a built-in theme"), "{}", user.errors[1]);
+    }
+
+    #[test]
+    fn resolve_follows_system_and_falls_back_for_missing_themes() {
+        let mut custom = ThemeSpec::dark();
+        custom.name = "Ocean".to_string();
+        custom.radius = 8.0;
+        let user = UserThemes {
+            themes: vec![custom],
+            errors: Vec::new(),
+        };
+
+        let resolve = |choice: ThemeChoice, mode| user.resolve(&choice, mode).name;
+        assert_eq!(resolve(ThemeChoice::System, iced::theme::Mode::Light), ThemeSpec::light().name);
+        assert_eq!(resolve(ThemeChoice::System, iced::theme::Mode::None), ThemeSpec::dark().name);
+        assert_eq!(resolve(ThemeChoice::Custom("Ocean".into()), iced::theme::Mode::Dark), "Ocean");
+        assert_eq!(resolve(ThemeChoice::Custom("Gone".into()), iced::theme::Mode::Dark), ThemeSpec::dark().name);
+        assert_eq!(user.choices().len(), ThemeChoice::BUILT_IN.len() + 1);
+    }
+}
@@ -0,0 +1,210 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::{hide_window, ytdlp};
+use crate::config;
+
+/// Options understood by a particular yt-dlp build, parsed from `--help`,
+/// and the extractors it ships, from `--list-extractors`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub path: PathBuf,
+    pub version: String,
+    options: BTreeSet<String>,
+    #[serde(default)]
+    extractors: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn supports(&self, option: &str) -> bool {
+        self.options.contains(option)
+    }
+
+    /// Extractors whose name matches the site of `host`, e.g. `youtube` and
+    /// `youtube:tab` for `www.youtube.com`. yt-dlp only exposes extractor
+    /// names, not their URL patterns, so this is a best guess; an empty
+    /// result means the generic extractor will most likely be used.
+    pub fn extractors_for(&self, host: &str) -> Vec<&str> {
+        let Some(site) = site_name(host) else {
+            return Vec::new();
+        };
+        self.extractors
+            .iter()
+            .filter(|name| {
+                let base = name.split(':').next().unwrap_or_default();
+                base.eq_ignore_ascii_case(site)
+            })
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl Capabilities {
+    /// Capabilities of a yt-dlp that knows exactly `options`, for tests.
+    pub fn with_options(options: &[&str]) -> Self {
+        Self {
+            options: options.iter().map(|option| option.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The part of a host name that names the site: `youtube` for
+/// `www.youtube.com`, `bbc` for `www.bbc.co.uk`.
+fn site_name(host: &str) -> Option<&str> {
+    let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+    match labels.as_slice() {
+        [.., site, "co" | "com" | "org" | "net" | "ac" | "gov", _tld] => Some(site),
+        [.., site, _tld] => Some(site),
+        _ => None,
+    }
+}
+
+fn cache_path() -> Option<PathBuf> {
+    config::data_dir().map(|p| p.join("capabilities.json"))
+}
+
+/// Returns the capabilities of the yt-dlp at `path`, reusing the on-disk cache
+/// when it was built from the same binary and version.
+pub fn probe(path: &Path) -> Option<Capabilities> {
+    let version = ytdlp::version(path)?;
+
+    if let Some(cached) = load_cache()
+        && cached.path == path
+        && cached.version == version
+        && !cached.extractors.is_empty()
+    {
+        return Some(cached);
+    }
+
+    let mut cmd = Command::new(path);
+    cmd.arg("--help")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut cmd);
+
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let capabilities = Capabilities {
+        path: path.to_path_buf(),
+        version,
+        options: parse_help(&String::from_utf8_lossy(&output.stdout)),
+        extractors: list_extractors(path),
+    };
+
+    save_cache(&capabilities);
+    Some(capabilities)
+}
+
+/// Collects every option name from `--help` output. Options appear at the start
+/// of indented lines, e.g. `    -x, --extract-audio   Convert video files...`.
+fn parse_help(help: &str) -> BTreeSet<String> {
+    help.lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            line.starts_with('-').then_some(line)
+        })
+        .flat_map(|line| {
+            line.split_whitespace()
+                .map(|word| word.trim_end_matches(','))
+                .take_while(|word| word.starts_with('-'))
+                .map(|word| word.split('=').next().unwrap_or(word).to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// One extractor name per line, e.g. `youtube:tab`. Lines with a note in
+/// parentheses, like `(CURRENTLY BROKEN)`, keep just the name.
+fn list_extractors(path: &Path) -> Vec<String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("--list-extractors")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut cmd);
+
+    let Ok(output) = cmd.output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+fn load_cache() -> Option<Capabilities> {
+    let content = fs::read_to_string(cache_path()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_cache(capabilities: &Capabilities) {
+    let Some(path) = cache_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(content) = serde_json::to_string(capabilities) {
+        let _ = fs::write(path, content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_help_collects_short_and_long_options() {
+        let help = "\
+Usage: yt-dlp [OPTIONS] URL [URL...]
+
+General Options:
+    -h, --help                      Print this help text and exit
+    --js-runtimes RUNTIME           Additional JavaScript runtime to enable
+Post-Processing Options:
+    -x, --extract-audio             Convert video files to audio-only files
+    --merge-output-format FORMAT    Containers that may be used when merging
+    --print-to-file [WHEN:]TEMPLATE FILE
+                                    Append given template to the file. The
+                                    values of WHEN and TEMPLATE are the same
+    --no-foo=BAR                    Made up, to check the value is dropped
+";
+        let options = parse_help(help);
+        for option in [
+            "-h",
+            "--help",
+            "--js-runtimes",
+            "-x",
+            "--extract-audio",
+            "--merge-output-format",
+            "--print-to-file",
+            "--no-foo",
+        ] {
+            assert!(options.contains(option), "missing {option}");
+        }
+        assert!(!options.contains("RUNTIME"));
+        assert!(!options.contains("Usage:"));
+        assert_eq!(options.len(), 8);
+    }
+
+    #[test]
+    fn extractors_match_the_site_of_a_host() {
+        let capabilities = Capabilities {
+            extractors: ["youtube", "youtube:tab", "youtubewebarchive", "BBC", "bbc.co.uk", "generic"]
+                .map(String::from)
+                .to_vec(),
+            ..Default::default()
+        };
+        assert_eq!(capabilities.extractors_for("www.youtube.com"), ["youtube", "youtube:tab"]);
+        assert_eq!(capabilities.extractors_for("www.bbc.co.uk"), ["BBC"]);
+        assert!(capabilities.extractors_for("localhost").is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::hide_window;
+use crate::paths;
+
+#[derive(Debug, Clone, Default)]
+pub struct FfmpegInfo {
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_version: Option<String>,
+}
+
+impl FfmpegInfo {
+    pub fn is_available(&self) -> bool {
+        self.ffmpeg_version.is_some()
+    }
+}
+
+impl std::fmt::Display for FfmpegInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.ffmpeg_version, &self.ffprobe_version) {
+            (Some(ffmpeg), Some(ffprobe)) => write!(f, "ffmpeg {} / ffprobe {}", ffmpeg, ffprobe),
+            (Some(ffmpeg), None) => write!(f, "ffmpeg {} (ffprobe not found)", ffmpeg),
+            (None, _) => write!(f, "ffmpeg not found"),
+        }
+    }
+}
+
+/// Looks for ffmpeg and ffprobe the same way yt-dlp does with
+/// `--ffmpeg-location`: `location` may be the ffmpeg binary itself or the
+/// directory containing it. An empty location means searching PATH.
+pub fn detect(location: &str) -> FfmpegInfo {
+    let (ffmpeg, ffprobe) = binaries(location.trim());
+
+    FfmpegInfo {
+        ffmpeg_version: version(&ffmpeg),
+        ffprobe_version: version(&ffprobe),
+    }
+}
+
+fn binaries(location: &str) -> (PathBuf, PathBuf) {
+    let ffmpeg = format!("ffmpeg{}", std::env::consts::EXE_SUFFIX);
+    let ffprobe = format!("ffprobe{}", std::env::consts::EXE_SUFFIX);
+
+    if location.is_empty() {
+        return (PathBuf::from(ffmpeg), PathBuf::from(ffprobe));
+    }
+
+    let path = paths::expand(location);
+    if path.is_dir() {
+        (path.join(ffmpeg), path.join(ffprobe))
+    } else {
+        let ffprobe = path.with_file_name(ffprobe);
+        (path, ffprobe)
+    }
+}
+
+fn version(path: &Path) -> Option<String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("-version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut cmd);
+
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Extracts the version from the first line of `-version` output, e.g.
+/// `ffmpeg version 6.1.1-3ubuntu5 Copyright (c) ...`.
+fn parse_version(output: &str) -> Option<String> {
+    let first_line = output.lines().next()?;
+    let mut words = first_line.split_whitespace();
+    words.find(|w| *w == "version")?;
+    words.next().map(|v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_comes_from_the_first_line() {
+        let output = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers\n\
+                      built with gcc 13 (Ubuntu 13.2.0-23ubuntu3)\n";
+        assert_eq!(parse_version(output).as_deref(), Some("6.1.1-3ubuntu5"));
+        assert_eq!(
+            parse_version("ffprobe version n7.0 Copyright (c) 2007-2024\n").as_deref(),
+            Some("n7.0")
+        );
+    }
+
+    #[test]
+    fn output_without_a_version_is_rejected() {
+        assert_eq!(parse_version(""), None);
+        assert_eq!(parse_version("ffmpeg version"), None);
+        assert_eq!(parse_version("usage: ffmpeg [options]\nffmpeg version 6.1\n"), None);
+    }
+
+    #[test]
+    fn location_may_be_the_binary_or_its_directory() {
+        let (ffmpeg, ffprobe) = binaries("");
+        assert_eq!(ffmpeg, PathBuf::from(format!("ffmpeg{}", std::env::consts::EXE_SUFFIX)));
+        assert_eq!(ffprobe, PathBuf::from(format!("ffprobe{}", std::env::consts::EXE_SUFFIX)));
+
+        let dir = std::env::temp_dir();
+        let (ffmpeg, ffprobe) = binaries(&dir.display().to_string());
+        assert_eq!(ffmpeg, dir.join(format!("ffmpeg{}", std::env::consts::EXE_SUFFIX)));
+        assert_eq!(ffprobe, dir.join(format!("ffprobe{}", std::env::consts::EXE_SUFFIX)));
+
+        let binary = dir.join("ffmpeg-static");
+        let (ffmpeg, ffprobe) = binaries(&binary.display().to_string());
+        assert_eq!(ffmpeg, binary);
+        assert_eq!(ffprobe, dir.join(format!("ffprobe{}", std::env::consts::EXE_SUFFIX)));
+    }
+}
@@ -0,0 +1,15 @@
+pub mod capabilities;
+pub mod ffmpeg;
+pub mod ytdlp;
+
+use std::process::Command;
+
+/// Keeps helper processes from flashing a console window on Windows.
+#[cfg(windows)]
+pub(crate) fn hide_window(cmd: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+}
+
+#[cfg(not(windows))]
+pub(crate) fn hide_window(_cmd: &mut Command) {}
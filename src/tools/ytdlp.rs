@@ -0,0 +1,664 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use super::hide_window;
+use crate::config;
+use crate::paths;
+use crate::settings::AdvancedSettings;
+
+/// Name of the checksum file published next to every yt-dlp release asset.
+const CHECKSUMS_FILE: &str = "SHA2-256SUMS";
+
+#[cfg(windows)]
+const BINARY_NAME: &str = "yt-dlp.exe";
+#[cfg(not(windows))]
+const BINARY_NAME: &str = "yt-dlp";
+
+#[cfg(windows)]
+const RELEASE_ASSET: &str = "yt-dlp.exe";
+#[cfg(target_os = "macos")]
+const RELEASE_ASSET: &str = "yt-dlp_macos";
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+const RELEASE_ASSET: &str = "yt-dlp_linux_aarch64";
+#[cfg(all(target_os = "linux", not(target_arch = "aarch64")))]
+const RELEASE_ASSET: &str = "yt-dlp_linux";
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+const RELEASE_ASSET: &str = "yt-dlp";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Nightly,
+    Master,
+}
+
+impl UpdateChannel {
+    pub const ALL: [UpdateChannel; 3] = [
+        UpdateChannel::Stable,
+        UpdateChannel::Nightly,
+        UpdateChannel::Master,
+    ];
+
+    /// Channel name as yt-dlp's `--update-to` spells it.
+    fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Nightly => "nightly",
+            UpdateChannel::Master => "master",
+        }
+    }
+}
+
+impl std::fmt::Display for UpdateChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UpdateChannel::Stable => write!(f, "Stable"),
+            UpdateChannel::Nightly => write!(f, "Nightly"),
+            UpdateChannel::Master => write!(f, "Master"),
+        }
+    }
+}
+
+fn bin_dir() -> Option<PathBuf> {
+    config::data_dir().map(|p| p.join("bin"))
+}
+
+/// Location of the yt-dlp copy managed by yt-dlgui.
+pub fn managed_path() -> Option<PathBuf> {
+    bin_dir().map(|p| p.join(BINARY_NAME))
+}
+
+fn previous_path() -> Option<PathBuf> {
+    bin_dir().map(|p| p.join(format!("{}.prev", BINARY_NAME)))
+}
+
+pub fn has_previous() -> bool {
+    previous_path().is_some_and(|p| p.exists())
+}
+
+/// Resolves the yt-dlp executable a download should use: the managed copy when
+/// enabled and installed, otherwise the configured path (or `yt-dlp` on PATH).
+pub fn resolve_path(settings: &AdvancedSettings) -> PathBuf {
+    if settings.use_managed_ytdlp
+        && let Some(path) = managed_path().filter(|p| p.exists())
+    {
+        return path;
+    }
+
+    if settings.ytdlp_path.trim().is_empty() {
+        PathBuf::from("yt-dlp")
+    } else {
+        paths::expand(&settings.ytdlp_path)
+    }
+}
+
+/// Runs `yt-dlp --version` and returns the reported version string.
+pub fn version(path: &Path) -> Option<String> {
+    let mut cmd = Command::new(path);
+    cmd.arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut cmd);
+
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// Installs a yt-dlp binary from a local file, verified against the
+/// `SHA2-256SUMS` next to it. Without one the file is only installed when
+/// `allow_unverified` is set.
+pub fn install_from_file(source: &Path, allow_unverified: bool) -> Result<String, String> {
+    install_file_in(&bin_dir().ok_or(NO_DATA_DIR)?, source, allow_unverified)
+}
+
+/// Downloads the platform release asset and its `SHA2-256SUMS` from `base_url`
+/// (a GitHub release download URL or any mirror with the same layout), verifies
+/// the checksum and installs it.
+pub fn install_from_mirror(base_url: &str) -> Result<String, String> {
+    let dir = bin_dir().ok_or(NO_DATA_DIR)?;
+    let staged = stage(&dir, &download_release(base_url)?)?;
+    promote(&dir, &staged)
+}
+
+/// Updates the managed copy with yt-dlp's own updater (`--update-to
+/// target`, see [`update_target`]). The current binary is kept for
+/// [`rollback`] only when the version actually changed, so an update that
+/// finds nothing new keeps the older copy to return to.
+pub fn update(target: &str) -> Result<String, String> {
+    update_in(&bin_dir().ok_or(NO_DATA_DIR)?, target)
+}
+
+/// Release download URL installs fetch from: the configured mirror, or the
+/// GitHub releases of `channel` when none is set.
+pub fn release_source(mirror: &str, channel: UpdateChannel) -> String {
+    let mirror = mirror.trim().trim_end_matches('/');
+    if !mirror.is_empty() {
+        return mirror.to_string();
+    }
+    channel_source(channel).to_string()
+}
+
+/// `CHANNEL@TAG` for yt-dlp's `--update-to`: the repository of a GitHub
+/// release mirror URL, or `channel` when none is set. yt-dlp only updates
+/// from GitHub, so other mirrors can install but not update.
+pub fn update_target(mirror: &str, channel: UpdateChannel) -> Result<String, String> {
+    let mirror = mirror.trim().trim_end_matches('/');
+    if mirror.is_empty() {
+        return Ok(format!("{}@latest", channel.as_str()));
+    }
+
+    let parts: Vec<&str> = mirror
+        .strip_prefix("https://github.com/")
+        .map(|path| path.split('/').collect())
+        .unwrap_or_default();
+    match parts.as_slice() {
+        [owner, repo, "releases", "latest", "download"] => Ok(format!("{}/{}@latest", owner, repo)),
+        [owner, repo, "releases", "download", tag] => Ok(format!("{}/{}@{}", owner, repo, tag)),
+        _ => Err(format!(
+            "yt-dlp can only update from GitHub releases, not {}. Use Download Latest to install from this mirror.",
+            mirror
+        )),
+    }
+}
+
+fn channel_source(channel: UpdateChannel) -> &'static str {
+    match channel {
+        UpdateChannel::Stable => "https://github.com/yt-dlp/yt-dlp/releases/latest/download",
+        UpdateChannel::Nightly => {
+            "https://github.com/yt-dlp/yt-dlp-nightly-builds/releases/latest/download"
+        }
+        UpdateChannel::Master => {
+            "https://github.com/yt-dlp/yt-dlp-master-builds/releases/latest/download"
+        }
+    }
+}
+
+/// Swaps the managed binary with the copy kept by the last install or update.
+pub fn rollback() -> Result<String, String> {
+    rollback_in(&bin_dir().ok_or(NO_DATA_DIR)?)
+}
+
+const NO_DATA_DIR: &str = "Could not determine data directory";
+
+fn install_file_in(dir: &Path, source: &Path, allow_unverified: bool) -> Result<String, String> {
+    let data = fs::read(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+
+    let sums_path = source.with_file_name(CHECKSUMS_FILE);
+    if sums_path.exists() {
+        let sums = fs::read_to_string(&sums_path)
+            .map_err(|e| format!("Failed to read {}: {}", sums_path.display(), e))?;
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        verify_checksum(&data, &sums, &name)?;
+    } else if !allow_unverified {
+        return Err(format!(
+            "No {} next to {}. Put the release's checksum file beside it, or allow unverified installs.",
+            CHECKSUMS_FILE,
+            source.display()
+        ));
+    }
+
+    let staged = stage(dir, &data)?;
+    promote(dir, &staged)
+}
+
+fn update_in(dir: &Path, target: &str) -> Result<String, String> {
+    let path = dir.join(BINARY_NAME);
+    if !path.exists() {
+        return Err("yt-dlp is not installed yet".to_string());
+    }
+    let current = version(&path);
+
+    // yt-dlp replaces itself, so copy it first to have something to roll back to
+    let backup = dir.join(format!("{}.old", BINARY_NAME));
+    fs::copy(&path, &backup).map_err(|e| format!("Failed to keep current yt-dlp: {}", e))?;
+
+    let mut cmd = Command::new(&path);
+    cmd.arg("--update-to").arg(target).stdin(Stdio::null());
+    hide_window(&mut cmd);
+    let output = cmd.output();
+
+    let updated = version(&path);
+    let failure = match output {
+        Err(e) => Some(format!("Failed to run yt-dlp: {}", e)),
+        Ok(ref output) if !output.status.success() => Some(update_error(output)),
+        Ok(_) if updated.is_none() => Some("Updated yt-dlp does not run".to_string()),
+        Ok(_) => None,
+    };
+    if let Some(error) = failure {
+        if updated != current {
+            let _ = fs::rename(&backup, &path);
+        }
+        let _ = fs::remove_file(&backup);
+        return Err(error);
+    }
+
+    if updated == current {
+        let _ = fs::remove_file(&backup);
+    } else {
+        let previous = dir.join(format!("{}.prev", BINARY_NAME));
+        fs::rename(&backup, &previous).map_err(|e| format!("Failed to keep previous yt-dlp: {}", e))?;
+    }
+    updated.ok_or_else(|| "Installed yt-dlp does not run".to_string())
+}
+
+/// Last line yt-dlp's updater printed, which carries the reason it failed.
+fn update_error(output: &std::process::Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stderr
+        .lines()
+        .chain(stdout.lines())
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .map(|line| line.trim_start_matches("ERROR: ").to_string())
+        .unwrap_or_else(|| format!("yt-dlp update failed ({})", output.status))
+}
+
+fn rollback_in(dir: &Path) -> Result<String, String> {
+    let path = dir.join(BINARY_NAME);
+    let previous = dir.join(format!("{}.prev", BINARY_NAME));
+    if !previous.exists() {
+        return Err("No previous version to roll back to".to_string());
+    }
+
+    swap_files(&path, &previous)?;
+    match version(&path) {
+        Some(version) => Ok(version),
+        None => {
+            // Keep the copy that works in place
+            swap_files(&path, &previous)?;
+            Err("Previous yt-dlp does not run".to_string())
+        }
+    }
+}
+
+/// Exchanges two files through a `.swap` name, moving everything back when
+/// a step fails so neither copy is lost.
+fn swap_files(current: &Path, previous: &Path) -> Result<(), String> {
+    let swap = current.with_extension("swap");
+    fs::rename(current, &swap).map_err(|e| format!("Failed to move current yt-dlp: {}", e))?;
+    if let Err(e) = fs::rename(previous, current) {
+        let _ = fs::rename(&swap, current);
+        return Err(format!("Failed to restore previous yt-dlp: {}", e));
+    }
+    if let Err(e) = fs::rename(&swap, previous) {
+        let _ = fs::rename(current, previous);
+        let _ = fs::rename(&swap, current);
+        return Err(format!("Failed to keep current yt-dlp: {}", e));
+    }
+    Ok(())
+}
+
+/// Fetches the platform asset from a release download URL and checks it
+/// against the release's `SHA2-256SUMS`.
+fn download_release(base_url: &str) -> Result<Vec<u8>, String> {
+    let base = base_url.trim().trim_end_matches('/');
+    if base.is_empty() {
+        return Err("No mirror URL configured".to_string());
+    }
+
+    let sums = fetch(&format!("{}/{}", base, CHECKSUMS_FILE))?;
+    let sums = String::from_utf8(sums).map_err(|_| "Checksum file is not valid text".to_string())?;
+    let data = fetch(&format!("{}/{}", base, RELEASE_ASSET))?;
+
+    verify_checksum(&data, &sums, RELEASE_ASSET)?;
+    Ok(data)
+}
+
+/// Writes a new binary next to the managed one and checks that it runs.
+fn stage(dir: &Path, data: &[u8]) -> Result<PathBuf, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+    let staged = dir.join(format!("{}.new", BINARY_NAME));
+    fs::write(&staged, data).map_err(|e| format!("Failed to write yt-dlp: {}", e))?;
+    make_executable(&staged)?;
+
+    if version(&staged).is_none() {
+        let _ = fs::remove_file(&staged);
+        return Err("Downloaded file is not a working yt-dlp binary".to_string());
+    }
+    Ok(staged)
+}
+
+/// Moves a staged binary into place, keeping the current one as `.prev`.
+fn promote(dir: &Path, staged: &Path) -> Result<String, String> {
+    let path = dir.join(BINARY_NAME);
+    if path.exists() {
+        let previous = dir.join(format!("{}.prev", BINARY_NAME));
+        fs::rename(&path, &previous).map_err(|e| format!("Failed to keep previous yt-dlp: {}", e))?;
+    }
+    fs::rename(staged, &path).map_err(|e| format!("Failed to install yt-dlp: {}", e))?;
+
+    version(&path).ok_or_else(|| "Installed yt-dlp does not run".to_string())
+}
+
+fn fetch(url: &str) -> Result<Vec<u8>, String> {
+    let response = ureq::get(url)
+        .call()
+        .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+
+    let mut data = Vec::new();
+    response
+        .into_body()
+        .into_reader()
+        .read_to_end(&mut data)
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+
+    Ok(data)
+}
+
+/// Checks `data` against the entry for `name` in a `sha256sum`-style listing.
+pub fn verify_checksum(data: &[u8], sums: &str, name: &str) -> Result<(), String> {
+    let expected = sums
+        .lines()
+        .filter_map(|line| {
+            let (hash, file) = line.split_once(char::is_whitespace)?;
+            Some((hash, file.trim().trim_start_matches('*')))
+        })
+        .find(|(_, file)| *file == name)
+        .map(|(hash, _)| hash.to_ascii_lowercase())
+        .ok_or_else(|| format!("No checksum listed for {}", name))?;
+
+    let actual = sha256_hex(data);
+    if actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            name, expected, actual
+        ))
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .map_err(|e| format!("Failed to mark yt-dlp executable: {}", e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    /// Serves `files` over HTTP on a local port until the test ends and
+    /// returns the base URL.
+    fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { continue };
+                let mut request = String::new();
+                let mut reader = BufReader::new(&stream);
+                if reader.read_line(&mut request).is_err() {
+                    continue;
+                }
+                // Drain the headers
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    line.clear();
+                }
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = match files.get(path.trim_start_matches('/')) {
+                    Some(body) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(body);
+                        response
+                    }
+                    None => b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_vec(),
+                };
+                let _ = stream.write_all(&response);
+            }
+        });
+        base
+    }
+
+    fn release(version: &str) -> (String, HashMap<String, Vec<u8>>) {
+        let binary = format!("#!/bin/sh\necho {}\n", version).into_bytes();
+        let sums = format!("{}  {}\n", sha256_hex(&binary), RELEASE_ASSET);
+        let files = HashMap::from([
+            (RELEASE_ASSET.to_string(), binary),
+            (CHECKSUMS_FILE.to_string(), sums.into_bytes()),
+        ]);
+        (serve(files.clone()), files)
+    }
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn verify_checksum_accepts_matching_entry() {
+        let data = b"yt-dlp";
+        let sums = format!("{} *yt-dlp_linux\n{}  yt-dlp.exe\n", sha256_hex(b"other"), sha256_hex(data));
+        assert_eq!(verify_checksum(data, &sums, "yt-dlp.exe"), Ok(()));
+    }
+
+    #[test]
+    fn verify_checksum_rejects_mismatch_and_missing_entry() {
+        let sums = format!("{}  yt-dlp.exe\n", sha256_hex(b"other"));
+        let mismatch = verify_checksum(b"yt-dlp", &sums, "yt-dlp.exe").unwrap_err();
+        assert!(mismatch.starts_with("Checksum mismatch"), "{}", mismatch);
+        let missing = verify_checksum(b"yt-dlp", &sums, "yt-dlp_macos").unwrap_err();
+        assert_eq!(missing, "No checksum listed for yt-dlp_macos");
+    }
+
+    /// Writes a shell script standing in for yt-dlp that reports `version`.
+    /// Given `--update-to`, it records the target next to itself and runs
+    /// `on_update`.
+    #[cfg(unix)]
+    fn fake_ytdlp(path: &Path, version: &str, on_update: &str) {
+        let script = format!(
+            "#!/bin/sh\nif [ \"$1\" = --update-to ]; then\n  echo \"$2\" > \"$0.target\"\n  {}\nfi\necho {}\n",
+            on_update, version
+        );
+        fs::write(path, script).unwrap();
+        make_executable(path).unwrap();
+    }
+
+    /// `on_update` for [`fake_ytdlp`] that replaces the script with one
+    /// reporting `version`, the way yt-dlp's updater replaces its binary.
+    #[cfg(unix)]
+    fn replace_with(version: &str) -> String {
+        format!(
+            "printf '#!/bin/sh\\necho {}\\n' > \"$0.tmp\"; chmod +x \"$0.tmp\"; mv \"$0.tmp\" \"$0\"; exit 0",
+            version
+        )
+    }
+
+    #[test]
+    fn release_source_follows_channel_unless_mirrored() {
+        assert_eq!(
+            release_source("", UpdateChannel::Nightly),
+            channel_source(UpdateChannel::Nightly)
+        );
+        assert_eq!(
+            release_source(channel_source(UpdateChannel::Stable), UpdateChannel::Master),
+            channel_source(UpdateChannel::Stable)
+        );
+        assert_eq!(
+            release_source("http://mirror.local/yt-dlp/", UpdateChannel::Nightly),
+            "http://mirror.local/yt-dlp"
+        );
+    }
+
+    #[test]
+    fn update_target_uses_channel_or_mirrored_repository() {
+        assert_eq!(update_target(" ", UpdateChannel::Nightly), Ok("nightly@latest".to_string()));
+        assert_eq!(
+            update_target(channel_source(UpdateChannel::Master), UpdateChannel::Stable),
+            Ok("yt-dlp/yt-dlp-master-builds@latest".to_string())
+        );
+        assert_eq!(
+            update_target(
+                "https://github.com/example/yt-dlp/releases/download/2025.01.01/",
+                UpdateChannel::Stable
+            ),
+            Ok("example/yt-dlp@2025.01.01".to_string())
+        );
+        let error = update_target("http://mirror.local/yt-dlp", UpdateChannel::Stable).unwrap_err();
+        assert!(error.starts_with("yt-dlp can only update from GitHub releases"), "{}", error);
+    }
+
+    // Installs shell scripts standing in for yt-dlp, so this needs a Unix shell
+    #[cfg(unix)]
+    #[test]
+    fn install_from_local_mirror_keeps_previous_copy() {
+        let dir = scratch_dir("install");
+        let (old, _) = release("2024.01.01");
+        let (new, _) = release("2025.01.01");
+
+        let staged = stage(&dir, &download_release(&old).unwrap()).unwrap();
+        assert_eq!(promote(&dir, &staged), Ok("2024.01.01".to_string()));
+        let staged = stage(&dir, &download_release(&new).unwrap()).unwrap();
+        assert_eq!(promote(&dir, &staged), Ok("2025.01.01".to_string()));
+        let previous = dir.join(format!("{}.prev", BINARY_NAME));
+        assert_eq!(version(&previous).as_deref(), Some("2024.01.01"));
+        assert!(!dir.join(format!("{}.new", BINARY_NAME)).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn install_rejects_tampered_download() {
+        let (_, mut files) = release("2025.01.01");
+        files.insert(RELEASE_ASSET.to_string(), b"#!/bin/sh\necho evil\n".to_vec());
+        let base = serve(files);
+
+        let error = download_release(&base).unwrap_err();
+        assert!(error.starts_with("Checksum mismatch"), "{}", error);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn update_runs_ytdlp_updater_and_keeps_rollback_target() {
+        let dir = scratch_dir("update");
+        let path = dir.join(BINARY_NAME);
+        let previous = dir.join(format!("{}.prev", BINARY_NAME));
+        fake_ytdlp(&path, "2024.01.01", &replace_with("2025.01.01"));
+
+        assert_eq!(update_in(&dir, "nightly@latest"), Ok("2025.01.01".to_string()));
+        let target = fs::read_to_string(dir.join(format!("{}.target", BINARY_NAME))).unwrap();
+        assert_eq!(target.trim(), "nightly@latest");
+        assert_eq!(version(&previous).as_deref(), Some("2024.01.01"));
+
+        // Nothing new: the older copy stays the rollback target
+        fake_ytdlp(&path, "2025.01.01", "exit 0");
+        assert_eq!(update_in(&dir, "nightly@latest"), Ok("2025.01.01".to_string()));
+        assert_eq!(version(&previous).as_deref(), Some("2024.01.01"));
+        assert!(!dir.join(format!("{}.old", BINARY_NAME)).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn failed_update_reports_error_and_keeps_binary() {
+        let dir = scratch_dir("update-failed");
+        let path = dir.join(BINARY_NAME);
+        fake_ytdlp(
+            &path,
+            "2024.01.01",
+            "echo 'ERROR: Unable to obtain version info' >&2; exit 1",
+        );
+
+        assert_eq!(
+            update_in(&dir, "stable@latest"),
+            Err("Unable to obtain version info".to_string())
+        );
+        assert_eq!(version(&path).as_deref(), Some("2024.01.01"));
+        assert!(!dir.join(format!("{}.prev", BINARY_NAME)).exists());
+        assert!(!dir.join(format!("{}.old", BINARY_NAME)).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rollback_swaps_back_when_previous_copy_is_broken() {
+        let dir = scratch_dir("rollback");
+        let path = dir.join(BINARY_NAME);
+        let previous = dir.join(format!("{}.prev", BINARY_NAME));
+        fake_ytdlp(&path, "2025.01.01", "exit 0");
+        fs::write(&previous, b"broken").unwrap();
+
+        assert_eq!(rollback_in(&dir), Err("Previous yt-dlp does not run".to_string()));
+        assert_eq!(version(&path).as_deref(), Some("2025.01.01"));
+        assert_eq!(fs::read(&previous).unwrap(), b"broken");
+        assert!(!path.with_extension("swap").exists());
+
+        fake_ytdlp(&previous, "2024.01.01", "exit 0");
+        assert_eq!(rollback_in(&dir), Ok("2024.01.01".to_string()));
+        assert_eq!(version(&previous).as_deref(), Some("2025.01.01"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn swap_files_moves_current_back_when_a_rename_fails() {
+        let dir = scratch_dir("swap");
+        let current = dir.join(BINARY_NAME);
+        fs::write(&current, b"current").unwrap();
+
+        let error = swap_files(&current, &dir.join("missing")).unwrap_err();
+        assert!(error.starts_with("Failed to restore previous yt-dlp"), "{}", error);
+        assert_eq!(fs::read(&current).unwrap(), b"current");
+        assert!(!current.with_extension("swap").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn install_from_file_requires_checksums_unless_allowed() {
+        let dir = scratch_dir("file");
+        let source = dir.join("download").join("yt-dlp_custom");
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::write(&source, b"#!/bin/sh\necho 2025.02.02\n").unwrap();
+
+        let error = install_file_in(&dir, &source, false).unwrap_err();
+        assert!(error.starts_with("No SHA2-256SUMS next to"), "{}", error);
+        assert!(!dir.join(BINARY_NAME).exists());
+
+        #[cfg(unix)]
+        assert_eq!(install_file_in(&dir, &source, true), Ok("2025.02.02".to_string()));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,95 @@
+//! Tray icon. On Linux this is a StatusNotifierItem served over D-Bus, shown
+//! by any desktop with a status notifier host; elsewhere the tray is
+//! reported as unavailable and the window behaves as before.
+
+#[cfg(target_os = "linux")]
+mod sni;
+
+use iced::futures::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// What the title bar's close button does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseAction {
+    #[default]
+    Quit,
+    /// Hide the window and keep running in the tray.
+    HideToTray,
+}
+
+impl CloseAction {
+    pub const ALL: [CloseAction; 2] = [CloseAction::Quit, CloseAction::HideToTray];
+}
+
+impl std::fmt::Display for CloseAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseAction::Quit => write!(f, "Quit"),
+            CloseAction::HideToTray => write!(f, "Hide to Tray"),
+        }
+    }
+}
+
+/// Entries of the tray menu, plus a click on the icon itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayAction {
+    Activate,
+    ShowWindow,
+    PauseAll,
+    ResumeAll,
+    Quit,
+}
+
+#[derive(Debug, Clone)]
+pub enum TrayEvent {
+    Ready(TrayHandle),
+    Unavailable(String),
+    Action(TrayAction),
+}
+
+/// Sends tooltip updates to the running tray icon.
+#[derive(Debug, Clone)]
+pub struct TrayHandle {
+    tooltip: mpsc::UnboundedSender<String>,
+}
+
+impl TrayHandle {
+    pub fn set_tooltip(&self, tooltip: String) {
+        let _ = self.tooltip.send(tooltip);
+    }
+}
+
+/// Shows the tray icon for as long as the stream is polled.
+pub fn run() -> impl Stream<Item = TrayEvent> {
+    async_stream::stream! {
+        #[cfg(target_os = "linux")]
+        {
+            let (tooltip_tx, tooltip_rx) = mpsc::unbounded_channel();
+            let (action_tx, mut action_rx) = mpsc::unbounded_channel();
+
+            match sni::register(action_tx).await {
+                Ok(tray) => {
+                    yield TrayEvent::Ready(TrayHandle { tooltip: tooltip_tx });
+                    let mut tooltip_rx = tooltip_rx;
+                    // `tray` holds the D-Bus connection, and with it the icon,
+                    // for as long as this loop runs.
+                    loop {
+                        tokio::select! {
+                            action = action_rx.recv() => match action {
+                                Some(action) => yield TrayEvent::Action(action),
+                                None => break,
+                            },
+                            Some(tooltip) = tooltip_rx.recv() => tray.set_tooltip(tooltip).await,
+                        }
+                    }
+                }
+                Err(e) => yield TrayEvent::Unavailable(e),
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        yield TrayEvent::Unavailable("The tray icon is only supported on Linux".to_string());
+    }
+}
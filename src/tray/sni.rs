@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use tokio::sync::mpsc;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue, Value};
+use zbus::{interface, Connection};
+
+use super::TrayAction;
+
+const ITEM_PATH: &str = "/StatusNotifierItem";
+const MENU_PATH: &str = "/MenuBar";
+
+/// Menu entries as (dbusmenu id, label, action). Id 0 is the root.
+const MENU: [(i32, &str, TrayAction); 4] = [
+    (1, "Show Window", TrayAction::ShowWindow),
+    (2, "Pause All", TrayAction::PauseAll),
+    (3, "Resume All", TrayAction::ResumeAll),
+    (4, "Quit", TrayAction::Quit),
+];
+
+/// A registered tray icon. Dropping it removes the icon.
+pub struct Tray {
+    connection: Connection,
+}
+
+/// Serves the item and its menu on the session bus and announces them to
+/// the status notifier watcher.
+pub async fn register(actions: mpsc::UnboundedSender<TrayAction>) -> Result<Tray, String> {
+    let name = format!("org.kde.StatusNotifierItem-{}-1", std::process::id());
+    let connection = zbus::connection::Builder::session()
+        .and_then(|b| b.name(name.as_str()))
+        .and_then(|b| {
+            b.serve_at(
+                ITEM_PATH,
+                Item {
+                    tooltip: String::new(),
+                    actions: actions.clone(),
+                },
+            )
+        })
+        .and_then(|b| b.serve_at(MENU_PATH, Menu { actions }))
+        .map_err(|e| format!("Failed to connect to the session bus: {}", e))?
+        .build()
+        .await
+        .map_err(|e| format!("Failed to connect to the session bus: {}", e))?;
+
+    connection
+        .call_method(
+            Some("org.kde.StatusNotifierWatcher"),
+            "/StatusNotifierWatcher",
+            Some("org.kde.StatusNotifierWatcher"),
+            "RegisterStatusNotifierItem",
+            &(name.as_str(),),
+        )
+        .await
+        .map_err(|e| format!("No tray host is running: {}", e))?;
+
+    Ok(Tray { connection })
+}
+
+impl Tray {
+    pub async fn set_tooltip(&self, tooltip: String) {
+        let Ok(item) = self
+            .connection
+            .object_server()
+            .interface::<_, Item>(ITEM_PATH)
+            .await
+        else {
+            return;
+        };
+
+        let mut item_ref = item.get_mut().await;
+        if item_ref.tooltip == tooltip {
+            return;
+        }
+        item_ref.tooltip = tooltip;
+        let _ = Item::new_tool_tip(item.signal_emitter()).await;
+    }
+}
+
+struct Item {
+    tooltip: String,
+    actions: mpsc::UnboundedSender<TrayAction>,
+}
+
+/// Icon, title and tooltip: (icon name, icon pixmaps, title, description).
+type ToolTip = (String, Vec<(i32, i32, Vec<u8>)>, String, String);
+
+#[interface(name = "org.kde.StatusNotifierItem")]
+impl Item {
+    #[zbus(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[zbus(property)]
+    fn id(&self) -> &str {
+        "yt-dlgui"
+    }
+
+    #[zbus(property)]
+    fn title(&self) -> &str {
+        "yt-dlgui"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[zbus(property)]
+    fn icon_name(&self) -> &str {
+        "folder-download"
+    }
+
+    #[zbus(property)]
+    fn tool_tip(&self) -> ToolTip {
+        (
+            String::new(),
+            Vec::new(),
+            "yt-dlgui".to_string(),
+            self.tooltip.clone(),
+        )
+    }
+
+    #[zbus(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+
+    #[zbus(property)]
+    fn menu(&self) -> OwnedObjectPath {
+        OwnedObjectPath::try_from(MENU_PATH).expect("valid object path")
+    }
+
+    fn activate(&self, _x: i32, _y: i32) {
+        let _ = self.actions.send(TrayAction::Activate);
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        let _ = self.actions.send(TrayAction::Activate);
+    }
+
+    fn context_menu(&self, _x: i32, _y: i32) {}
+
+    fn scroll(&self, _delta: i32, _orientation: &str) {}
+
+    #[zbus(signal)]
+    async fn new_tool_tip(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+/// The static menu, exported through the `com.canonical.dbusmenu` protocol
+/// that status notifier hosts use to draw it.
+struct Menu {
+    actions: mpsc::UnboundedSender<TrayAction>,
+}
+
+type MenuProperties = HashMap<String, OwnedValue>;
+
+fn properties(id: i32) -> MenuProperties {
+    let mut properties = HashMap::new();
+    if id == 0 {
+        properties.insert("children-display".to_string(), owned("submenu"));
+    } else if let Some((_, label, _)) = MENU.iter().find(|(item, ..)| *item == id) {
+        properties.insert("label".to_string(), owned(*label));
+    }
+    properties
+}
+
+fn owned<'a>(value: impl Into<Value<'a>>) -> OwnedValue {
+    value.into().try_to_owned().expect("plain values have no file descriptors")
+}
+
+#[interface(name = "com.canonical.dbusmenu")]
+impl Menu {
+    #[zbus(property)]
+    fn version(&self) -> u32 {
+        3
+    }
+
+    #[zbus(property)]
+    fn text_direction(&self) -> &str {
+        "ltr"
+    }
+
+    #[zbus(property)]
+    fn status(&self) -> &str {
+        "normal"
+    }
+
+    #[zbus(property)]
+    fn icon_theme_path(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn get_layout(
+        &self,
+        _parent_id: i32,
+        _recursion_depth: i32,
+        _property_names: Vec<String>,
+    ) -> (u32, (i32, MenuProperties, Vec<OwnedValue>)) {
+        let children = MENU
+            .iter()
+            .map(|(id, ..)| owned((*id, properties(*id), Vec::<OwnedValue>::new())))
+            .collect();
+        (1, (0, properties(0), children))
+    }
+
+    fn get_group_properties(
+        &self,
+        ids: Vec<i32>,
+        _property_names: Vec<String>,
+    ) -> Vec<(i32, MenuProperties)> {
+        ids.into_iter().map(|id| (id, properties(id))).collect()
+    }
+
+    fn get_property(&self, id: i32, name: String) -> OwnedValue {
+        properties(id).remove(&name).unwrap_or_else(|| owned(""))
+    }
+
+    fn event(&self, id: i32, event_id: &str, _data: Value<'_>, _timestamp: u32) {
+        if event_id == "clicked"
+            && let Some((.., action)) = MENU.iter().find(|(item, ..)| *item == id)
+        {
+            let _ = self.actions.send(*action);
+        }
+    }
+
+    fn event_group(&self, events: Vec<(i32, String, OwnedValue, u32)>) -> Vec<i32> {
+        for (id, event_id, data, timestamp) in events {
+            self.event(id, &event_id, data.into(), timestamp);
+        }
+        Vec::new()
+    }
+
+    fn about_to_show(&self, _id: i32) -> bool {
+        false
+    }
+
+    fn about_to_show_group(&self, _ids: Vec<i32>) -> (Vec<i32>, Vec<i32>) {
+        (Vec::new(), Vec::new())
+    }
+}
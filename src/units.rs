@@ -0,0 +1,165 @@
+use std::sync::OnceLock;
+
+const BYTE_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+/// Number formatting conventions taken from the user's locale.
+#[derive(Debug, Clone, Copy)]
+struct NumberLocale {
+    decimal_separator: char,
+}
+
+fn locale() -> NumberLocale {
+    static LOCALE: OnceLock<NumberLocale> = OnceLock::new();
+    *LOCALE.get_or_init(|| {
+        let name = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|v| !v.is_empty())
+            .unwrap_or_default();
+        NumberLocale {
+            decimal_separator: decimal_separator_for(&name),
+        }
+    })
+}
+
+/// Languages whose locales write `1,5` rather than `1.5`.
+fn decimal_separator_for(locale: &str) -> char {
+    const COMMA_LANGUAGES: [&str; 24] = [
+        "bg", "cs", "da", "de", "el", "es", "et", "fi", "fr", "hr", "hu", "id", "it", "lt", "lv",
+        "nb", "nl", "pl", "pt", "ro", "ru", "sk", "sv", "tr",
+    ];
+
+    let language = locale
+        .split(['_', '.', '@', '-'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if COMMA_LANGUAGES.contains(&language.as_str()) {
+        ','
+    } else {
+        '.'
+    }
+}
+
+fn decimal(value: f64, precision: usize) -> String {
+    let formatted = format!("{:.*}", precision, value);
+    match locale().decimal_separator {
+        '.' => formatted,
+        separator => formatted.replace('.', &separator.to_string()),
+    }
+}
+
+/// Parses a size such as `500K`, `1.5G`, `2TiB` or `2000` into bytes. Units
+/// are binary, as in yt-dlp options.
+pub fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    if size.is_empty() {
+        return None;
+    }
+
+    let size = size.trim_end_matches(['B', 'b']);
+    let size = match size.strip_suffix(['i', 'I']) {
+        Some(rest) if rest.ends_with(['k', 'K', 'm', 'M', 'g', 'G', 't', 'T']) => rest,
+        _ => size,
+    };
+    let (number, multiplier) = match size.chars().last()? {
+        'k' | 'K' => (&size[..size.len() - 1], 1024.0),
+        'm' | 'M' => (&size[..size.len() - 1], 1024.0 * 1024.0),
+        'g' | 'G' => (&size[..size.len() - 1], 1024.0 * 1024.0 * 1024.0),
+        't' | 'T' => (&size[..size.len() - 1], 1024.0 * 1024.0 * 1024.0 * 1024.0),
+        _ => (size, 1.0),
+    };
+
+    let value: f64 = number.trim().parse().ok()?;
+    (value > 0.0).then_some((value * multiplier) as u64)
+}
+
+/// Formats a byte count with binary units, e.g. `12.3 MiB`.
+pub fn format_bytes(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < BYTE_UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, BYTE_UNITS[0])
+    } else {
+        format!("{} {}", decimal(value, 1), BYTE_UNITS[unit])
+    }
+}
+
+/// Formats a transfer rate in bytes per second, e.g. `1.2 MiB/s`.
+pub fn format_speed(bytes_per_sec: f64) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec.max(0.0) as u64))
+}
+
+/// Formats a duration in seconds as `m:ss` or `h:mm:ss`.
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    let secs = seconds % 60;
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+/// Formats a 0.0..=1.0 fraction as a percentage, e.g. `42.5%`.
+pub fn format_percent(fraction: f32) -> String {
+    format!("{}%", decimal(f64::from(fraction) * 100.0, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_separator_follows_the_language() {
+        assert_eq!(decimal_separator_for("de_DE.UTF-8"), ',');
+        assert_eq!(decimal_separator_for("pt-BR"), ',');
+        assert_eq!(decimal_separator_for("en_US.UTF-8"), '.');
+        assert_eq!(decimal_separator_for("C"), '.');
+        assert_eq!(decimal_separator_for(""), '.');
+    }
+
+    #[test]
+    fn bytes_use_binary_units() {
+        let separator = locale().decimal_separator;
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), format!("1{}5 KiB", separator));
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), format!("5{}0 GiB", separator));
+        assert_eq!(format_speed(-3.0), "0 B/s");
+    }
+
+    #[test]
+    fn durations_gain_hours_when_needed() {
+        assert_eq!(format_duration(0), "0:00");
+        assert_eq!(format_duration(75), "1:15");
+        assert_eq!(format_duration(3600 + 62), "1:01:02");
+    }
+
+    #[test]
+    fn sizes_parse_with_binary_suffixes() {
+        assert_eq!(parse_size("500"), Some(500));
+        assert_eq!(parse_size(" 2K "), Some(2048));
+        assert_eq!(parse_size("1.5MB"), Some(1024 * 1024 * 3 / 2));
+        assert_eq!(parse_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("0"), None);
+        assert_eq!(parse_size("lots"), None);
+    }
+
+    #[test]
+    fn sizes_parse_terabytes_and_iec_suffixes() {
+        assert_eq!(parse_size("2T"), Some(2 << 40));
+        assert_eq!(parse_size("1.5 TiB"), Some(3 << 39));
+        assert_eq!(parse_size("2 GiB"), Some(2 << 30));
+        assert_eq!(parse_size("500kib"), Some(500 << 10));
+        assert_eq!(parse_size("2i"), None);
+    }
+}
@@ -0,0 +1,426 @@
+use std::fs;
+use std::path::Path;
+
+/// Finds a single web URL in `text`, e.g. what was copied to the clipboard.
+/// Text with anything besides the URL is ignored so copying a paragraph that
+/// happens to contain a link does not trigger an offer.
+pub fn detect(text: &str) -> Option<String> {
+    let candidate = text
+        .trim()
+        .trim_matches(|c| matches!(c, '<' | '>' | '"' | '\''));
+    if candidate.is_empty() || candidate.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let (scheme, rest) = candidate.split_once("://")?;
+    if !scheme.eq_ignore_ascii_case("http") && !scheme.eq_ignore_ascii_case("https") {
+        return None;
+    }
+
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if !host.contains('.') {
+        return None;
+    }
+
+    Some(normalize(candidate))
+}
+
+/// Query parameters that only track where a link was shared from. They are
+/// dropped from every URL.
+const TRACKING_PARAMS: [&str; 10] = [
+    "fbclid", "gclid", "dclid", "msclkid", "mc_cid", "mc_eid", "igshid", "igsh", "ref_src", "ref_url",
+];
+
+/// Share-tracking parameters specific to YouTube links. `t` is kept: it is
+/// the start time.
+const YOUTUBE_TRACKING_PARAMS: [&str; 4] = ["si", "feature", "pp", "ab_channel"];
+
+/// Canonical form used to compare and download URLs: lowercase scheme and
+/// host, no fragment, no trailing slash, tracking parameters removed and
+/// `youtu.be` short links expanded.
+pub fn normalize(url: &str) -> String {
+    let url = url.trim();
+    let url = url.split_once('#').map_or(url, |(before, _)| before);
+
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let host_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (host, rest) = rest.split_at(host_end);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let mut host = host.to_ascii_lowercase();
+    let mut path = path.trim_end_matches('/').to_string();
+    let mut params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (key.to_string(), value.to_string())
+        })
+        .collect();
+
+    // youtu.be/<id>?t=42 -> www.youtube.com/watch?v=<id>&t=42
+    if host == "youtu.be" && path.len() > 1 {
+        params.insert(0, ("v".to_string(), path[1..].to_string()));
+        host = "www.youtube.com".to_string();
+        path = "/watch".to_string();
+    }
+
+    let youtube = is_youtube(&host);
+    params.retain(|(key, _)| {
+        let key = key.to_ascii_lowercase();
+        let tracking = key.starts_with("utm_")
+            || TRACKING_PARAMS.contains(&key.as_str())
+            || (youtube && YOUTUBE_TRACKING_PARAMS.contains(&key.as_str()));
+        !tracking
+    });
+
+    let mut normalized = format!("{}://{}{}", scheme.to_ascii_lowercase(), host, path);
+    if !params.is_empty() {
+        let query: Vec<String> = params
+            .iter()
+            .map(|(key, value)| if value.is_empty() { key.clone() } else { format!("{}={}", key, value) })
+            .collect();
+        normalized.push('?');
+        normalized.push_str(&query.join("&"));
+    }
+    normalized
+}
+
+fn is_youtube(host: &str) -> bool {
+    host == "youtube.com" || host.ends_with(".youtube.com")
+}
+
+/// What a URL points at, as far as can be told without asking yt-dlp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrlKind {
+    Video,
+    Playlist,
+    /// A video opened from a playlist; yt-dlp downloads the whole playlist.
+    VideoInPlaylist,
+    Channel,
+    /// A site without specific rules here; yt-dlp decides.
+    Other,
+}
+
+impl std::fmt::Display for UrlKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlKind::Video => write!(f, "Single video"),
+            UrlKind::Playlist => write!(f, "Playlist"),
+            UrlKind::VideoInPlaylist => write!(f, "Video in a playlist (the whole playlist is downloaded)"),
+            UrlKind::Channel => write!(f, "Channel"),
+            UrlKind::Other => write!(f, "Link"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UrlInfo {
+    pub normalized: String,
+    pub host: String,
+    pub kind: UrlKind,
+}
+
+/// Validates and normalises what was typed into the URL field.
+pub fn analyze(input: &str) -> Result<UrlInfo, String> {
+    let input = input.trim();
+    let normalized = detect(input).ok_or_else(|| {
+        if input.contains("://") {
+            "Only http and https links can be downloaded".to_string()
+        } else {
+            "Not a valid URL, it should start with https://".to_string()
+        }
+    })?;
+
+    let rest = normalized.split_once("://").map_or("", |(_, rest)| rest);
+    let host_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (host, rest) = rest.split_at(host_end);
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let has_param = |name: &str| {
+        query
+            .split('&')
+            .any(|pair| pair.split('=').next() == Some(name))
+    };
+
+    let kind = if is_youtube(host) {
+        if path == "/playlist" {
+            UrlKind::Playlist
+        } else if path == "/watch" && has_param("list") {
+            UrlKind::VideoInPlaylist
+        } else if path == "/watch" || path.starts_with("/shorts/") || path.starts_with("/live/") {
+            UrlKind::Video
+        } else if path.starts_with("/@")
+            || path.starts_with("/channel/")
+            || path.starts_with("/c/")
+            || path.starts_with("/user/")
+        {
+            UrlKind::Channel
+        } else {
+            UrlKind::Other
+        }
+    } else if has_param("list") || path.contains("/playlist") || path.contains("/sets/") {
+        UrlKind::Playlist
+    } else {
+        UrlKind::Other
+    };
+
+    Ok(UrlInfo {
+        host: host.to_string(),
+        normalized,
+        kind,
+    })
+}
+
+/// The uploads tab of a YouTube channel URL. A bare channel lists only its
+/// tabs (Videos, Shorts, Live), which hold no videos themselves.
+pub fn channel_uploads(url: &str) -> String {
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_string();
+    };
+    let host_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (host, rest) = rest.split_at(host_end);
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path.trim_end_matches('/'), Some(query)),
+        None => (rest.trim_end_matches('/'), None),
+    };
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let channel_root = match segments.as_slice() {
+        [handle] => handle.starts_with('@'),
+        [prefix, _] => matches!(*prefix, "channel" | "c" | "user"),
+        _ => false,
+    };
+    if !is_youtube(&host.to_ascii_lowercase()) || !channel_root {
+        return url.to_string();
+    }
+
+    match query {
+        Some(query) => format!("{}://{}{}/videos?{}", scheme, host, path, query),
+        None => format!("{}://{}{}/videos", scheme, host, path),
+    }
+}
+
+/// Every web URL in free-form text, in order and without duplicates. Lines
+/// starting with `#` are comments, as in yt-dlp's `--batch-file`.
+pub fn extract_all(text: &str) -> Vec<String> {
+    let mut found: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        for word in line.split_whitespace() {
+            if let Some(url) = detect(word)
+                && !found.contains(&url)
+            {
+                found.push(url);
+            }
+        }
+    }
+    found
+}
+
+/// Reads the links out of a dropped file: Windows `.url` shortcuts, macOS
+/// `.webloc` files, freedesktop `.desktop` links, or any text file listing
+/// URLs.
+pub fn from_file(path: &Path) -> Result<Vec<String>, String> {
+    let content = fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let content = String::from_utf8_lossy(&content);
+
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    let urls = match extension.as_str() {
+        "url" | "desktop" => content
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("URL="))
+            .filter_map(detect)
+            .collect(),
+        "webloc" if content.starts_with("bplist") => {
+            return Err(format!("{} is a binary property list, which is not supported", path.display()));
+        }
+        "webloc" => webloc_url(&content).and_then(detect).into_iter().collect(),
+        _ => extract_all(&content),
+    };
+
+    if urls.is_empty() {
+        Err(format!("No URLs found in {}", path.display()))
+    } else {
+        Ok(urls)
+    }
+}
+
+/// The `<string>` following `<key>URL</key>` in an XML property list.
+fn webloc_url(plist: &str) -> Option<&str> {
+    let (_, after_key) = plist.split_once("<key>URL</key>")?;
+    let (_, after_tag) = after_key.split_once("<string>")?;
+    let (url, _) = after_tag.split_once("</string>")?;
+    Some(url.trim())
+}
+
+/// One non-empty, non-comment line of a batch list.
+#[derive(Debug, Clone)]
+pub struct BatchLine {
+    /// 1-based line number in the editor.
+    pub number: usize,
+    pub url: Result<String, String>,
+}
+
+/// Validates a batch list in the `--batch-file` format: one URL per line,
+/// blank lines and `#` comments ignored.
+pub fn parse_batch(text: &str) -> Vec<BatchLine> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| BatchLine {
+            number,
+            url: detect(line).ok_or_else(|| format!("Not a URL: {}", line)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_uploads_points_bare_channels_at_videos() {
+        assert_eq!(channel_uploads("https://www.youtube.com/@name"), "https://www.youtube.com/@name/videos");
+        assert_eq!(
+            channel_uploads("https://youtube.com/channel/UC123/"),
+            "https://youtube.com/channel/UC123/videos"
+        );
+        assert_eq!(channel_uploads("https://www.youtube.com/c/name?x=1"), "https://www.youtube.com/c/name/videos?x=1");
+    }
+
+    #[test]
+    fn channel_uploads_leaves_tabs_and_other_sites() {
+        for url in [
+            "https://www.youtube.com/@name/shorts",
+            "https://www.youtube.com/playlist?list=PL1",
+            "https://vimeo.com/@name",
+        ] {
+            assert_eq!(channel_uploads(url), url);
+        }
+    }
+
+    #[test]
+    fn detect_accepts_a_lone_web_url() {
+        assert_eq!(
+            detect("  <https://Example.com/Watch/#top>  ").as_deref(),
+            Some("https://example.com/Watch")
+        );
+        assert_eq!(detect("\"http://example.org\"").as_deref(), Some("http://example.org"));
+    }
+
+    #[test]
+    fn detect_ignores_text_around_links_and_other_schemes() {
+        for text in [
+            "",
+            "see https://example.com",
+            "ftp://example.com/file",
+            "https://localhost/video",
+            "example.com/watch?v=1",
+        ] {
+            assert_eq!(detect(text), None, "{text}");
+        }
+    }
+
+    fn dropped(name: &str, content: &str) -> Result<Vec<String>, String> {
+        let dir = std::env::temp_dir().join(format!("yt-dlgui-urls-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, content).unwrap();
+        let result = from_file(&path);
+        let _ = fs::remove_file(path);
+        result
+    }
+
+    #[test]
+    fn from_file_reads_link_files() {
+        assert_eq!(
+            dropped("Clip.url", "[InternetShortcut]\r\nURL=https://example.com/a\r\n"),
+            Ok(vec!["https://example.com/a".to_string()])
+        );
+        assert_eq!(
+            dropped("Clip.desktop", "[Desktop Entry]\nType=Link\nURL=https://example.com/b\n"),
+            Ok(vec!["https://example.com/b".to_string()])
+        );
+        let webloc = "<?xml version=\"1.0\"?>\n<plist><dict>\n<key>URL</key>\n<string>https://example.com/c</string>\n</dict></plist>";
+        assert_eq!(dropped("Clip.webloc", webloc), Ok(vec!["https://example.com/c".to_string()]));
+        assert!(dropped("Clip.webloc", "bplist00").unwrap_err().contains("binary property list"));
+    }
+
+    #[test]
+    fn from_file_lists_every_url_in_text() {
+        let text = "# saved links\nhttps://example.com/a and https://example.com/b\nhttps://example.com/a/\n";
+        assert_eq!(
+            dropped("links.txt", text),
+            Ok(vec!["https://example.com/a".to_string(), "https://example.com/b".to_string()])
+        );
+        assert!(dropped("empty.txt", "nothing here").unwrap_err().starts_with("No URLs found"));
+    }
+
+    #[test]
+    fn parse_batch_numbers_lines_and_skips_comments() {
+        let lines = parse_batch("# list\nhttps://example.com/a\n\n  example.com\nhttps://example.com/b#top\n");
+        let summary: Vec<(usize, Result<String, String>)> =
+            lines.into_iter().map(|line| (line.number, line.url)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, Ok("https://example.com/a".to_string())),
+                (4, Err("Not a URL: example.com".to_string())),
+                (5, Ok("https://example.com/b".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_strips_tracking_and_expands_short_links() {
+        assert_eq!(
+            normalize("HTTPS://Example.COM/path/?utm_source=x&id=3&fbclid=abc#frag"),
+            "https://example.com/path?id=3"
+        );
+        assert_eq!(
+            normalize("https://youtu.be/abc123?si=share&t=42"),
+            "https://www.youtube.com/watch?v=abc123&t=42"
+        );
+        assert_eq!(
+            normalize("https://m.youtube.com/watch?v=abc123&feature=shared&pp=x"),
+            "https://m.youtube.com/watch?v=abc123"
+        );
+        // `si` is only tracking on YouTube.
+        assert_eq!(normalize("https://example.com/watch?si=1"), "https://example.com/watch?si=1");
+    }
+
+    #[test]
+    fn analyze_classifies_urls() {
+        let kind = |url: &str| analyze(url).unwrap().kind;
+        assert_eq!(kind("https://www.youtube.com/watch?v=abc"), UrlKind::Video);
+        assert_eq!(kind("https://youtu.be/abc"), UrlKind::Video);
+        assert_eq!(kind("https://www.youtube.com/shorts/abc"), UrlKind::Video);
+        assert_eq!(kind("https://www.youtube.com/playlist?list=PL1"), UrlKind::Playlist);
+        assert_eq!(kind("https://www.youtube.com/watch?v=abc&list=PL1"), UrlKind::VideoInPlaylist);
+        assert_eq!(kind("https://www.youtube.com/@name"), UrlKind::Channel);
+        assert_eq!(kind("https://soundcloud.com/artist/sets/album"), UrlKind::Playlist);
+        assert_eq!(kind("https://vimeo.com/12345"), UrlKind::Other);
+
+        let info = analyze("  https://WWW.Example.com/a  ").unwrap();
+        assert_eq!(info.host, "www.example.com");
+        assert_eq!(info.normalized, "https://www.example.com/a");
+    }
+
+    #[test]
+    fn analyze_explains_rejected_input() {
+        assert_eq!(
+            analyze("ftp://example.com/file").unwrap_err(),
+            "Only http and https links can be downloaded"
+        );
+        assert_eq!(analyze("example.com").unwrap_err(), "Not a valid URL, it should start with https://");
+    }
+}
@@ -1,3 +1,7 @@
 pub mod modal;
+pub mod resize_edges;
+pub mod speed_graph;
 
 pub use modal::*;
+pub use resize_edges::*;
+pub use speed_graph::*;
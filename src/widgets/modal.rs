@@ -8,6 +8,7 @@ pub fn modal<'a>(
     base: impl Into<Element<'a, Message>>,
     modal_content: impl Into<Element<'a, Message>>,
     on_backdrop_click: Message,
+    radius: f32,
 ) -> Element<'a, Message> {
     let backdrop = mouse_area(
         container(Space::new())
@@ -20,7 +21,7 @@ pub fn modal<'a>(
     let modal_box = container(modal_content)
         .max_width(600)
         .max_height(700)
-        .style(modal_container_style);
+        .style(modal_container_style(radius));
 
     let modal_layer = container(modal_box)
         .width(Fill)
@@ -0,0 +1,39 @@
+use iced::mouse::Interaction;
+use iced::widget::{column, mouse_area, row, stack, Space};
+use iced::window::Direction;
+use iced::{Element, Fill, Length};
+
+use crate::message::Message;
+
+/// Width of the grab area along each edge of the undecorated window.
+const EDGE: f32 = 5.0;
+
+/// Lays thin hit-zones over the window's edges and corners that start a
+/// system resize, since the window has no decorations to grab.
+pub fn resize_edges<'a>(base: impl Into<Element<'a, Message>>) -> Element<'a, Message> {
+    let zone = |direction: Direction, width: Length, height: Length, interaction: Interaction| {
+        mouse_area(Space::new().width(width).height(height))
+            .on_press(Message::WindowResize(direction))
+            .interaction(interaction)
+    };
+
+    let edge = Length::Fixed(EDGE);
+    let top = row![
+        zone(Direction::NorthWest, edge, edge, Interaction::ResizingDiagonallyDown),
+        zone(Direction::North, Fill, edge, Interaction::ResizingVertically),
+        zone(Direction::NorthEast, edge, edge, Interaction::ResizingDiagonallyUp),
+    ];
+    let middle = row![
+        zone(Direction::West, edge, Fill, Interaction::ResizingHorizontally),
+        Space::new().width(Fill).height(Fill),
+        zone(Direction::East, edge, Fill, Interaction::ResizingHorizontally),
+    ]
+    .height(Fill);
+    let bottom = row![
+        zone(Direction::SouthWest, edge, edge, Interaction::ResizingDiagonallyUp),
+        zone(Direction::South, Fill, edge, Interaction::ResizingVertically),
+        zone(Direction::SouthEast, edge, edge, Interaction::ResizingDiagonallyDown),
+    ];
+
+    stack![base.into(), column![top, middle, bottom]].into()
+}
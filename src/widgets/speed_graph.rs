@@ -0,0 +1,78 @@
+use iced::mouse;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::{Fill, Point, Rectangle, Renderer, Theme};
+
+use crate::download::stats::{TransferHistory, GRAPH_WINDOW};
+use crate::message::Message;
+use crate::theme::graph_colors;
+
+/// Live throughput chart covering the last [`GRAPH_WINDOW`] of a download.
+pub struct SpeedGraph {
+    points: Vec<(f32, f64)>,
+}
+
+pub fn speed_graph(history: &TransferHistory) -> Canvas<SpeedGraph, Message> {
+    canvas::Canvas::new(SpeedGraph {
+        points: history.graph_points(),
+    })
+    .width(Fill)
+    .height(48)
+}
+
+impl canvas::Program<Message> for SpeedGraph {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        let size = frame.size();
+
+        let (background, fill, line_color) = graph_colors(theme);
+        frame.fill_rectangle(Point::ORIGIN, size, background);
+
+        let max_speed = self.points.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+        if self.points.len() >= 2 && max_speed > 0.0 {
+            let window = GRAPH_WINDOW.as_secs_f32();
+            let to_point = |(age, speed): (f32, f64)| {
+                Point::new(
+                    size.width * (1.0 - age / window),
+                    size.height * (1.0 - (speed / max_speed) as f32 * 0.9),
+                )
+            };
+
+            let line = Path::new(|builder| {
+                let mut points = self.points.iter().copied().map(to_point);
+                if let Some(first) = points.next() {
+                    builder.move_to(first);
+                    points.for_each(|p| builder.line_to(p));
+                }
+            });
+
+            let area = Path::new(|builder| {
+                let mut points = self.points.iter().copied().map(to_point).peekable();
+                if let Some(first) = points.peek().copied() {
+                    builder.move_to(Point::new(first.x, size.height));
+                    points.for_each(|p| builder.line_to(p));
+                    builder.line_to(Point::new(size.width, size.height));
+                    builder.close();
+                }
+            });
+
+            frame.fill(&area, fill);
+            frame.stroke(
+                &line,
+                Stroke::default()
+                    .with_color(line_color)
+                    .with_width(1.5),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+}